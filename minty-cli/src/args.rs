@@ -2,7 +2,8 @@ use clap::{
     builder::{EnumValueParser, StringValueParser},
     value_parser, ArgAction, Args, Parser, Subcommand, ValueEnum,
 };
-use std::path::PathBuf;
+use serde::{Deserialize, Deserializer};
+use std::{collections::BTreeMap, path::PathBuf};
 
 use crate::key_value::KeyValueParser;
 
@@ -47,12 +48,42 @@ pub(crate) struct Convert {
     /// Determines how errors should be written to stderr.
     #[arg(long, default_value = "default")]
     pub(crate) error_mode: ErrorMode,
+    /// The kind of output to produce.
+    #[arg(long, default_value = "html")]
+    pub(crate) emit: Emit,
+    /// Check the source for errors without writing any output.
+    #[arg(long)]
+    pub(crate) check: bool,
+    /// Read output options from the given JSON file.
+    /// Options given as arguments take precedence over those in the file.
+    ///
+    /// [default: `.mintyml.json` in the working directory, if it exists]
+    #[arg(long, value_name = "PATH")]
+    pub(crate) config: Option<PathBuf>,
+    /// After converting, keep running and reconvert each source file when it changes.
+    /// Files added after the initial conversion are not picked up. Press Ctrl-C to stop.
+    #[cfg(feature = "watch")]
+    #[arg(long, conflicts_with = "stdin")]
+    pub(crate) watch: bool,
+}
+
+#[derive(Debug, Default, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Emit {
+    /// Convert the source to HTML.
+    #[default]
+    Html,
+    /// Write the parsed document tree as JSON, with source ranges given as byte offsets.
+    /// No inference or other transformations are applied. Output files are given a `.json`
+    /// extension.
+    AstJson,
 }
 
 #[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq)]
 pub enum ErrorMode {
     /// stderr will contain human-readable errors.
     Default,
+    /// stderr will contain human-readable errors, each followed by the offending source line.
+    Snippet,
     /// stderr will contain a JSON stream of errors.
     Json,
     /// No errors will be written to stderr
@@ -86,18 +117,35 @@ pub(crate) struct ConvertSource {
     pub(crate) src_files: Option<Vec<PathBuf>>,
 }
 
-#[derive(Debug, Args)]
+#[derive(Debug, Default, Args, Deserialize)]
 #[command(next_help_heading = "Output Options")]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
 pub(crate) struct ConvertOptions {
     /// Produce XHTML5 instead of HTML
-    #[arg(short, long)]
-    pub(crate) xml: bool,
+    #[arg(
+        short, long, num_args = 0..=1, value_name = "ENABLE",
+        require_equals = true, action = ArgAction::Set,
+        default_missing_value = "true"
+    )]
+    pub(crate) xml: Option<bool>,
     /// Produce HTML with line breaks and indentation for readability.
-    #[arg(short, long)]
-    pub(crate) pretty: bool,
-    /// Number of spaces for each indentation level when `--pretty` is enabled.
-    #[arg(long, requires = "pretty", value_parser = value_parser!(u8).range(0..=16), default_value = "2")]
-    pub(crate) indent: u8,
+    #[arg(
+        short, long, num_args = 0..=1, value_name = "ENABLE",
+        require_equals = true, action = ArgAction::Set,
+        default_missing_value = "true"
+    )]
+    pub(crate) pretty: Option<bool>,
+    /// Number of spaces for each indentation level. Requires `--pretty`.
+    ///
+    /// [default: 2]
+    #[arg(long, value_parser = value_parser!(u8).range(0..=16))]
+    pub(crate) indent: Option<u8>,
+    /// Character used for indentation. Requires `--pretty`.
+    /// With `tabs`, each indentation level is a single tab and `--indent` is ignored.
+    ///
+    /// [default: spaces]
+    #[arg(long)]
+    pub(crate) indent_style: Option<IndentStyle>,
     /// Make a complete HTML page by wrapping the contents in `<html>` tags.
     ///
     /// * If the source document already has an `html` element at the top level, no changes will be made.
@@ -117,8 +165,12 @@ pub(crate) struct ConvertOptions {
     pub(crate) complete_page: Option<bool>,
     /// Convert a MinTyML fragment without wrapping it in `<html>` tags.
     /// Equivalent to `--complete-page=false`
-    #[arg(long, conflicts_with = "complete_page")]
-    pub(crate) fragment: bool,
+    #[arg(
+        long, num_args = 0..=1, value_name = "ENABLE",
+        require_equals = true, action = ArgAction::Set,
+        default_missing_value = "true", conflicts_with = "complete_page"
+    )]
+    pub(crate) fragment: Option<bool>,
     /// Override the element types used when converting special tags.
     ///
     /// This argument may be used multiple times to allow multiple overrides.
@@ -131,6 +183,7 @@ pub(crate) struct ConvertOptions {
         action = ArgAction::Append,
         value_delimiter = ','
     )]
+    #[serde(deserialize_with = "deserialize_special_tags")]
     pub(crate) special_tag: Vec<(SpecialTag, String)>,
     /// If enabled, a best-effort conversion will be attempted for files with errors.
     #[arg(
@@ -165,9 +218,89 @@ pub(crate) struct ConvertOptions {
         default_missing_value = "true", overrides_with = "metadata",
     )]
     pub(crate) metadata_elements: Option<bool>,
+    /// EXPERIMENTAL: When `--metadata` is enabled, whether to add the source range attributes
+    /// (`mty:start`, `mty:end`, `mty:content-start`, `mty:content-end`).
+    ///
+    /// [default: true]
+    #[arg(
+        long, num_args = 0..=1, value_name = "ENABLE",
+        require_equals = true, action = ArgAction::Set,
+        default_missing_value = "true",
+    )]
+    pub(crate) metadata_ranges: Option<bool>,
+    /// EXPERIMENTAL: When `--metadata` is enabled, whether to add the flag attributes
+    /// (`mty:verbatim`, `mty:raw`, `mty:multiline`).
+    ///
+    /// [default: true]
+    #[arg(
+        long, num_args = 0..=1, value_name = "ENABLE",
+        require_equals = true, action = ArgAction::Set,
+        default_missing_value = "true",
+    )]
+    pub(crate) metadata_flags: Option<bool>,
 }
 
-#[derive(Debug, Default, ValueEnum, Clone, Copy, PartialEq, Eq)]
+impl ConvertOptions {
+    /// Fills in each option that wasn't given as an argument from `file`.
+    pub(crate) fn merge_file(self, file: Self) -> Self {
+        let Self {
+            xml,
+            pretty,
+            indent,
+            indent_style,
+            complete_page,
+            fragment,
+            special_tag,
+            forgiving,
+            fail_fast,
+            metadata,
+            metadata_elements,
+            metadata_ranges,
+            metadata_flags,
+        } = file;
+
+        Self {
+            xml: self.xml.or(xml),
+            pretty: self.pretty.or(pretty),
+            indent: self.indent.or(indent),
+            indent_style: self.indent_style.or(indent_style),
+            complete_page: self
+                .complete_page
+                .or(self.fragment.map(|f| !f))
+                .or(complete_page)
+                .or(fragment.map(|f| !f)),
+            fragment: self.fragment.or(fragment),
+            // Later overrides win, so the arguments go last.
+            special_tag: special_tag.into_iter().chain(self.special_tag).collect(),
+            forgiving: self.forgiving.or(forgiving),
+            fail_fast: self.fail_fast.or(fail_fast),
+            metadata: self.metadata.or(metadata),
+            metadata_elements: self.metadata_elements.or(metadata_elements),
+            metadata_ranges: self.metadata_ranges.or(metadata_ranges),
+            metadata_flags: self.metadata_flags.or(metadata_flags),
+        }
+    }
+}
+
+/// Reads special tag overrides from a map like `{ "underline": "ins" }`.
+fn deserialize_special_tags<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<(SpecialTag, String)>, D::Error> {
+    BTreeMap::<SpecialTag, String>::deserialize(deserializer).map(|tags| tags.into_iter().collect())
+}
+
+#[derive(Debug, Default, ValueEnum, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum IndentStyle {
+    /// Indent each level with the number of spaces given by `--indent`.
+    #[default]
+    Spaces,
+    /// Indent each level with a tab.
+    Tabs,
+}
+
+#[derive(Debug, Default, ValueEnum, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub(crate) enum FailFast {
     /// Continue processing after first error found.
     #[default]
@@ -179,7 +312,8 @@ pub(crate) enum FailFast {
     File,
 }
 
-#[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub(crate) enum SpecialTag {
     #[value(help = "<# strong #> (default: 'strong')")]
     Strong,
@@ -1,7 +1,8 @@
 use core::fmt;
+#[cfg(feature = "watch")]
+use std::sync::{Mutex, PoisonError};
 use std::{
     fmt::Write as _,
-    iter,
     path::{Path, PathBuf},
 };
 
@@ -10,7 +11,7 @@ use mintyml::MetadataConfig;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 use crate::{
-    args::{self, FailFast},
+    args::{self, Emit, FailFast},
     error_reporter::{ErrorCategory, OwnedStreamName},
     utils::{default, ArcPath, PathExt, UtilExt},
     AppCx, CxType, IoHelper, Result,
@@ -21,6 +22,16 @@ struct ConvertCx<'cx, Cx: CxType> {
     args: super::args::Convert,
     dot_path: ArcPath,
     empty_path: ArcPath,
+    /// Records each converted source file when `--watch` is enabled.
+    #[cfg(feature = "watch")]
+    watched: Option<Mutex<Vec<WatchedFile>>>,
+}
+
+#[cfg(feature = "watch")]
+struct WatchedFile {
+    source: ArcPath,
+    dest: OwnedStreamName,
+    success: bool,
 }
 
 impl<'cx, Cx: CxType> ConvertCx<'cx, Cx> {
@@ -44,7 +55,12 @@ impl<'cx, Cx: CxType> ConvertCx<'cx, Cx> {
         let mut config_buf = None;
         let config = config.unwrap_or_else(|| config_buf.insert(self.args.options.as_config()));
 
-        let (out, error) = if self.args.options.forgiving.unwrap_or(false) {
+        let (out, error) = if self.args.emit == Emit::AstJson {
+            match mintyml::tree::parse(&src) {
+                Ok(document) => (Some(serde_json::to_string(&document)?), None),
+                Err(error) => (None, Some(error)),
+            }
+        } else if self.args.options.forgiving.unwrap_or(false) {
             match mintyml::convert_forgiving(&src, config) {
                 Ok(out) => (Some(out), None),
                 Err((out, error)) => (out, Some(error)),
@@ -58,13 +74,25 @@ impl<'cx, Cx: CxType> ConvertCx<'cx, Cx> {
 
         let success = error.is_none() && out.is_some();
 
+        #[cfg(feature = "watch")]
+        if let (Some(watched), OwnedStreamName::File(source)) = (&self.watched, &source_name) {
+            watched
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(WatchedFile {
+                    source: source.clone(),
+                    dest: dest_name.clone(),
+                    success,
+                });
+        }
+
         if let Some(error) = error {
             self.cx
                 .reporter
                 .conversion_error(source_name.clone(), error);
         }
 
-        if let Some(out) = out {
+        if let Some(out) = out.filter(|_| !self.args.check) {
             self.cx
                 .io
                 .write(&dest_name, out.as_str())
@@ -81,9 +109,47 @@ impl<'cx, Cx: CxType> ConvertCx<'cx, Cx> {
         let src = self.conversion_src_type()?;
         let dest = self.conversion_dest_type()?;
 
+        #[cfg(feature = "watch")]
+        if self.args.watch {
+            self.watched = Some(default());
+            self.convert_src_to_dest(src, dest)?;
+            return self.watch();
+        }
+
         self.convert_src_to_dest(src, dest)
     }
 
+    /// Reconverts each source file recorded by the initial conversion whenever it changes, until
+    /// the watcher stops. Returns whether the latest conversion of every file succeeded.
+    #[cfg(feature = "watch")]
+    fn watch(&mut self) -> Result<bool> {
+        use crate::watch::Watcher;
+
+        let mut files = self
+            .watched
+            .take()
+            .map(|w| w.into_inner().unwrap_or_else(PoisonError::into_inner))
+            .unwrap_or_default();
+        let paths: Vec<_> = files.iter().map(|f| f.source.clone()).collect();
+        let config = self.args.options.as_config();
+        let mut watcher = self.cx.cx_type.watcher(&self.cx.io)?;
+
+        while let Some(changed) = watcher.next_changes(&paths)? {
+            for file in files.iter_mut().filter(|f| changed.contains(&f.source)) {
+                let source = OwnedStreamName::File(file.source.clone());
+                file.success = match self.convert(source, file.dest.clone(), Some(&config)) {
+                    Ok(success) => success,
+                    Err(e) => {
+                        self.cx.reporter.other_error(e);
+                        false
+                    }
+                };
+            }
+        }
+
+        Ok(files.iter().all(|f| f.success))
+    }
+
     fn search_dir_inner(
         &self,
         base: &ArcPath,
@@ -199,7 +265,7 @@ impl<'cx, Cx: CxType> ConvertCx<'cx, Cx> {
                     dest_buf.push(&src.relative);
                 }
 
-                change_extension(&mut dest_buf, &self.args.options);
+                change_extension(&mut dest_buf, &self.args.options, self.args.emit);
                 let dest_file: ArcPath = dest_buf.into();
                 let src_file = src.joined();
 
@@ -212,7 +278,7 @@ impl<'cx, Cx: CxType> ConvertCx<'cx, Cx> {
             .try_reduce(|| true, |lhs, rhs| Ok(lhs & rhs))
     }
 
-    fn convert_src_to_dest(mut self, src: SourceType, dest: DestinationType) -> Result<bool> {
+    fn convert_src_to_dest(&mut self, src: SourceType, dest: DestinationType) -> Result<bool> {
         match (src, dest) {
             (
                 SourceType::File(src, PathType::ProbablyDir | PathType::Dir { .. }),
@@ -442,14 +508,20 @@ enum DestinationType {
 
 impl args::ConvertOptions {
     pub(crate) fn as_config(&self) -> mintyml::OutputConfig {
-        mintyml::OutputConfig::new()
-            .xml(self.xml)
-            .complete_page(self.complete_page.unwrap_or(!self.fragment))
-            .update(|cfg| {
-                if self.pretty {
-                    cfg.indent = Some(iter::repeat(' ').take(self.indent as usize).collect());
-                }
-            })
+        let config = mintyml::OutputConfig::new()
+            .xml(self.xml.unwrap_or(false))
+            .complete_page(
+                self.complete_page
+                    .unwrap_or(!self.fragment.unwrap_or(false)),
+            );
+
+        let config = match self.indent_style.unwrap_or_default() {
+            _ if self.pretty != Some(true) => config,
+            args::IndentStyle::Spaces => config.indent_spaces(self.indent.unwrap_or(2).into()),
+            args::IndentStyle::Tabs => config.indent_tabs(),
+        };
+
+        config
             .update(|config| {
                 for (key, value) in &self.special_tag {
                     let target = match key {
@@ -479,6 +551,8 @@ impl args::ConvertOptions {
                     } => {
                         config.metadata = MetadataConfig::new()
                             .elements(self.metadata_elements.unwrap_or(false))
+                            .ranges(self.metadata_ranges.unwrap_or(true))
+                            .flags(self.metadata_flags.unwrap_or(true))
                             .wrap_some();
                     }
                     _ => {}
@@ -487,26 +561,63 @@ impl args::ConvertOptions {
             })
             .update(|config| config.fail_fast = self.fail_fast.map(|f| f != FailFast::False))
     }
+
+    /// Checks for options that only apply alongside another one, once any config file has been
+    /// merged in.
+    fn validate(&self) -> Result {
+        if self.pretty != Some(true) && (self.indent.is_some() || self.indent_style.is_some()) {
+            return Err(
+                anyhow!("'--indent' and '--indent-style' require '--pretty'")
+                    .context(ErrorCategory::Argument),
+            );
+        }
+        Ok(())
+    }
 }
 
 impl super::args::Convert {
-    pub(crate) fn execute(self, cx: &AppCx<impl CxType>) -> Result<bool> {
+    /// Reads the options from `--config` or the default config file, if any, using them for each
+    /// option not given as an argument.
+    fn load_config_file(&mut self, cx: &AppCx<impl CxType>) -> Result {
+        let path = match self.config.take() {
+            Some(path) => path,
+            None if cx.io.is_file(DEFAULT_CONFIG_FILE.as_ref())? => DEFAULT_CONFIG_FILE.into(),
+            None => return Ok(()),
+        };
+
+        let file = try_with_context!(cx.io.read(path.as_path()), path.display().to_string());
+        let file = serde_json::from_str(&file)
+            .with_context(|| format!("Invalid config file '{}'", path.display()))
+            .context(ErrorCategory::Argument)?;
+
+        self.options = std::mem::take(&mut self.options).merge_file(file);
+        Ok(())
+    }
+
+    pub(crate) fn execute(mut self, cx: &AppCx<impl CxType>) -> Result<bool> {
         cx.reporter.set_mode(self.error_mode);
+        self.load_config_file(cx)?;
+        self.options.validate()?;
 
         ConvertCx {
             cx,
             args: self,
             dot_path: ".".into(),
             empty_path: "".into(),
+            #[cfg(feature = "watch")]
+            watched: None,
         }
         .execute()
     }
 }
 
+const DEFAULT_CONFIG_FILE: &str = ".mintyml.json";
+
 pub(crate) fn output_name(
     orig: &Path,
     out_dir: &Path,
     options: &args::ConvertOptions,
+    emit: Emit,
 ) -> Result<PathBuf> {
     let mut path = out_dir.join(if orig.is_absolute() {
         orig.file_name()
@@ -516,16 +627,19 @@ pub(crate) fn output_name(
         orig
     });
 
-    change_extension(&mut path, options);
+    change_extension(&mut path, options, emit);
     path.wrap_ok()
 }
 
-pub(crate) fn change_extension(path: &mut PathBuf, options: &args::ConvertOptions) {
+pub(crate) fn change_extension(path: &mut PathBuf, options: &args::ConvertOptions, emit: Emit) {
     if has_minty_extension(&path) {
         path.set_extension("");
     }
-    path.as_mut_os_string()
-        .push(if options.xml { ".xhtml" } else { ".html" });
+    path.as_mut_os_string().push(match emit {
+        Emit::AstJson => ".json",
+        Emit::Html if options.xml == Some(true) => ".xhtml",
+        Emit::Html => ".html",
+    });
 }
 
 pub(crate) fn has_minty_extension<P: AsRef<Path>>(path: P) -> bool {
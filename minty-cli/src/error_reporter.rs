@@ -9,7 +9,7 @@ use std::{
     thread,
 };
 
-use mintyml::error::{DisplayWithSrcOptions, LocationRange};
+use mintyml::error::{DisplayWithSrcOptions, LineIndex, LocationRange};
 use serde::{ser::Serializer, Serialize};
 
 use crate::{
@@ -31,6 +31,8 @@ struct ErrorEntry<'lt, M, E> {
     #[serde(serialize_with = "serialize_display_iter")]
     expected: E,
     message: Option<SerializeDisplay<M>>,
+    #[serde(skip)]
+    snippet: Option<(&'lt str, LocationRange)>,
 }
 
 fn should_skip_iter(it: &impl Iterator) -> bool {
@@ -52,13 +54,20 @@ where
 struct ErrorLocation {
     start: usize,
     end: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<usize>,
 }
 
 impl ErrorLocation {
-    fn new(range: LocationRange) -> Self {
+    fn new(range: LocationRange, src: Option<(&str, &LineIndex)>) -> Self {
+        let line_column = src.map(|(src, index)| index.line_column(src, range.start.position));
         Self {
             start: range.start.position,
             end: range.end.position,
+            line: line_column.map(|lc| lc.line),
+            column: line_column.map(|lc| lc.column),
         }
     }
 }
@@ -159,6 +168,10 @@ impl ErrorEventData {
         let mut display_with_src_options = DisplayWithSrcOptions::default();
         display_with_src_options.show_location = false;
 
+        // Index the source once for all of its errors.
+        let line_index = src.as_deref().map(|s| LineIndex::new(s));
+        let indexed_src = src.as_deref().map(String::as_str).zip(line_index.as_ref());
+
         for error in syntax_errors {
             let source_name = source_name.as_ref().map(|s| s.as_ref());
             let expected = match error.kind {
@@ -174,11 +187,12 @@ impl ErrorEventData {
                 ErrorEntry {
                     category: ErrorCategory::Syntax,
                     source_name,
-                    location: ErrorLocation::new(error.range).into(),
+                    location: ErrorLocation::new(error.range, indexed_src).into(),
                     expected,
                     message: src.as_ref().map(|s| {
                         SerializeDisplay(error.display_with_src(s, &display_with_src_options))
                     }),
+                    snippet: src.as_deref().map(|s| (s.as_str(), error.range)),
                 },
             )?;
         }
@@ -190,11 +204,12 @@ impl ErrorEventData {
                 ErrorEntry {
                     category: ErrorCategory::Semantic,
                     source_name,
-                    location: ErrorLocation::new(error.range).into(),
+                    location: ErrorLocation::new(error.range, indexed_src).into(),
                     expected: std::iter::empty::<&str>(),
                     message: src.as_ref().map(|s| {
                         SerializeDisplay(error.display_with_src(s, &display_with_src_options))
                     }),
+                    snippet: src.as_deref().map(|s| (s.as_str(), error.range)),
                 },
             )?;
         }
@@ -215,6 +230,7 @@ impl ErrorEventData {
                     location: None,
                     expected: std::iter::empty::<&str>(),
                     message: Some(SerializeDisplay(error.root_cause())),
+                    snippet: None,
                 },
             )?;
         }
@@ -267,7 +283,7 @@ where
     }
 
     match error_mode {
-        ErrorMode::Default => {
+        ErrorMode::Default | ErrorMode::Snippet => {
             match entry.category {
                 ErrorCategory::Argument => (),
                 category => write!(out, "{category} error: ")?,
@@ -281,12 +297,20 @@ where
                 }
                 _ => (),
             }
-            if let Some(ErrorLocation { start, end }) = entry.location {
-                write!(out, "at position {start}")?;
-                if end >= start + 1 {
-                    write!(out, "..<{end}")?;
+            match entry.location {
+                Some(ErrorLocation {
+                    line: Some(line),
+                    column: Some(column),
+                    ..
+                }) => write!(out, "at line {line}, column {column}: ")?,
+                Some(ErrorLocation { start, end, .. }) => {
+                    write!(out, "at position {start}")?;
+                    if end >= start + 1 {
+                        write!(out, "..<{end}")?;
+                    }
+                    write!(out, ": ")?;
                 }
-                write!(out, ": ")?;
+                None => (),
             }
             if let Some(SerializeDisplay(ref message)) = entry.message {
                 write!(out, "{message}")?;
@@ -294,6 +318,10 @@ where
                 write!(out, "unknown error")?;
             }
             write!(out, "\n")?;
+
+            if let (ErrorMode::Snippet, Some((src, range))) = (error_mode, entry.snippet) {
+                write_snippet(out, src, range)?;
+            }
         }
         ErrorMode::Json => {
             serde_json::to_writer(&mut *out, &entry)?;
@@ -304,6 +332,40 @@ where
     Ok(())
 }
 
+/// Writes the source line containing the start of `range`, with the range underlined:
+///
+/// ```text
+///   |
+/// 3 |   bar <(baz
+///   |       ^^
+/// ```
+///
+/// Only the first line of a multi-line range is shown, followed by `...`.
+fn write_snippet(out: &mut impl Write, src: &str, range: LocationRange) -> anyhow::Result<()> {
+    let start = range.start.position.min(src.len());
+    let end = range.end.position.clamp(start, src.len());
+
+    let line_start = src[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = src[start..].find('\n').map_or(src.len(), |i| start + i);
+    let line = src[line_start..line_end].trim_end_matches('\r');
+    let line_number = src[..line_start].matches('\n').count() + 1;
+
+    // Keep tabs so the underline stays aligned with the source line.
+    let padding: String = src[line_start..start]
+        .chars()
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let underline_end = end.min(line_start + line.len()).max(start);
+    let underline = "^".repeat(src[start..underline_end].chars().count().max(1));
+    let continued = if end > line_end { "..." } else { "" };
+
+    let gutter = line_number.to_string().len();
+    writeln!(out, "{:gutter$} |", "")?;
+    writeln!(out, "{line_number} | {line}")?;
+    writeln!(out, "{:gutter$} | {padding}{underline}{continued}", "")?;
+    Ok(())
+}
+
 impl ErrorReporter {
     pub fn initialize(io: impl IoHelper + 'static) -> Self {
         let (sender, receiver) = mpsc::channel::<ErrorEvent>();
@@ -358,12 +420,13 @@ impl ErrorReporter {
 
     pub fn conversion_error(&self, source_name: StreamName, error: mintyml::error::ConvertError) {
         let (src, syntax_errors, semantic_errors) = match error {
-            mintyml::ConvertError::Syntax { syntax_errors, src } => {
-                (Some(src), syntax_errors, default())
-            }
+            mintyml::ConvertError::Syntax {
+                syntax_errors, src, ..
+            } => (Some(src), syntax_errors, default()),
             mintyml::ConvertError::Semantic {
                 semantic_errors,
                 src,
+                ..
             } => (Some(src), default(), semantic_errors),
             _ => (None, default(), default()),
         };
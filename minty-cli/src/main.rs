@@ -17,6 +17,8 @@ mod io_helper;
 mod key_value;
 #[cfg(test)]
 mod tests;
+#[cfg(feature = "watch")]
+mod watch;
 
 use std::process::ExitCode;
 
@@ -37,23 +39,55 @@ impl args::Cli {
 
 trait CxType: Send + Sync {
     type Io: IoHelper + 'static;
+    #[cfg(feature = "watch")]
+    type Watcher: watch::Watcher;
+
+    #[cfg(feature = "watch")]
+    fn watcher(&self, io: &Self::Io) -> Result<Self::Watcher>;
 }
 struct DefaultCx;
 
 impl CxType for DefaultCx {
     type Io = io_helper::DefaultIoHelper;
+    #[cfg(feature = "watch")]
+    type Watcher = watch::PollWatcher;
+
+    #[cfg(feature = "watch")]
+    fn watcher(&self, _io: &Self::Io) -> Result<Self::Watcher> {
+        use std::sync::atomic::Ordering;
+
+        let watcher = watch::PollWatcher::default();
+        let stop = watcher.stop_flag();
+        ctrlc::set_handler(move || stop.store(true, Ordering::Relaxed))?;
+        Ok(watcher)
+    }
 }
 
 #[cfg(test)]
-struct TestCx;
+#[derive(Default)]
+struct TestCx {
+    /// Batches of `(path, contents)` edits replayed by `--watch`.
+    #[cfg(feature = "watch")]
+    watch_edits: Vec<Vec<(std::path::PathBuf, String)>>,
+}
 
 #[cfg(test)]
 impl CxType for TestCx {
     type Io = io_helper::test_helper::TestIoHelper;
+    #[cfg(feature = "watch")]
+    type Watcher = watch::test_helper::TestWatcher;
+
+    #[cfg(feature = "watch")]
+    fn watcher(&self, io: &Self::Io) -> Result<Self::Watcher> {
+        Ok(watch::test_helper::TestWatcher {
+            io: io.clone(),
+            edits: self.watch_edits.clone().into_iter(),
+        })
+    }
 }
 
 struct AppCx<Cx: CxType> {
-    #[allow(unused)]
+    #[cfg_attr(not(feature = "watch"), allow(unused))]
     pub cx_type: Cx,
     pub io: Cx::Io,
     pub reporter: ErrorReporter,
@@ -83,6 +117,7 @@ fn test_main<'arg>(
     input: impl Into<Option<&'arg str>>,
     root: impl Into<Option<io_helper::test_helper::TestDir>>,
     cwd: impl Into<Option<&'arg str>>,
+    watch_edits: Vec<Vec<(std::path::PathBuf, String)>>,
 ) -> TestOutput {
     use utils::UtilExt;
     let args = args.as_ref();
@@ -93,7 +128,16 @@ fn test_main<'arg>(
         cwd.into().unwrap_or("/").into(),
         input.into().unwrap_or(""),
     );
-    let mut cx = AppCx::new(TestCx, io);
+    #[cfg(not(feature = "watch"))]
+    assert!(
+        watch_edits.is_empty(),
+        "`--watch` requires the `watch` feature"
+    );
+    let cx_type = TestCx {
+        #[cfg(feature = "watch")]
+        watch_edits,
+    };
+    let mut cx = AppCx::new(cx_type, io);
     let args = shlex::split(args).unwrap_or_else(|| panic!("Invalid arguments {args:?}"));
     let outcome = args::Cli::try_parse_from(args)
         .err_into()
@@ -36,12 +36,24 @@ macro_rules! test_main_inner {
         $([files = $root:expr])?
         $([cwd = $cwd:expr])?
         $([assert_empty_stderr = $assert_empty_stderr:expr])?
+        $([watch = $watch:expr])?
     ) => {
         match test_main(
             $($args)?,
             or_default!($([$input])? [None]),
             or_default!($([crate::io_helper::test_helper::TestDir::from_file_list($root)])? [None]),
             or_default!($([$cwd])? [None]),
+            or_default!($([
+                $watch
+                    .into_iter()
+                    .map(|edits| {
+                        edits
+                            .into_iter()
+                            .map(|(path, value): (&str, &str)| (path.into(), value.into()))
+                            .collect()
+                    })
+                    .collect()
+            ])? [Vec::new()]),
         ) { actual => {
             if or_default!($([$assert_empty_stderr])? [true]) {
                 assert_eq!(&actual.stderr, "")
@@ -287,3 +299,221 @@ fn convert_dir_implicit_dest_recurse() {
         ])
         .unwrap()
 }
+
+#[test]
+fn check_writes_nothing() {
+    let actual = test_main!(
+        "--dir c --check",
+        files = [
+            ("/a/b/c/good.mty", Some(BASIC_SRC)),
+            ("/a/b/c/bad.mty", Some("section {\n  foo <(bar\n}\n")),
+        ],
+        cwd = "/a/b",
+        assert_empty_stderr = false,
+    );
+
+    assert!(!actual.outcome.unwrap());
+    assert!(actual.stderr.contains("bad.mty"), "{}", actual.stderr);
+    assert!(!actual.stderr.contains("good.mty"), "{}", actual.stderr);
+    actual
+        .root
+        .compare_file_list([
+            ("/a/b/c/good.mty", contains(BASIC_SRC)),
+            ("/a/b/c/bad.mty", any()),
+        ])
+        .unwrap()
+}
+
+#[cfg(feature = "watch")]
+#[test]
+fn watch_reconverts_changed_files() {
+    let actual = test_main!(
+        "--dir c --watch",
+        files = [
+            ("/a/b/c/foo.mty", Some(BASIC_SRC)),
+            ("/a/b/c/bar.mty", Some(BASIC_SRC2)),
+        ],
+        cwd = "/a/b",
+        assert_empty_stderr = false,
+        watch = [
+            vec![("c/foo.mty", BASIC_SRC2)],
+            vec![("c/bar.mty", "section {\n  foo <(bar\n}\n")],
+        ],
+    );
+
+    assert!(!actual.outcome.unwrap());
+    assert!(actual.stderr.contains("bar.mty"), "{}", actual.stderr);
+    assert!(!actual.stderr.contains("foo.mty"), "{}", actual.stderr);
+    actual
+        .root
+        .compare_file_list([
+            ("/a/b/c/foo.mty", any()),
+            ("/a/b/c/bar.mty", any()),
+            ("/a/b/c/foo.html", contains(BASIC_OUT2)),
+            ("/a/b/c/bar.html", contains(BASIC_OUT2)),
+        ])
+        .unwrap()
+}
+
+#[cfg(feature = "watch")]
+#[test]
+fn poll_watcher_stops() {
+    use std::{sync::atomic::Ordering, thread, time::Duration};
+
+    use crate::watch::{PollWatcher, Watcher};
+
+    let mut watcher = PollWatcher::new(Duration::from_millis(1));
+    let stop = watcher.stop_flag();
+    let handle = thread::spawn(move || watcher.next_changes(&[]).unwrap());
+
+    stop.store(true, Ordering::Relaxed);
+    assert!(handle.join().unwrap().is_none());
+}
+
+#[test]
+fn error_mode_snippet() {
+    let actual = test_main!(
+        "--stdin --stdout --error-mode snippet",
+        stdin = "section {\n  foo\n  bar <(baz\n}\n",
+        assert_empty_stderr = false,
+    );
+
+    assert_eq!(
+        actual.stderr,
+        concat!(
+            "Syntax error: <stdin>: at line 3, column 7: Unclosed delimiter \"<(\"\n",
+            "  |\n",
+            "3 |   bar <(baz\n",
+            "  |       ^^\n",
+        )
+    );
+}
+
+#[test]
+fn pretty_indent_tabs() {
+    let actual = test_main!(
+        "--stdin --stdout --pretty --indent-style tabs",
+        stdin = "section {\n  div> Hello\n}\n",
+    );
+
+    assert!(actual.outcome.unwrap());
+    assert_eq!(actual.stdout, "<section>\n\t<div>Hello</div>\n</section>\n");
+}
+
+#[test]
+fn config_file_options() {
+    let config = r#"{ "pretty": true, "indent": 4, "special-tag": { "strong": "b" } }"#;
+    let src = "section {\n  div> <#Hello#>\n}\n";
+
+    let actual = test_main!(
+        "--stdin --stdout",
+        stdin = src,
+        files = [("/a/.mintyml.json", Some(config))],
+        cwd = "/a",
+    );
+    assert!(actual.outcome.unwrap());
+    assert_eq!(
+        actual.stdout,
+        "<section>\n    <div><b>Hello</b></div>\n</section>\n"
+    );
+
+    let actual = test_main!(
+        "--stdin --stdout --config cfg/minty.json --indent-style tabs --special-tag strong=i",
+        stdin = src,
+        files = [("/a/cfg/minty.json", Some(config))],
+        cwd = "/a",
+    );
+    assert!(actual.outcome.unwrap());
+    assert_eq!(
+        actual.stdout,
+        "<section>\n\t<div><i>Hello</i></div>\n</section>\n"
+    );
+
+    let actual = test_main!(
+        "--stdin --stdout",
+        stdin = src,
+        files = [("/a/.mintyml.json", Some(r#"{ "prety": true }"#))],
+        cwd = "/a",
+        assert_empty_stderr = false,
+    );
+    let error = actual.outcome.unwrap_err();
+    assert!(
+        format!("{error:#}").contains("unknown field `prety`"),
+        "{error:#}"
+    );
+}
+
+#[test]
+fn config_file_flags_overridden() {
+    let config = r#"{ "pretty": true, "xml": true }"#;
+    let src = "section {\n  input>\n}\n";
+
+    let actual = test_main!(
+        "--stdin --stdout",
+        stdin = src,
+        files = [("/a/.mintyml.json", Some(config))],
+        cwd = "/a",
+    );
+    assert!(actual.outcome.unwrap());
+    assert_eq!(actual.stdout, "<section>\n  <input/>\n</section>\n");
+
+    let actual = test_main!(
+        "--stdin --stdout --pretty=false --xml=false",
+        stdin = src,
+        files = [("/a/.mintyml.json", Some(config))],
+        cwd = "/a",
+    );
+    assert!(actual.outcome.unwrap());
+    assert_eq!(actual.stdout, "<section><input></section>");
+}
+
+#[test]
+fn indent_requires_pretty() {
+    let actual = test_main!(
+        "--stdin --stdout --indent 4",
+        stdin = "div> Hello",
+        assert_empty_stderr = false,
+    );
+    let error = actual.outcome.unwrap_err();
+    assert!(
+        format!("{error:#}").contains("require '--pretty'"),
+        "{error:#}"
+    );
+}
+
+#[test]
+fn emit_ast_json() {
+    let actual = test_main!("--stdin --stdout --emit ast-json", stdin = "div.a> Hi");
+
+    assert!(actual.outcome.unwrap());
+    let json: serde_json::Value = serde_json::from_str(&actual.stdout).unwrap();
+    let element = &json["nodes"][0];
+
+    assert_eq!(element["type"], "element");
+    assert_eq!(element["kind"]["type"], "standard");
+    assert_eq!(
+        element["range"],
+        serde_json::json!({ "start": 0, "end": 9 })
+    );
+    assert_eq!(element["selectors"][0]["tag"], "div");
+    assert_eq!(element["selectors"][0]["items"][0]["type"], "class");
+    assert_eq!(element["selectors"][0]["items"][0]["value"], "a");
+}
+
+#[test]
+fn emit_ast_json_dir() {
+    let actual = test_main!(
+        "--dir c --emit ast-json",
+        files = [("/a/b/c/foo.mty", Some(BASIC_SRC))],
+        cwd = "/a/b"
+    );
+
+    assert!(actual.outcome.unwrap());
+    actual
+        .root
+        .compare_file_list([
+            ("/a/b/c/foo.mty", contains(BASIC_SRC)),
+            ("/a/b/c/foo.json", any()),
+        ])
+        .unwrap()
+}
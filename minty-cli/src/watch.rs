@@ -0,0 +1,119 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+    time::SystemTime,
+};
+
+use crate::{utils::ArcPath, Result};
+
+/// A source of file change events for `--watch`.
+pub(crate) trait Watcher {
+    /// Blocks until at least one of `paths` changes and returns the changed paths,
+    /// or `None` if watching should stop.
+    fn next_changes(&mut self, paths: &[ArcPath]) -> Result<Option<Vec<ArcPath>>>;
+}
+
+/// Watches files by periodically polling their modification times.
+pub(crate) struct PollWatcher {
+    interval: Duration,
+    modified: BTreeMap<ArcPath, Option<SystemTime>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl PollWatcher {
+    pub(crate) fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            modified: BTreeMap::new(),
+            stop: Default::default(),
+        }
+    }
+
+    /// A flag that makes the watcher stop at its next poll once set, like on Ctrl-C.
+    pub(crate) fn stop_flag(&self) -> Arc<AtomicBool> {
+        self.stop.clone()
+    }
+
+    fn modified(path: &ArcPath) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|md| md.modified()).ok()
+    }
+}
+
+impl Default for PollWatcher {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(250))
+    }
+}
+
+impl Watcher for PollWatcher {
+    fn next_changes(&mut self, paths: &[ArcPath]) -> Result<Option<Vec<ArcPath>>> {
+        for path in paths {
+            if !self.modified.contains_key(path) {
+                self.modified.insert(path.clone(), Self::modified(path));
+            }
+        }
+
+        loop {
+            thread::sleep(self.interval);
+
+            if self.stop.load(Ordering::Relaxed) {
+                return Ok(None);
+            }
+
+            let changed: Vec<_> = paths
+                .iter()
+                .filter(|path| {
+                    let modified = Self::modified(path);
+                    self.modified.insert((*path).clone(), modified) != Some(modified)
+                })
+                .cloned()
+                .collect();
+
+            if !changed.is_empty() {
+                return Ok(Some(changed));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_helper {
+    use std::{path::PathBuf, vec};
+
+    use super::Watcher;
+    use crate::{
+        io_helper::{test_helper::TestIoHelper, IoHelper},
+        utils::ArcPath,
+        Result,
+    };
+
+    /// Replays a fixed list of edits, writing the new contents of each file before reporting it
+    /// as changed. Stops watching once the edits run out.
+    pub(crate) struct TestWatcher {
+        pub(crate) io: TestIoHelper,
+        pub(crate) edits: vec::IntoIter<Vec<(PathBuf, String)>>,
+    }
+
+    impl Watcher for TestWatcher {
+        fn next_changes(&mut self, _paths: &[ArcPath]) -> Result<Option<Vec<ArcPath>>> {
+            let Some(edits) = self.edits.next() else {
+                return Ok(None);
+            };
+
+            edits
+                .into_iter()
+                .map(|(path, value)| {
+                    self.io.write(path.as_path(), &value)?;
+                    Ok(path.into())
+                })
+                .collect::<Result<_>>()
+                .map(Some)
+        }
+    }
+}
@@ -54,4 +54,44 @@ describe(MintymlConverter, () => {
 
         expect(actual).toEqual(expected)
     })
+    test('completePage and xml', async () => {
+        const target = new MintymlConverter({
+            xml: true,
+            completePage: true,
+        })
+
+        const actual = await target.convert('title> Foo\ninput>\n')
+
+        expect(actual).toEqual('<html><head><title>Foo</title></head> <body><input/></body></html>')
+    })
+    test('every option', async () => {
+        const target = new MintymlConverter({
+            xml: true,
+            indent: 2,
+            completePage: true,
+            specialTags: { strong: 'b' },
+            metadata: { elements: true },
+            fail_fast: true,
+        })
+
+        const expected = `\
+<html xmlns:mty="tag:youngspe.github.io,2024:mintyml/metadata" mty:content-start="0" mty:content-end="23">
+<head>
+  <title mty:start="0" mty:end="10" mty:content-start="6" mty:content-end="10"><mty:text mty:raw="true" mty:start="7" mty:end="10">Foo</mty:text></title>
+</head>
+<body>
+  <p mty:start="12" mty:end="22" mty:content-start="14" mty:content-end="22"><b mty:start="15" mty:end="22" mty:content-start="17" mty:content-end="20"><mty:text mty:raw="true" mty:start="17" mty:end="20">bar</mty:text></b></p>
+</body>
+</html>
+`
+
+        const actual = await target.convert('title> Foo\n\np> <#bar#>\n')
+
+        expect(actual).toEqual(expected)
+
+        const result = await target.convertForgiving('p> <(b> a\n')
+
+        expect(result.success).toBe(false)
+        expect(result.output).toBeNull()
+    })
 })
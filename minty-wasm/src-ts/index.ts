@@ -145,15 +145,14 @@ export class MintymlConverter implements MintymlConverterOptions {
      */
     async convertForgiving(src: string): Promise<MintymlConversionResult> {
         const mintyml = await _mintyml
-        const result = mintyml.convert(
-            src,
-            this.xml ?? undefined,
-            this.indent ?? -1,
-            this.completePage ?? undefined,
-            this.specialTags,
-            this.metadata,
-            this.fail_fast ?? undefined,
-        )
+        const result = mintyml.convertWithOptions(src, {
+            xml: this.xml,
+            indent: this.indent,
+            completePage: this.completePage,
+            specialTags: this.specialTags,
+            metadata: this.metadata,
+            failFast: this.fail_fast,
+        })
         if (result.error) {
             const outError = result.error as MintymlError
 
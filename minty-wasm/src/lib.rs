@@ -5,16 +5,48 @@ extern crate wasm_bindgen;
 
 use alloc::{format, string::String};
 use js_sys::{JsString, Reflect};
-use mintyml::{error::LocationRange, ConvertError, MetadataConfig, OutputConfig};
+use mintyml::{
+    error::{LineIndex, LocationRange, SyntaxErrorKind, UnclosedDelimiterKind},
+    ConvertError, MetadataConfig, OutputConfig,
+};
 use wasm_bindgen::prelude::*;
 
+/// A stable name for the kind of a syntax error, so consumers don't need to parse the message.
+fn syntax_error_kind_name(kind: &SyntaxErrorKind) -> &'static str {
+    match kind {
+        SyntaxErrorKind::ParseFailed { .. } => "parseFailed",
+        SyntaxErrorKind::InvalidEscape { .. } => "invalidEscape",
+        SyntaxErrorKind::Unclosed { .. } => "unclosed",
+        SyntaxErrorKind::MisplacedItem { .. } => "misplaced",
+        SyntaxErrorKind::InvalidItem { .. } => "invalidItem",
+        SyntaxErrorKind::MismatchedDelimiter { .. } => "mismatchedDelimiter",
+        _ => "unknown",
+    }
+}
+
+fn delimiter_name(delimiter: &UnclosedDelimiterKind) -> &'static str {
+    match delimiter {
+        UnclosedDelimiterKind::Block { .. } => "block",
+        UnclosedDelimiterKind::Inline { .. } => "inline",
+        UnclosedDelimiterKind::SpecialInline { .. } => "specialInline",
+        UnclosedDelimiterKind::Comment { .. } => "comment",
+        UnclosedDelimiterKind::RawComment { .. } => "rawComment",
+        UnclosedDelimiterKind::AttributeList { .. } => "attributeList",
+        _ => "unknown",
+    }
+}
+
 fn to_js_error(e: ConvertError) -> JsValue {
     struct Context {
         message_key: JsString,
         actual_key: JsString,
         start_key: JsString,
         end_key: JsString,
+        line_key: JsString,
+        column_key: JsString,
         expected_key: JsString,
+        kind_key: JsString,
+        delimiter_key: JsString,
     }
 
     let cx = Context {
@@ -22,7 +54,11 @@ fn to_js_error(e: ConvertError) -> JsValue {
         actual_key: JsString::from("actual"),
         start_key: JsString::from("start"),
         end_key: JsString::from("end"),
+        line_key: JsString::from("line"),
+        column_key: JsString::from("column"),
         expected_key: JsString::from("expected"),
+        kind_key: JsString::from("kind"),
+        delimiter_key: JsString::from("delimiter"),
     };
 
     let error = js_sys::Object::new();
@@ -31,15 +67,23 @@ fn to_js_error(e: ConvertError) -> JsValue {
         obj: &js_sys::Object,
         range: LocationRange,
         message: impl core::fmt::Display,
+        src: &str,
+        line_index: &LineIndex,
         cx: &Context,
     ) {
+        let line_column = line_index.line_column(src, range.start.position);
         let _ = Reflect::set(obj, &cx.start_key, &range.start.position.into());
         let _ = Reflect::set(obj, &cx.end_key, &range.end.position.into());
+        let _ = Reflect::set(obj, &cx.line_key, &line_column.line.into());
+        let _ = Reflect::set(obj, &cx.column_key, &line_column.column.into());
         let _ = Reflect::set(obj, &cx.message_key, &format!("{message}").into());
     }
 
     match e {
-        ConvertError::Syntax { syntax_errors, src } => {
+        ConvertError::Syntax {
+            syntax_errors, src, ..
+        } => {
+            let line_index = LineIndex::new(&src);
             let errors = syntax_errors
                 .into_iter()
                 .map(|e| {
@@ -49,11 +93,23 @@ fn to_js_error(e: ConvertError) -> JsValue {
                         &obj,
                         range,
                         e.display_with_src(&src, &Default::default()),
+                        &src,
+                        &line_index,
                         &cx,
                     );
 
+                    let _ =
+                        Reflect::set(&obj, &cx.kind_key, &syntax_error_kind_name(&e.kind).into());
+
                     match e.kind {
-                        mintyml::SyntaxErrorKind::ParseFailed { expected, .. } => {
+                        SyntaxErrorKind::Unclosed { ref delimiter, .. } => {
+                            let _ = Reflect::set(
+                                &obj,
+                                &cx.delimiter_key,
+                                &delimiter_name(delimiter).into(),
+                            );
+                        }
+                        SyntaxErrorKind::ParseFailed { expected, .. } => {
                             if range != LocationRange::INVALID {
                                 let _ = Reflect::set(
                                     &obj,
@@ -86,7 +142,9 @@ fn to_js_error(e: ConvertError) -> JsValue {
         ConvertError::Semantic {
             semantic_errors,
             src,
+            ..
         } => {
+            let line_index = LineIndex::new(&src);
             let errors = semantic_errors
                 .into_iter()
                 .map(|e| {
@@ -96,6 +154,8 @@ fn to_js_error(e: ConvertError) -> JsValue {
                         &obj,
                         range,
                         e.display_with_src(&src, &Default::default()),
+                        &src,
+                        &line_index,
                         &cx,
                     );
                     obj
@@ -125,34 +185,31 @@ fn get_special_tags(config: &mut OutputConfig, special_tags: JsValue) -> Result<
     Ok(())
 }
 
-pub fn convert_inner(
-    src: &str,
-    xml: Option<bool>,
-    indent: i32,
-    complete_page: Option<bool>,
-    special_tags: JsValue,
-    metadata: JsValue,
-    fail_fast: Option<bool>,
-) -> Result<String, (Option<String>, JsValue)> {
-    let mut config = OutputConfig::new();
-
-    config.xml = xml;
-    config.complete_page = complete_page;
-    config.fail_fast = fail_fast;
-
-    get_special_tags(&mut config, special_tags).map_err(|e| (None, e))?;
-
+/// Reads the `metadata` option, which is either a boolean or an object of metadata flags.
+fn get_metadata(config: &mut OutputConfig, metadata: JsValue) -> Result<(), JsValue> {
     if metadata.is_truthy() {
         let mut metadata_config = MetadataConfig::new();
         if metadata.is_object() {
-            metadata_config.elements = Reflect::get(&metadata, &"elements".into())
-                .map_err(|e| (None, e))?
-                .is_truthy();
+            metadata_config.elements =
+                Some(Reflect::get(&metadata, &"elements".into())?.is_truthy());
+
+            for (key, target) in [
+                ("ranges", &mut metadata_config.ranges),
+                ("flags", &mut metadata_config.flags),
+            ] {
+                let value = Reflect::get(&metadata, &key.into())?;
+                if !value.is_undefined() {
+                    *target = Some(value.is_truthy());
+                }
+            }
         }
         config.metadata = metadata_config.into();
     }
+    Ok(())
+}
 
-    if indent >= 0 {
+fn indent_config(config: &mut OutputConfig, indent: f64) {
+    if indent >= 0. {
         config.indent = Some(
             core::iter::repeat(' ')
                 .take(indent as usize)
@@ -160,9 +217,55 @@ pub fn convert_inner(
                 .into(),
         )
     }
+}
+
+/// Builds an [`OutputConfig`] from a JS options object. Missing, `null`, and `undefined` options
+/// keep their defaults.
+fn config_from_options(options: &JsValue) -> Result<OutputConfig<'static>, JsValue> {
+    let mut config = OutputConfig::new();
+    if !options.is_object() {
+        return Ok(config);
+    }
+
+    let field = |key: &str| Reflect::get(options, &key.into());
+
+    config.xml = field("xml")?.as_bool();
+    config.complete_page = field("completePage")?.as_bool();
+    config.fail_fast = field("failFast")?.as_bool();
+    if let Some(indent) = field("indent")?.as_f64() {
+        indent_config(&mut config, indent);
+    }
+    get_special_tags(&mut config, field("specialTags")?)?;
+    get_metadata(&mut config, field("metadata")?)?;
+
+    Ok(config)
+}
+
+pub fn convert_inner(src: &str, config: OutputConfig) -> Result<String, (Option<String>, JsValue)> {
     mintyml::convert_forgiving(src, config).map_err(|(out, e)| (out, to_js_error(e)))
 }
 
+/// Converts `src` to HTML with the given options object, returning `{ success, output, error }`.
+#[wasm_bindgen(js_name = convertWithOptions)]
+pub fn convert_with_options(src: &str, options: JsValue) -> Result<JsValue, JsValue> {
+    let (success, out, err) = match config_from_options(&options)
+        .map_err(|e| (None, e))
+        .and_then(|config| convert_inner(src, config))
+    {
+        Ok(out) => (true, Some(out), None),
+        Err((out, e)) => (false, out, Some(e)),
+    };
+
+    let mut obj = js_sys::Object::new();
+    Reflect::set(&mut obj, &"success".into(), &success.into())?;
+    Reflect::set(&mut obj, &"output".into(), &out.into())?;
+    Reflect::set(&mut obj, &"error".into(), &err.into())?;
+    Ok(obj.into())
+}
+
+/// Converts `src` to HTML with positional options.
+///
+/// @deprecated Use `convertWithOptions` instead.
 #[wasm_bindgen]
 pub fn convert(
     src: &str,
@@ -173,22 +276,12 @@ pub fn convert(
     metadata: JsValue,
     fail_fast: Option<bool>,
 ) -> Result<JsValue, JsValue> {
-    let (success, out, err) = match convert_inner(
-        src,
-        xml,
-        indent,
-        complete_page,
-        special_tags,
-        metadata,
-        fail_fast,
-    ) {
-        Ok(out) => (true, Some(out), None),
-        Err((out, e)) => (false, out, Some(e)),
-    };
-
-    let mut obj = js_sys::Object::new();
-    Reflect::set(&mut obj, &"success".into(), &success.into())?;
-    Reflect::set(&mut obj, &"output".into(), &out.into())?;
-    Reflect::set(&mut obj, &"error".into(), &err.into())?;
-    Ok(obj.into())
+    let options = js_sys::Object::new();
+    Reflect::set(&options, &"xml".into(), &xml.into())?;
+    Reflect::set(&options, &"indent".into(), &indent.into())?;
+    Reflect::set(&options, &"completePage".into(), &complete_page.into())?;
+    Reflect::set(&options, &"specialTags".into(), &special_tags)?;
+    Reflect::set(&options, &"metadata".into(), &metadata)?;
+    Reflect::set(&options, &"failFast".into(), &fail_fast.into())?;
+    convert_with_options(src, options.into())
 }
@@ -0,0 +1,52 @@
+//! Compares the time to convert a large document again after a single-character edit with
+//! `convert` and with `convert_with_cache`.
+//!
+//! Run with `cargo bench -p mintyml --bench cache`.
+
+use std::time::{Duration, Instant};
+
+use mintyml::{ConvertCache, OutputConfig};
+
+const ITERATIONS: u32 = 5;
+
+fn large_document() -> String {
+    let mut src = String::new();
+    for i in 0..1_000 {
+        src.push_str(&format!(
+            "section#s{i} {{\n    h2> Section {i}\n\n    Some <#bold#> text and a <(a[href=\"#s{i}\"]> link )>.\n}}\n\n"
+        ));
+    }
+    src
+}
+
+/// Returns the average time taken by `f` over `ITERATIONS` runs.
+fn average(mut f: impl FnMut(u32)) -> Duration {
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        f(i);
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let src = large_document();
+    let config = OutputConfig::new();
+
+    // Alternate between two versions of the document that differ by one character in the middle.
+    let edit_at = src.find("Section 500").unwrap();
+    let mut edited = src.clone();
+    edited.replace_range(edit_at..edit_at + 1, "s");
+    let versions = [&src, &edited];
+
+    let elapsed = average(|i| {
+        mintyml::convert(versions[i as usize % 2], &config).unwrap();
+    });
+    println!("convert ({} bytes): {elapsed:?}", src.len());
+
+    let mut cache = ConvertCache::new();
+    mintyml::convert_with_cache(&src, &config, &mut cache).unwrap();
+    let elapsed = average(|i| {
+        mintyml::convert_with_cache(versions[(i as usize + 1) % 2], &config, &mut cache).unwrap();
+    });
+    println!("convert_with_cache ({} bytes): {elapsed:?}", src.len());
+}
@@ -0,0 +1,41 @@
+//! Measures conversion of a large, text-heavy paragraph.
+//!
+//! Run with `cargo bench -p mintyml --bench escape`.
+
+use std::{hint::black_box, time::Instant};
+
+use mintyml::OutputConfig;
+
+const ITERATIONS: u32 = 200;
+
+fn large_paragraph() -> String {
+    let sentence = "The quick brown fox jumps over the lazy dog and keeps on running. ";
+    let mut src = String::from("section {\n");
+    for _ in 0..2_000 {
+        src.push_str("    ");
+        src.push_str(sentence);
+        src.push('\n');
+    }
+    src.push_str("}\n");
+    src
+}
+
+fn main() {
+    let src = large_paragraph();
+    let config = OutputConfig::new();
+
+    // Warm up
+    black_box(mintyml::convert(&src, &config).unwrap());
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(mintyml::convert(black_box(&src), &config).unwrap());
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "convert large plain-text paragraph ({} bytes): {:?}/iter",
+        src.len(),
+        elapsed / ITERATIONS,
+    );
+}
@@ -0,0 +1,86 @@
+//! Compares the peak memory use of `convert_to` and `convert_streaming` on a large document.
+//!
+//! Run with `cargo bench -p mintyml --bench streaming`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt,
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use mintyml::OutputConfig;
+
+/// Tracks the current and peak number of bytes allocated.
+struct Counting;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOC: Counting = Counting;
+
+/// Discards its output so only the converter's own allocations are measured.
+struct Sink;
+
+impl fmt::Write for Sink {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        black_box(s);
+        Ok(())
+    }
+}
+
+fn large_document() -> String {
+    let mut src = String::new();
+    for i in 0..1_000 {
+        src.push_str(&format!(
+            "section#s{i} {{\n    h2> Section {i}\n\n    Some <#bold#> text and a <(a[href=\"#s{i}\"]> link )>.\n}}\n\n"
+        ));
+    }
+    src
+}
+
+fn measure(name: &str, src: &str, f: impl FnOnce(&str, &mut Sink)) {
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+
+    let start = Instant::now();
+    f(src, &mut Sink);
+    let elapsed = start.elapsed();
+
+    println!(
+        "{name} ({} bytes): {:?}, peak {} bytes",
+        src.len(),
+        elapsed,
+        PEAK.load(Ordering::Relaxed) - baseline,
+    );
+}
+
+fn main() {
+    let src = large_document();
+    let config = OutputConfig::new();
+
+    measure("convert_to", &src, |src, out| {
+        mintyml::convert_to(src, &config, out).unwrap()
+    });
+    measure("convert_streaming", &src, |src, out| {
+        mintyml::convert_streaming(src, &config, out).unwrap()
+    });
+}
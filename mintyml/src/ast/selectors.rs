@@ -26,14 +26,16 @@ gramma::define_string_pattern!(
     fn selector_chain() {
         repeat(
             1..,
-            (!char("\\[]{}<> \n\r\t") & !whitespace()) + !precedes(char('>'))
-                | char((alphanumeric(), "*"))
-                | escape(),
+            !precedes(char('!') + post_selector_chain())
+                + ((!char("\\[]{}<> \n\r\t") & !whitespace()) + !precedes(char('>'))
+                    | char((alphanumeric(), "*"))
+                    | escape()),
         )
         .simple()
     }
     pub fn post_selector_chain() {
-        char(" \t").repeat(..).simple()
+        char('!').optional()
+            + char(" \t").repeat(..).simple()
             + (char('>') | char('{') + !precedes(!(whitespace() | char('}'))))
             | char('[')
     }
@@ -66,6 +68,7 @@ gramma::define_rule!(
         pub start: Location,
         pub first: SelectorStart,
         pub segments: Vec<SelectorSegment>,
+        pub raw: Option<Bang>,
         pub end: Location,
     }
 
@@ -168,6 +171,20 @@ mod test {
         parse_as_vec_before::<Selector, RightAngle>("a.b#c[x].b>");
     }
 
+    #[test]
+    fn raw_selector() {
+        let selector = gramma::parse_tree::<(Selector, RightAngle), 1>("a.b[x]!>")
+            .unwrap()
+            .0;
+        assert!(selector.raw.is_some());
+
+        let selector = gramma::parse_tree::<(Selector, LeftBrace), 1>("a.b!{")
+            .unwrap()
+            .0;
+        assert!(selector.raw.is_some());
+        assert!(selector.segments.is_empty());
+    }
+
     /// Test that in invalid selector parses correctly so we can report it as an error later.
     #[test]
     fn invalid_selector() {
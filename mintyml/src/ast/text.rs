@@ -11,6 +11,8 @@ gramma::define_string_pattern!(
             1..,
             (!char("\\[]{}<> \n\r\t") & !whitespace()) + !precedes(char('>'))
                 | alphanumeric()
+                // A `\` at the end of a line doesn't escape the line break.
+                | (char('\\') + !precedes(!char('\n')))
                 | escape(),
         )
         .simple()
@@ -120,6 +122,12 @@ gramma::define_token!(
     })]
     pub struct CommentText;
 
+    #[pattern(matcher = {
+        exactly("<!!") + char(..).repeat(..).lazy() + exactly("!!>")
+    })]
+    /// A comment written to the output as-is, like `<!![if IE]> ... <![endif]!!>`.
+    pub struct RawComment;
+
     #[pattern(exact = "<!")]
     pub struct OpenComment;
     #[pattern(exact = "!>")]
@@ -130,6 +138,7 @@ gramma::define_rule!(
     pub enum InlineText {
         Segment { value: TextSegment },
         Verbatim { value: Verbatim },
+        RawComment { comment: RawComment },
         Comment { comment: Comment },
         Interpolation { interpolation: Interpolation },
     }
@@ -39,6 +39,8 @@ gramma::define_token!(
     pub struct Hash;
     #[pattern(exact = "=")]
     pub struct Equals;
+    #[pattern(exact = "!")]
+    pub struct Bang;
 
     #[pattern(matcher = {
         (whitespace() & !char('\n')).repeat(..).simple()
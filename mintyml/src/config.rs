@@ -1,34 +1,160 @@
-use crate::Src;
+use alloc::{borrow::Cow, collections::BTreeMap, string::String, sync::Arc, vec::Vec};
+use core::fmt;
+
+use crate::{
+    document::SpecialKind,
+    error::Diagnostic,
+    inference::engine::{DynInfer, Infer},
+    ElementRef, Src,
+};
+
+/// The tag used for a kind of special element, or whether its syntax is disabled.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum SpecialTag<'src> {
+    /// The special element is converted to an element with this tag.
+    Tag(Src<'src>),
+    /// The special syntax is not recognized, so its delimiters are written as literal text.
+    ///
+    /// For code blocks, the disabled tag is left out, so disabling `code_block_container`
+    /// writes code blocks in a `code` element alone.
+    Disabled,
+}
+
+impl<'src> From<Src<'src>> for SpecialTag<'src> {
+    fn from(tag: Src<'src>) -> Self {
+        Self::Tag(tag)
+    }
+}
+
+impl<'src> From<&'src str> for SpecialTag<'src> {
+    fn from(tag: &'src str) -> Self {
+        Self::Tag(tag.into())
+    }
+}
+
+impl<'src> From<&'src String> for SpecialTag<'src> {
+    fn from(tag: &'src String) -> Self {
+        Self::Tag(tag.into())
+    }
+}
+
+impl From<String> for SpecialTag<'_> {
+    fn from(tag: String) -> Self {
+        Self::Tag(tag.into())
+    }
+}
 
 /// Defines overrides for the element types (or _tags_) inferred from special
 /// inline elements and code blocks.
 #[non_exhaustive]
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, rename_all = "camelCase")
+)]
 pub struct SpecialTagConfig<'src> {
     /// Tag name for `</ ... />`. Defaults to `em`.
-    pub emphasis: Option<Src<'src>>,
+    pub emphasis: Option<SpecialTag<'src>>,
     /// Tag name for `<# ... #>`. Defaults to `strong`.
-    pub strong: Option<Src<'src>>,
+    pub strong: Option<SpecialTag<'src>>,
     /// Tag name for `<_ ... _>`. Defaults to `u`.
-    pub underline: Option<Src<'src>>,
+    pub underline: Option<SpecialTag<'src>>,
     /// Tag name for `<~ ... ~>`. Defaults to `s`.
-    pub strike: Option<Src<'src>>,
+    pub strike: Option<SpecialTag<'src>>,
     /// Tag name for `<" ... ">`. Defaults to `q`.
-    pub quote: Option<Src<'src>>,
+    pub quote: Option<SpecialTag<'src>>,
     /// Tag name for ``<` ... `>``. Defaults to `code`.
-    pub code: Option<Src<'src>>,
+    pub code: Option<SpecialTag<'src>>,
     /// Code blocks (denoted with ```` ``` ````) will use the same tag as inline code,
     /// wrapped in the tag provided by this field. Defaults to `pre`.
-    pub code_block_container: Option<Src<'src>>,
+    pub code_block_container: Option<SpecialTag<'src>>,
+}
+
+impl<'src> SpecialTagConfig<'src> {
+    /// The override for the tag of `kind`, or `None` to use the default.
+    pub fn get(&self, kind: SpecialKind) -> Option<&SpecialTag<'src>> {
+        self.field(kind).as_ref()
+    }
+
+    /// Sets the override for the tag of `kind`.
+    pub fn set(&mut self, kind: SpecialKind, tag: impl Into<SpecialTag<'src>>) {
+        *self.field_mut(kind) = Some(tag.into());
+    }
+
+    /// Combines this config with `overrides`, using the tag from `overrides` for each kind it
+    /// sets.
+    pub fn merge(self, overrides: Self) -> Self {
+        let Self {
+            emphasis,
+            strong,
+            underline,
+            strike,
+            quote,
+            code,
+            code_block_container,
+        } = overrides;
+
+        Self {
+            emphasis: emphasis.or(self.emphasis),
+            strong: strong.or(self.strong),
+            underline: underline.or(self.underline),
+            strike: strike.or(self.strike),
+            quote: quote.or(self.quote),
+            code: code.or(self.code),
+            code_block_container: code_block_container.or(self.code_block_container),
+        }
+    }
+
+    fn field(&self, kind: SpecialKind) -> &Option<SpecialTag<'src>> {
+        match kind {
+            SpecialKind::Emphasis => &self.emphasis,
+            SpecialKind::Strong => &self.strong,
+            SpecialKind::Underline => &self.underline,
+            SpecialKind::Strike => &self.strike,
+            SpecialKind::Quote => &self.quote,
+            SpecialKind::Code => &self.code,
+            SpecialKind::CodeBlockContainer => &self.code_block_container,
+        }
+    }
+
+    fn field_mut(&mut self, kind: SpecialKind) -> &mut Option<SpecialTag<'src>> {
+        match kind {
+            SpecialKind::Emphasis => &mut self.emphasis,
+            SpecialKind::Strong => &mut self.strong,
+            SpecialKind::Underline => &mut self.underline,
+            SpecialKind::Strike => &mut self.strike,
+            SpecialKind::Quote => &mut self.quote,
+            SpecialKind::Code => &mut self.code,
+            SpecialKind::CodeBlockContainer => &mut self.code_block_container,
+        }
+    }
 }
 
 /// Configuration options for document parsing metadata.
 #[non_exhaustive]
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, rename_all = "camelCase")
+)]
 pub struct MetadataConfig {
     /// Generate elements for nodes that don't correspond directly to HTML elements,
-    /// like comments and text segments.
-    pub elements: bool,
+    /// like comments and text segments. Defaults to `false`.
+    pub elements: Option<bool>,
+    /// Emit the positional attributes `mty:start`, `mty:end`, `mty:content-start`,
+    /// and `mty:content-end`. Defaults to `true`.
+    pub ranges: Option<bool>,
+    /// Emit the flag attributes `mty:verbatim`, `mty:raw`, and `mty:multiline`.
+    /// Defaults to `true`.
+    pub flags: Option<bool>,
 }
 
 impl MetadataConfig {
@@ -36,19 +162,361 @@ impl MetadataConfig {
         Self::default()
     }
 
+    /// Combines this config with `overrides`, using the value from `overrides` for each option
+    /// it sets.
+    pub fn merge(self, overrides: Self) -> Self {
+        let Self {
+            elements,
+            ranges,
+            flags,
+        } = overrides;
+
+        Self {
+            elements: elements.or(self.elements),
+            ranges: ranges.or(self.ranges),
+            flags: flags.or(self.flags),
+        }
+    }
+
     /// Generate elements for nodes that don't correspond directly to HTML elements,
     /// like comments and text segments.
     pub fn elements(self, enable: bool) -> Self {
         Self {
-            elements: enable,
+            elements: Some(enable),
+            ..self
+        }
+    }
+
+    /// Emit the positional attributes `mty:start`, `mty:end`, `mty:content-start`,
+    /// and `mty:content-end`.
+    pub fn ranges(self, enable: bool) -> Self {
+        Self {
+            ranges: Some(enable),
+            ..self
+        }
+    }
+
+    /// Emit the flag attributes `mty:verbatim`, `mty:raw`, and `mty:multiline`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::{MetadataConfig, OutputConfig};
+    /// let out = mintyml::convert(
+    ///     "pre> 'x'",
+    ///     OutputConfig::new().metadata(MetadataConfig::new().flags(false).ranges(false)),
+    /// ).unwrap();
+    ///
+    /// assert!(!out.contains("mty:raw"));
+    /// assert!(!out.contains("mty:start"));
+    /// ```
+    pub fn flags(self, enable: bool) -> Self {
+        Self {
+            flags: Some(enable),
             ..self
         }
     }
 }
 
+/// The line break inserted between lines of output.
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum LineEnding {
+    /// `"\n"`
+    #[default]
+    Lf,
+    /// `"\r\n"`
+    CrLf,
+}
+
+impl LineEnding {
+    /// The string value of this line ending.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+/// The form of the `<meta>` element that declares the document's character encoding.
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum CharsetMetaStyle {
+    /// `<meta charset="utf-8">`
+    #[default]
+    Short,
+    /// `<meta http-equiv="Content-Type" content="text/html; charset=utf-8">`
+    HttpEquiv,
+}
+
+/// How the case of tag names is written to the output.
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum TagCase {
+    /// Tag names are written exactly as they appear in the source.
+    #[default]
+    Preserve,
+    /// Tag names are converted to ASCII lowercase.
+    Lower,
+}
+
+/// Whether a space is written between two adjacent inline special elements, like `<#a#>` and
+/// `</b/>`.
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum SpacesPolicy {
+    /// A space is written only if there is one in the source.
+    #[default]
+    Preserve,
+    /// A space is always written, even if there is none in the source.
+    Always,
+    /// A space is never written, even if there is one in the source.
+    Never,
+}
+
+/// Which elements receive the `lang` and `dir` attributes.
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum LangScope {
+    /// Each top-level element.
+    #[default]
+    TopLevel,
+    /// Only the root element, like the `html` element of a complete page. If the document
+    /// doesn't have a single top-level element, its content is wrapped in a `div`.
+    RootOnly,
+}
+
+/// Which of the elements created for an element that inference gives more than one tag receives
+/// its classes and ID.
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum ClassPlacement {
+    /// The outermost element, as in `<li class="x"><a>...</a></li>`.
+    #[default]
+    Outermost,
+    /// The innermost element, as in `<li><a class="x">...</a></li>`.
+    Innermost,
+}
+
+/// Which elements without content, classes, IDs, or attributes are left out of the output.
+/// Void elements like `br` are always kept.
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum DropEmptyPolicy {
+    /// Keep all empty elements.
+    #[default]
+    Never,
+    /// Drop empty elements whose tags were all inferred, like `>` on its own line.
+    Inferred,
+    /// Drop all empty elements, including ones with explicit tags like `div>`.
+    Always,
+}
+
+/// The text direction assigned to the `dir` attribute of each top-level element.
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum Direction {
+    /// `dir="ltr"`
+    Ltr,
+    /// `dir="rtl"`
+    Rtl,
+    /// `dir="rtl"` if [`OutputConfig::lang`] is a known right-to-left language,
+    /// otherwise no `dir` attribute.
+    #[default]
+    Auto,
+}
+
+impl Direction {
+    /// The value of the `dir` attribute for this direction given the document language `lang`,
+    /// or `None` if no attribute should be added.
+    pub(crate) fn value(self, lang: Option<&str>) -> Option<&'static str> {
+        match self {
+            Direction::Ltr => Some("ltr"),
+            Direction::Rtl => Some("rtl"),
+            Direction::Auto => lang.filter(|l| is_rtl_lang(l)).map(|_| "rtl"),
+        }
+    }
+}
+
+/// Whether the primary subtag of the language tag `lang` is a language usually written
+/// right-to-left.
+fn is_rtl_lang(lang: &str) -> bool {
+    const RTL_LANGS: &[&str] = &[
+        "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ks", "nqo", "ps", "sd", "syr", "ug", "ur",
+        "yi",
+    ];
+    let primary = lang.split(['-', '_']).next().unwrap_or_default();
+    RTL_LANGS.iter().any(|l| l.eq_ignore_ascii_case(primary))
+}
+
+/// A callback that receives each [`Diagnostic`] as it is found.
+/// See [`OutputConfig::on_diagnostic`].
+#[derive(Clone)]
+pub struct DiagnosticHandler(Arc<dyn Fn(&Diagnostic) + Send + Sync>);
+
+impl DiagnosticHandler {
+    pub fn new(f: impl Fn(&Diagnostic) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    pub(crate) fn call(&self, diagnostic: &Diagnostic) {
+        (self.0)(diagnostic)
+    }
+}
+
+impl fmt::Debug for DiagnosticHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DiagnosticHandler").finish_non_exhaustive()
+    }
+}
+
+/// A callback that computes attributes for each element. See [`OutputConfig::element_visitor`].
+#[derive(Clone)]
+pub struct ElementVisitor(Arc<ElementVisitorFn>);
+
+type ElementVisitorFn = dyn Fn(&ElementRef, &mut Vec<(String, String)>) + Send + Sync;
+
+impl ElementVisitor {
+    /// Wraps `f`, which is called with each element and pushes the `(name, value)` attributes to
+    /// add to it.
+    pub fn new(
+        f: impl Fn(&ElementRef, &mut Vec<(String, String)>) + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(f))
+    }
+
+    pub(crate) fn call(&self, element: &ElementRef, attributes: &mut Vec<(String, String)>) {
+        (self.0)(element, attributes)
+    }
+}
+
+impl fmt::Debug for ElementVisitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ElementVisitor").finish_non_exhaustive()
+    }
+}
+
+/// A callback that rewrites tag names as they're written. See [`OutputConfig::tag_rewrite`].
+#[derive(Clone)]
+pub struct TagRewrite(Arc<TagRewriteFn>);
+
+type TagRewriteFn = dyn Fn(&str) -> Option<Cow<str>> + Send + Sync;
+
+impl TagRewrite {
+    /// Wraps `f`, which is called with a tag name and returns its replacement, or `None` to keep
+    /// it.
+    pub fn new(f: impl Fn(&str) -> Option<Cow<str>> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    pub(crate) fn call<'t>(&self, tag: &'t str) -> Option<Cow<'t, str>> {
+        (self.0)(tag)
+    }
+}
+
+impl fmt::Debug for TagRewrite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TagRewrite").finish_non_exhaustive()
+    }
+}
+
+/// A custom inference method for the root of a document. See [`OutputConfig::inference`].
+#[derive(Clone)]
+pub struct CustomInference(Arc<dyn for<'cfg> DynInfer<'cfg> + Send + Sync>);
+
+impl CustomInference {
+    /// Wraps `method`, which must implement [`Infer<'cfg>`](Infer) for every `'cfg`.
+    pub fn new(method: impl for<'cfg> Infer<'cfg> + Send + Sync) -> Self {
+        Self(Arc::new(method))
+    }
+
+    pub(crate) fn get<'cfg>(&self) -> &(dyn DynInfer<'cfg> + '_) {
+        &*self.0
+    }
+}
+
+impl fmt::Debug for CustomInference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CustomInference").field(&self.0).finish()
+    }
+}
+
+/// A callback that finds the responsive alternatives for an image.
+/// See [`OutputConfig::responsive_image_resolver`].
+#[derive(Clone)]
+pub struct ResponsiveImageResolver(ImageResolverFn);
+
+type ImageResolverFn = Arc<dyn Fn(&str) -> Option<Vec<(String, String)>> + Send + Sync>;
+
+impl ResponsiveImageResolver {
+    pub fn new(f: impl Fn(&str) -> Option<Vec<(String, String)>> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    pub(crate) fn resolve(&self, src: &str) -> Option<Vec<(String, String)>> {
+        (self.0)(src)
+    }
+}
+
+impl fmt::Debug for ResponsiveImageResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ResponsiveImageResolver")
+            .finish_non_exhaustive()
+    }
+}
+
 /// Configuration options for converting a MinTyML document.
+///
+/// With the `serde` feature enabled, this implements `Serialize` and `Deserialize`, using
+/// camel-case field names. Options that hold callbacks, like `on_diagnostic`, are skipped.
 #[non_exhaustive]
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, rename_all = "camelCase")
+)]
 pub struct OutputConfig<'src> {
     /// If `Some(tab)`, `tab` will be inserted for each indentation level.
     /// The string should only contain whitespace in order to produce valid HTML.
@@ -67,10 +535,246 @@ pub struct OutputConfig<'src> {
     ///
     /// This is most useful when `complete_page` is enabled so that the root element has a `lang` attribute.
     pub lang: Option<Src<'src>>,
+    /// If provided, a `dir` attribute will be assigned to the same elements as `lang`.
+    ///
+    /// See [`Direction`] for the values produced.
+    pub dir: Option<Direction>,
     /// If provided, parsing metadata will be added to the output.
     pub metadata: Option<MetadataConfig>,
     /// Whether the parser should opt to fail earlier if errors are present. Defaults to `false`.
     pub fail_fast: Option<bool>,
+    /// If provided, this string will be written before the document when `complete_page` is
+    /// enabled. An empty string suppresses the doctype.
+    ///
+    /// Defaults to `<!DOCTYPE html>`, or no doctype if `xml` is enabled.
+    pub doctype: Option<Src<'src>>,
+    /// The line break inserted between lines of output. Defaults to [`LineEnding::Lf`].
+    ///
+    /// This does not affect line breaks within text content.
+    pub line_ending: Option<LineEnding>,
+    /// If provided along with `indent`, whitespace between words and attributes will be replaced
+    /// with line breaks where needed to keep lines within this many columns.
+    ///
+    /// Whitespace within raw or preformatted elements is never changed,
+    /// so lines may still exceed the width if they contain long words.
+    pub max_width: Option<usize>,
+    /// If provided and `complete_page` is enabled, a `<meta>` element declaring the character
+    /// encoding will be added to the generated `<head>` if one isn't already present.
+    pub charset_meta: Option<CharsetMetaStyle>,
+    /// Whether to warn about `img` elements without an `alt` attribute. Defaults to `false`.
+    ///
+    /// An empty `alt` attribute is allowed, as it marks the image as decorative.
+    pub warn_missing_alt: Option<bool>,
+    /// Additional tags, matched case-insensitively, that are treated as void elements in HTML mode:
+    /// they have no closing tag.
+    ///
+    /// This is in addition to the standard HTML void elements like `img` and `br`.
+    pub void_elements: Option<Vec<Src<'src>>>,
+    /// If provided, the conversion will be aborted with [`ConvertError::OutputTooLarge`]
+    /// once the output would exceed this many bytes.
+    ///
+    /// [`ConvertError::OutputTooLarge`]: crate::ConvertError::OutputTooLarge
+    pub max_output_bytes: Option<usize>,
+    /// If provided, each element whose tag was inferred will be given a class made of this prefix
+    /// followed by the inferred tag, like `mty-inferred-p`.
+    ///
+    /// This is intended for debugging or demonstrating how inference works.
+    pub inferred_wrapper_class_prefix: Option<Src<'src>>,
+    /// Whether void elements should be written with self-closing syntax (`<br/>`) in HTML mode.
+    /// Defaults to `false`.
+    ///
+    /// This has no effect when `xml` is enabled, as all empty elements are self-closing.
+    pub self_close_void: Option<bool>,
+    /// How the case of tag names is written. Defaults to [`TagCase::Preserve`].
+    ///
+    /// Attribute names are not affected.
+    pub tag_case: Option<TagCase>,
+    /// Whether comments inside void elements should be moved to directly after the element.
+    /// Defaults to `false`.
+    pub relocate_void_comments: Option<bool>,
+    /// Whether attributes should be written in canonical XML order when `xml` is enabled.
+    /// Defaults to `false`.
+    ///
+    /// Namespace declarations come first, followed by attributes without a prefix,
+    /// followed by prefixed attributes ordered by prefix and then by local name.
+    pub xml_canonical_attrs: Option<bool>,
+    /// If `Some`, called with each error as soon as it is found during conversion,
+    /// before the conversion completes.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub on_diagnostic: Option<DiagnosticHandler>,
+    /// Whether the children of the root `html` element should be indented when `indent` is set.
+    /// Defaults to `false`.
+    pub indent_html_children: Option<bool>,
+    /// If `Some`, called with the `src` of each `img` element.
+    /// When it returns a non-empty list of `(media, srcset)` pairs, the image is wrapped in a
+    /// `picture` element with a `source` element for each pair, followed by the original `img`.
+    ///
+    /// Images that are already inside a `picture` element are left alone.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub responsive_image_resolver: Option<ResponsiveImageResolver>,
+    /// If `Some(tag)` and `complete_page` is not enabled, all top-level content will be wrapped
+    /// in a single element with the given tag.
+    pub root_element: Option<Src<'src>>,
+    /// Whether unrecognized escape sequences should be reported as errors even in text where
+    /// escape sequences aren't processed, like `'''` blocks and inline code. Defaults to `false`.
+    pub strict_escapes: Option<bool>,
+    /// Whether end tags like `</li>` and `</p>` should be left out where HTML allows it.
+    /// Has no effect when `xml` is enabled. Defaults to `false`.
+    pub omit_optional_end_tags: Option<bool>,
+    /// Whether top-level `head` and `body` elements should be wrapped in an `html` element
+    /// when `complete_page` is not enabled and there is no top-level `html` element.
+    /// Defaults to `false`.
+    pub auto_html_wrap: Option<bool>,
+    /// Whether runs of spaces and tabs in text should be collapsed to a single space.
+    /// Text in raw and preformatted elements like `pre` is left alone. Defaults to `false`.
+    pub collapse_text_whitespace: Option<bool>,
+    /// Whether `true` and `false` values of boolean-like attributes such as `contenteditable`,
+    /// `draggable`, `spellcheck`, and `aria-*` should be lowercased. Defaults to `false`.
+    pub normalize_boolean_values: Option<bool>,
+    /// If `Some`, replaces [`StandardInfer`](crate::inference::StandardInfer) as the method used
+    /// to infer the tags of top-level elements and to choose the methods used for their children.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub inference: Option<CustomInference>,
+    /// Whether a comment noting the number of errors should be added to the end of best-effort
+    /// output when a conversion fails, as with [`convert_forgiving`](crate::convert_forgiving).
+    /// Defaults to `false`.
+    pub annotate_errors_in_output: Option<bool>,
+    /// Whether the cells in the first row of a table should be inferred as `th` rather than `td`.
+    /// Cells in a `thead` are always inferred as `th`. Defaults to `false`.
+    pub table_header_row: Option<bool>,
+    /// Additional `(open, close)` delimiter pairs whose contents should be copied to the output
+    /// verbatim and unescaped, bypassing MinTyML syntax, like template expressions.
+    ///
+    /// `{{ ... }}`, `{% ... %}`, `<% ... %>`, and `<? ... ?>` are always treated this way.
+    pub opaque_spans: Option<Vec<(Src<'src>, Src<'src>)>>,
+    /// If `Some(selector)`, each `table` element will be wrapped in an element described by the
+    /// given selector, like `div.table-wrap`. Only the tag, classes, and ID are used, and the tag
+    /// defaults to `div`.
+    pub table_wrapper: Option<Src<'src>>,
+    /// Whether a space is written between adjacent inline special elements like `<#a#>` and
+    /// `</b/>`, including inline element chains like `<(b> a)>`. Defaults to
+    /// [`SpacesPolicy::Preserve`].
+    pub adjacent_inline_special_space: Option<SpacesPolicy>,
+    /// Whether attributes should be written in sorted order, regardless of whether `xml` is
+    /// enabled. The ID and classes are still written first, followed by the other attributes in
+    /// the same order as `xml_canonical_attrs`. Defaults to `false`.
+    pub sort_attributes: Option<bool>,
+    /// Which elements receive the `lang` and `dir` attributes. Defaults to
+    /// [`LangScope::TopLevel`].
+    pub lang_scope: Option<LangScope>,
+    /// Whether the children of elements with the reserved `mty-sort` attribute should be sorted
+    /// by ID. Children without an ID keep their position. Defaults to `false`.
+    ///
+    /// The `mty-sort` attribute is removed regardless.
+    pub sort_siblings_by_id: Option<bool>,
+    /// Whether attributes that are meaningless without a value, like `href` and `src`, should be
+    /// reported as errors when given none. Defaults to `false`.
+    pub strict_attributes: Option<bool>,
+    /// Additional attribute names, matched case-insensitively, that `strict_attributes` requires
+    /// to have a value.
+    ///
+    /// This is in addition to standard attributes like `href` and `src`.
+    pub value_required_attributes: Option<Vec<Src<'src>>>,
+    /// `(property, content)` pairs, like `("og:title", "My Page")`, each added to the `head`
+    /// created by `complete_page` as a `<meta property="..." content="...">` element.
+    pub open_graph: Option<Vec<(Src<'src>, Src<'src>)>>,
+    /// If `Some`, called for each element after inference to compute `(name, value)` attributes
+    /// to add to it. Attributes the element already has are left alone, and if the callback adds
+    /// the same name more than once, only the first is kept. Names are compared
+    /// case-insensitively.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub element_visitor: Option<ElementVisitor>,
+    /// Whether bare `http://` and `https://` URLs in text should be wrapped in `a` elements
+    /// linking to them. URLs inside raw elements, `a` elements, and verbatim segments are left
+    /// alone. Defaults to `false`.
+    pub autolink: Option<bool>,
+    /// If `Some`, called once with each tag name as it's written, after `tag_case` is applied.
+    /// Returning `Some` replaces the tag in the opening tag and in the closing tag, if one is
+    /// written. Void elements are identified by the replacement, so they have no closing tag.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub tag_rewrite: Option<TagRewrite>,
+    /// Whether the output should be well-formed XHTML5 that strict XML parsers accept.
+    /// Defaults to `false`.
+    ///
+    /// This enables everything `xml` does: empty and void elements are self-closed, text and
+    /// attribute values are escaped for XML, boolean attributes are written with an empty value,
+    /// and the content of `script` and `style` elements is wrapped in CDATA sections. It also adds
+    /// the XHTML namespace to each top-level `html` element without an `xmlns` attribute. No
+    /// other options are changed, so no doctype is written unless `doctype` is set.
+    pub xhtml_strict: Option<bool>,
+    /// Whether comments should be written to the output. If `false`, comments are left out
+    /// along with the spaces around them. Defaults to `true`.
+    pub emit_comments: Option<bool>,
+    /// If `Some(true)`, the output ends with exactly one line break, even without `indent`.
+    /// If `Some(false)`, the output doesn't end with a line break, even with `indent`.
+    /// If `None`, a line break is written at the end only when `indent` is set.
+    pub final_newline: Option<bool>,
+    /// If `Some`, each `$name` in an unquoted attribute value is replaced with the value of the
+    /// variable `name`, as in `[href=$base/page]`. Referring to a variable that isn't defined is
+    /// an error. Use `\$` for a literal `$`.
+    pub variables: Option<BTreeMap<Src<'src>, Src<'src>>>,
+    /// `(prefix, uri)` pairs declared as `xmlns:prefix="uri"` attributes on the root element in
+    /// XML mode. An empty prefix declares the default namespace. Ignored outside of XML mode, and
+    /// for fragments with more than one top-level element unless `complete_page` or
+    /// `root_element` gives them a root.
+    pub namespaces: Option<Vec<(Src<'src>, Src<'src>)>>,
+    /// Whether a forgiving conversion should produce no output if the document has unclosed,
+    /// misplaced, or mismatched delimiters, rather than guessing where they belong.
+    /// Unlike `fail_fast`, every such error is still reported. Defaults to `false`.
+    pub strict: Option<bool>,
+    /// Whether boolean attributes like `disabled` and `checked` should be written without a
+    /// value when their value is empty or matches their name, as in `<input disabled>`.
+    /// Has no effect when `xml` is enabled. Defaults to `false`.
+    pub minimize_boolean_attributes: Option<bool>,
+    /// Additional attribute names, matched case-insensitively, that `minimize_boolean_attributes`
+    /// treats as boolean attributes.
+    ///
+    /// This is in addition to standard attributes like `disabled` and `checked`.
+    pub boolean_attributes: Option<Vec<Src<'src>>>,
+    /// Whether the space between adjacent elements on the same line, as in `<(a)> <(b)>`, should
+    /// be written. Spaces next to text are always written. Defaults to `true`.
+    pub preserve_inline_space: Option<bool>,
+    /// Whether every non-ASCII character in text and attribute values should be written as a
+    /// numeric character reference like `&#233;`. Raw text, comments, and tag and attribute names
+    /// are written as-is. Defaults to `false`.
+    pub ascii_only: Option<bool>,
+    /// Whether a class given more than once to an element, as in `.a.b.a`, should only be written
+    /// the first time. Defaults to `true`.
+    pub dedup_classes: Option<bool>,
+    /// Whether inline code like ``<` ... `>`` should be wrapped in the code block container, as
+    /// code blocks are. Defaults to `false`, writing it as a bare `code` element.
+    pub inline_code_in_pre: Option<bool>,
+    /// Which element receives the classes and ID of an element that inference gives more than one
+    /// tag, like `["li", "a"]`. Defaults to [`ClassPlacement::Outermost`].
+    pub inferred_class_placement: Option<ClassPlacement>,
+    /// Whether lines whose leading whitespace mixes tabs and spaces, or uses a different one than
+    /// earlier lines in the same block, should be reported as errors. Defaults to `false`.
+    pub check_indentation: Option<bool>,
+    /// Replacements for characters in text and attribute values, like `'` to `&apos;`, that take
+    /// precedence over the built-in escapes. Raw text and comments are written as-is.
+    pub extra_escapes: Option<BTreeMap<char, Src<'src>>>,
+    /// Whether multiline text like `'''` blocks should have its first and last lines removed and
+    /// its indentation normalized. If `false`, everything between the delimiters is written
+    /// exactly as it appears in the source. Defaults to `true`.
+    pub trim_multiline: Option<bool>,
+    /// Whether elements inside ones that HTML doesn't allow to contain them, like a `div` inside
+    /// a `p`, should be reported as errors. Only a small set of common cases is checked.
+    /// Defaults to `false`.
+    pub strict_nesting: Option<bool>,
+    /// Which empty elements are left out of the output. An element counts as empty if it has no
+    /// content other than whitespace and no classes, IDs, or attributes, and elements left empty
+    /// after their children are dropped are dropped too. Elements added by other options, like
+    /// [`complete_page`](Self::complete_page), and elements that mean something even when empty,
+    /// like `td`, `textarea`, and `script`, are never dropped.
+    /// Defaults to [`DropEmptyPolicy::Never`].
+    pub drop_empty: Option<DropEmptyPolicy>,
+    /// If `Some(max)`, the conversion stops once more than `max` syntax and semantic errors have
+    /// been found, reporting only the first `max` and marking the error as truncated. A `max` of
+    /// `0` is treated as `1`.
+    pub max_errors: Option<usize>,
+    /// Whether an ID given to more than one element, with `#id` or an `id` attribute, should be
+    /// reported as an error. Defaults to `false`.
+    pub check_duplicate_ids: Option<bool>,
 }
 
 impl<'src> OutputConfig<'src> {
@@ -79,6 +783,26 @@ impl<'src> OutputConfig<'src> {
         Self::default()
     }
 
+    /// Instantiates with the options needed for well-formed XHTML5 output.
+    ///
+    /// This sets `xhtml_strict` to `true`, which also enables XML mode, and `doctype` to
+    /// `<!DOCTYPE html>` for when `complete_page` is enabled. All other options keep their
+    /// default values.
+    ///
+    /// # Example
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert("html> img[src=a.png]>", OutputConfig::xhtml()).unwrap();
+    ///
+    /// assert_eq!(
+    ///     out,
+    ///     r#"<html xmlns="http://www.w3.org/1999/xhtml"><img src="a.png"/></html>"#,
+    /// );
+    /// ```
+    pub fn xhtml() -> Self {
+        Self::new().xhtml_strict(true).doctype("<!DOCTYPE html>")
+    }
+
     /// Convenience method for mutating `self` in the same expression where it was created.
     ///
     /// # Example
@@ -96,6 +820,183 @@ impl<'src> OutputConfig<'src> {
         self
     }
 
+    /// Like [`update`](Self::update), but `f` may fail, in which case its error is returned.
+    ///
+    /// # Example
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let result = OutputConfig::new().xml(true).try_update(|config| {
+    ///     if config.xml == Some(true) {
+    ///         return Err("omit_optional_end_tags has no effect in XML mode");
+    ///     }
+    ///     config.omit_optional_end_tags = Some(true);
+    ///     Ok(())
+    /// });
+    ///
+    /// assert!(result.is_err());
+    /// ```
+    pub fn try_update<E>(mut self, f: impl FnOnce(&mut Self) -> Result<(), E>) -> Result<Self, E> {
+        f(&mut self)?;
+        Ok(self)
+    }
+
+    /// Combines this config with `overrides`, using the value from `overrides` for each option
+    /// it sets. Special tags and metadata options are merged individually, while other options are
+    /// replaced as a whole.
+    ///
+    /// # Example
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let base = OutputConfig::new().indent("  ").xml(true);
+    /// let config = base.merge(OutputConfig::new().xml(false));
+    ///
+    /// assert_eq!(config.indent.as_deref(), Some("  "));
+    /// assert_eq!(config.xml, Some(false));
+    /// ```
+    pub fn merge(mut self, overrides: Self) -> Self {
+        let Self {
+            indent,
+            xml,
+            special_tags,
+            complete_page,
+            lang,
+            dir,
+            metadata,
+            fail_fast,
+            doctype,
+            line_ending,
+            max_width,
+            charset_meta,
+            warn_missing_alt,
+            void_elements,
+            max_output_bytes,
+            inferred_wrapper_class_prefix,
+            self_close_void,
+            tag_case,
+            relocate_void_comments,
+            xml_canonical_attrs,
+            on_diagnostic,
+            indent_html_children,
+            responsive_image_resolver,
+            root_element,
+            strict_escapes,
+            omit_optional_end_tags,
+            auto_html_wrap,
+            collapse_text_whitespace,
+            normalize_boolean_values,
+            inference,
+            annotate_errors_in_output,
+            table_header_row,
+            opaque_spans,
+            table_wrapper,
+            adjacent_inline_special_space,
+            sort_attributes,
+            lang_scope,
+            sort_siblings_by_id,
+            strict_attributes,
+            value_required_attributes,
+            open_graph,
+            element_visitor,
+            autolink,
+            tag_rewrite,
+            xhtml_strict,
+            emit_comments,
+            final_newline,
+            variables,
+            namespaces,
+            strict,
+            minimize_boolean_attributes,
+            boolean_attributes,
+            preserve_inline_space,
+            ascii_only,
+            dedup_classes,
+            inline_code_in_pre,
+            inferred_class_placement,
+            check_indentation,
+            extra_escapes,
+            trim_multiline,
+            strict_nesting,
+            drop_empty,
+            max_errors,
+            check_duplicate_ids,
+        } = overrides;
+
+        self.special_tags = self.special_tags.merge(special_tags);
+        self.indent = indent.or(self.indent);
+        self.xml = xml.or(self.xml);
+        self.complete_page = complete_page.or(self.complete_page);
+        self.lang = lang.or(self.lang);
+        self.dir = dir.or(self.dir);
+        self.metadata = match (self.metadata, metadata) {
+            (Some(base), Some(metadata)) => Some(base.merge(metadata)),
+            (base, metadata) => metadata.or(base),
+        };
+        self.fail_fast = fail_fast.or(self.fail_fast);
+        self.doctype = doctype.or(self.doctype);
+        self.line_ending = line_ending.or(self.line_ending);
+        self.max_width = max_width.or(self.max_width);
+        self.charset_meta = charset_meta.or(self.charset_meta);
+        self.warn_missing_alt = warn_missing_alt.or(self.warn_missing_alt);
+        self.void_elements = void_elements.or(self.void_elements);
+        self.max_output_bytes = max_output_bytes.or(self.max_output_bytes);
+        self.inferred_wrapper_class_prefix =
+            inferred_wrapper_class_prefix.or(self.inferred_wrapper_class_prefix);
+        self.self_close_void = self_close_void.or(self.self_close_void);
+        self.tag_case = tag_case.or(self.tag_case);
+        self.relocate_void_comments = relocate_void_comments.or(self.relocate_void_comments);
+        self.xml_canonical_attrs = xml_canonical_attrs.or(self.xml_canonical_attrs);
+        self.on_diagnostic = on_diagnostic.or(self.on_diagnostic);
+        self.indent_html_children = indent_html_children.or(self.indent_html_children);
+        self.responsive_image_resolver =
+            responsive_image_resolver.or(self.responsive_image_resolver);
+        self.root_element = root_element.or(self.root_element);
+        self.strict_escapes = strict_escapes.or(self.strict_escapes);
+        self.omit_optional_end_tags = omit_optional_end_tags.or(self.omit_optional_end_tags);
+        self.auto_html_wrap = auto_html_wrap.or(self.auto_html_wrap);
+        self.collapse_text_whitespace = collapse_text_whitespace.or(self.collapse_text_whitespace);
+        self.normalize_boolean_values = normalize_boolean_values.or(self.normalize_boolean_values);
+        self.inference = inference.or(self.inference);
+        self.annotate_errors_in_output =
+            annotate_errors_in_output.or(self.annotate_errors_in_output);
+        self.table_header_row = table_header_row.or(self.table_header_row);
+        self.opaque_spans = opaque_spans.or(self.opaque_spans);
+        self.table_wrapper = table_wrapper.or(self.table_wrapper);
+        self.adjacent_inline_special_space =
+            adjacent_inline_special_space.or(self.adjacent_inline_special_space);
+        self.sort_attributes = sort_attributes.or(self.sort_attributes);
+        self.lang_scope = lang_scope.or(self.lang_scope);
+        self.sort_siblings_by_id = sort_siblings_by_id.or(self.sort_siblings_by_id);
+        self.strict_attributes = strict_attributes.or(self.strict_attributes);
+        self.value_required_attributes =
+            value_required_attributes.or(self.value_required_attributes);
+        self.open_graph = open_graph.or(self.open_graph);
+        self.element_visitor = element_visitor.or(self.element_visitor);
+        self.autolink = autolink.or(self.autolink);
+        self.tag_rewrite = tag_rewrite.or(self.tag_rewrite);
+        self.xhtml_strict = xhtml_strict.or(self.xhtml_strict);
+        self.emit_comments = emit_comments.or(self.emit_comments);
+        self.final_newline = final_newline.or(self.final_newline);
+        self.variables = variables.or(self.variables);
+        self.namespaces = namespaces.or(self.namespaces);
+        self.strict = strict.or(self.strict);
+        self.minimize_boolean_attributes =
+            minimize_boolean_attributes.or(self.minimize_boolean_attributes);
+        self.boolean_attributes = boolean_attributes.or(self.boolean_attributes);
+        self.preserve_inline_space = preserve_inline_space.or(self.preserve_inline_space);
+        self.ascii_only = ascii_only.or(self.ascii_only);
+        self.dedup_classes = dedup_classes.or(self.dedup_classes);
+        self.inline_code_in_pre = inline_code_in_pre.or(self.inline_code_in_pre);
+        self.inferred_class_placement = inferred_class_placement.or(self.inferred_class_placement);
+        self.check_indentation = check_indentation.or(self.check_indentation);
+        self.extra_escapes = extra_escapes.or(self.extra_escapes);
+        self.trim_multiline = trim_multiline.or(self.trim_multiline);
+        self.strict_nesting = strict_nesting.or(self.strict_nesting);
+        self.drop_empty = drop_empty.or(self.drop_empty);
+        self.max_errors = max_errors.or(self.max_errors);
+        self.check_duplicate_ids = check_duplicate_ids.or(self.check_duplicate_ids);
+        self
+    }
+
     /// Sets the string `tab` to insert for each indentation level of the output.
     /// The string should only contain whitespace in order to produce valid HTML.
     /// Setting this value enables automatic line breaks in the output.
@@ -138,6 +1039,37 @@ impl<'src> OutputConfig<'src> {
         self.update(|c| c.indent = Some(tab.into()))
     }
 
+    /// Indents each level of the output with `n` spaces. Equivalent to [`indent`](Self::indent)
+    /// with a string of `n` spaces.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert("{ Hello, world! }", OutputConfig::new().indent_spaces(2))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(out, "<div>\n  <p>Hello, world!</p>\n</div>\n");
+    /// ```
+    pub fn indent_spaces(self, n: usize) -> Self {
+        self.indent(" ".repeat(n))
+    }
+
+    /// Indents each level of the output with a tab. Equivalent to [`indent`](Self::indent)
+    /// with `"\t"`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert("{ Hello, world! }", OutputConfig::new().indent_tabs()).unwrap();
+    ///
+    /// assert_eq!(out, "<div>\n\t<p>Hello, world!</p>\n</div>\n");
+    /// ```
+    pub fn indent_tabs(self) -> Self {
+        self.indent("\t")
+    }
+
     /// Specifies whether the output document should be in XHTML5 rather than HTML.
     ///
     /// This is useful when the output needs to be read by an XML parser.
@@ -147,38 +1079,56 @@ impl<'src> OutputConfig<'src> {
 
     /// Overrides the tag used for `</ ... />`. Defaults to `em`.
     pub fn emphasis_tag(self, tag: impl Into<Src<'src>>) -> Self {
-        self.update(|c| c.special_tags.emphasis = Some(tag.into()))
+        self.update(|c| c.special_tags.emphasis = Some(SpecialTag::Tag(tag.into())))
     }
 
     /// Overrides the tag used for `<# ... #>`. Defaults to `strong`.
     pub fn strong_tag(self, tag: impl Into<Src<'src>>) -> Self {
-        self.update(|c| c.special_tags.strong = Some(tag.into()))
+        self.update(|c| c.special_tags.strong = Some(SpecialTag::Tag(tag.into())))
     }
 
     /// Overrides the tag used for `<_ ... _>`. Defaults to `u`.
     pub fn underline_tag(self, tag: impl Into<Src<'src>>) -> Self {
-        self.update(|c| c.special_tags.underline = Some(tag.into()))
+        self.update(|c| c.special_tags.underline = Some(SpecialTag::Tag(tag.into())))
     }
 
     /// Overrides the tag used for `<~ ... ~>`. Defaults to `s`.
     pub fn strike_tag(self, tag: impl Into<Src<'src>>) -> Self {
-        self.update(|c| c.special_tags.strike = Some(tag.into()))
+        self.update(|c| c.special_tags.strike = Some(SpecialTag::Tag(tag.into())))
     }
 
     /// Overrides the tag used for `<" ... ">`. Defaults to `q`.
     pub fn quote_tag(self, tag: impl Into<Src<'src>>) -> Self {
-        self.update(|c| c.special_tags.quote = Some(tag.into()))
+        self.update(|c| c.special_tags.quote = Some(SpecialTag::Tag(tag.into())))
     }
 
     /// Overrides the tag used for ``<` ... `>``. Defaults to `code`.
     pub fn code_tag(self, tag: impl Into<Src<'src>>) -> Self {
-        self.update(|c| c.special_tags.code = Some(tag.into()))
+        self.update(|c| c.special_tags.code = Some(SpecialTag::Tag(tag.into())))
     }
 
     /// Code blocks (denoted with ```` ``` ````) will use the same tag as inline code,
     /// wrapped in the tag provided by this call. Defaults to `pre`.
     pub fn code_block_container_tag(self, tag: impl Into<Src<'src>>) -> Self {
-        self.update(|c| c.special_tags.code_block_container = Some(tag.into()))
+        self.update(|c| c.special_tags.code_block_container = Some(SpecialTag::Tag(tag.into())))
+    }
+
+    /// Disables the syntax for a kind of special element, so its delimiters are written as
+    /// literal text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::{OutputConfig, SpecialKind};
+    /// let out = mintyml::convert(
+    ///     "p> a </b/> <#c#>",
+    ///     OutputConfig::new().disable_special(SpecialKind::Emphasis),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, "<p>a &lt;/b/&gt; <strong>c</strong></p>");
+    /// ```
+    pub fn disable_special(self, kind: SpecialKind) -> Self {
+        self.update(|c| c.special_tags.set(kind, SpecialTag::Disabled))
     }
 
     /// Whether the output should be a complete, valid HTML page. Defaults to `false`.
@@ -201,6 +1151,23 @@ impl<'src> OutputConfig<'src> {
         self.update(|c| c.lang = lang.into().into())
     }
 
+    /// Assigns a `dir` attribute to the same elements as [`OutputConfig::lang`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::{Direction, OutputConfig};
+    /// let out = mintyml::convert("p> مرحبا", OutputConfig::new()
+    ///     .lang("ar")
+    ///     .dir(Direction::Auto)
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, r#"<p lang="ar" dir="rtl">مرحبا</p>"#);
+    /// ```
+    pub fn dir(self, dir: Direction) -> Self {
+        self.update(|c| c.dir = Some(dir))
+    }
+
     /// If provided, parsing metadata will be added to the output.
     pub fn metadata(self, config: MetadataConfig) -> Self {
         Self {
@@ -213,4 +1180,1133 @@ impl<'src> OutputConfig<'src> {
     pub fn fail_fast(self, enable: bool) -> Self {
         self.update(|c| c.fail_fast = Some(enable))
     }
+
+    /// Sets the doctype written before the document when `complete_page` is enabled.
+    /// An empty string suppresses the doctype.
+    ///
+    /// Defaults to `<!DOCTYPE html>`, or no doctype if `xml` is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert("body> Hello", OutputConfig::new()
+    ///     .complete_page(true)
+    ///     .doctype("<!doctype html>")
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, "<!doctype html>\n<html><body>Hello</body></html>");
+    /// ```
+    pub fn doctype(self, doctype: impl Into<Src<'src>>) -> Self {
+        self.update(|c| c.doctype = Some(doctype.into()))
+    }
+
+    /// Sets the line break inserted between lines of output. Defaults to [`LineEnding::Lf`].
+    ///
+    /// This does not affect line breaks within text content.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::{LineEnding, OutputConfig};
+    /// let out = mintyml::convert("div> Hello", OutputConfig::new()
+    ///     .indent("  ")
+    ///     .line_ending(LineEnding::CrLf)
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, "<div>Hello</div>\r\n");
+    /// ```
+    pub fn line_ending(self, line_ending: LineEnding) -> Self {
+        self.update(|c| c.line_ending = Some(line_ending))
+    }
+
+    /// If `indent` is set, whitespace between words and attributes will be replaced
+    /// with line breaks where needed to keep lines within `width` columns.
+    ///
+    /// Whitespace within raw or preformatted elements is never changed,
+    /// so lines may still exceed the width if they contain long words.
+    pub fn max_width(self, width: usize) -> Self {
+        self.update(|c| c.max_width = Some(width))
+    }
+
+    /// If `complete_page` is enabled, a `<meta>` element declaring the character encoding
+    /// will be added to the generated `<head>` if one isn't already present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::{CharsetMetaStyle, OutputConfig};
+    /// let out = mintyml::convert("p> Hello", OutputConfig::new()
+    ///     .complete_page(true)
+    ///     .charset_meta(CharsetMetaStyle::Short)
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, concat!(
+    ///     "<!DOCTYPE html>\n",
+    ///     r#"<html><head><meta charset="utf-8"></head> <body><p>Hello</p></body></html>"#,
+    /// ));
+    /// ```
+    pub fn charset_meta(self, style: CharsetMetaStyle) -> Self {
+        self.update(|c| c.charset_meta = Some(style))
+    }
+
+    /// Whether to warn about `img` elements without an `alt` attribute. Defaults to `false`.
+    ///
+    /// An empty `alt` attribute is allowed, as it marks the image as decorative.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let (out, warnings) = mintyml::convert_forgiving_with_warnings(
+    ///     r#"img[src="a.png"]>"#,
+    ///     OutputConfig::new().warn_missing_alt(true),
+    /// );
+    ///
+    /// assert_eq!(out.unwrap(), r#"<img src="a.png">"#);
+    /// assert_eq!(warnings.len(), 1);
+    /// ```
+    pub fn warn_missing_alt(self, enable: bool) -> Self {
+        self.update(|c| c.warn_missing_alt = Some(enable))
+    }
+
+    /// Sets additional tags, matched case-insensitively, that are treated as void elements in
+    /// HTML mode: they have no closing tag.
+    ///
+    /// This is in addition to the standard HTML void elements like `img` and `br`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "my-icon[name=star]>",
+    ///     OutputConfig::new().void_elements(["my-icon"]),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, r#"<my-icon name="star">"#);
+    /// ```
+    pub fn void_elements<T: Into<Src<'src>>>(self, tags: impl IntoIterator<Item = T>) -> Self {
+        self.update(|c| c.void_elements = Some(tags.into_iter().map(Into::into).collect()))
+    }
+
+    /// Aborts the conversion with [`ConvertError::OutputTooLarge`] once the output would exceed
+    /// `limit` bytes.
+    ///
+    /// This guards against small documents that produce disproportionately large output.
+    ///
+    /// [`ConvertError::OutputTooLarge`]: crate::ConvertError::OutputTooLarge
+    pub fn max_output_bytes(self, limit: usize) -> Self {
+        self.update(|c| c.max_output_bytes = Some(limit))
+    }
+
+    /// Gives each element whose tag was inferred a class made of `prefix` followed by the
+    /// inferred tag.
+    ///
+    /// This is intended for debugging or demonstrating how inference works.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert("ul { > Hello }", OutputConfig::new()
+    ///     .inferred_wrapper_class_prefix("mty-inferred-")
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, r#"<ul><li class="mty-inferred-li">Hello</li></ul>"#);
+    /// ```
+    pub fn inferred_wrapper_class_prefix(self, prefix: impl Into<Src<'src>>) -> Self {
+        self.update(|c| c.inferred_wrapper_class_prefix = Some(prefix.into()))
+    }
+
+    /// Whether void elements should be written with self-closing syntax (`<br/>`) in HTML mode.
+    /// Defaults to `false`.
+    ///
+    /// This has no effect when `xml` is enabled, as all empty elements are self-closing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert("img[src=x]>", OutputConfig::new().self_close_void(true))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(out, r#"<img src="x"/>"#);
+    /// ```
+    pub fn self_close_void(self, enable: bool) -> Self {
+        self.update(|c| c.self_close_void = Some(enable))
+    }
+
+    /// Sets how the case of tag names is written. Defaults to [`TagCase::Preserve`].
+    ///
+    /// Attribute names are not affected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::{OutputConfig, TagCase};
+    /// let config = OutputConfig::new().tag_case(TagCase::Lower);
+    /// let out = mintyml::convert("DIV[ID=x]> hi", config).unwrap();
+    ///
+    /// assert_eq!(out, r#"<div ID="x">hi</div>"#);
+    /// ```
+    pub fn tag_case(self, tag_case: TagCase) -> Self {
+        self.update(|c| c.tag_case = Some(tag_case))
+    }
+
+    /// Whether comments inside void elements should be moved to directly after the element.
+    /// Defaults to `false`.
+    ///
+    /// Void elements like `img` can't contain anything, so this keeps the comments
+    /// from being written as though they were the element's content.
+    pub fn relocate_void_comments(self, enable: bool) -> Self {
+        self.update(|c| c.relocate_void_comments = Some(enable))
+    }
+
+    /// Whether attributes should be written in canonical XML order when `xml` is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "svg[xlink:href=a width=1 xmlns=b]#pic>",
+    ///     OutputConfig::new().xml(true).xml_canonical_attrs(true),
+    /// ).unwrap();
+    /// assert_eq!(out, r#"<svg xmlns="b" id="pic" width="1" xlink:href="a"/>"#);
+    /// ```
+    pub fn xml_canonical_attrs(self, enable: bool) -> Self {
+        self.update(|c| c.xml_canonical_attrs = Some(enable))
+    }
+
+    /// Calls `f` with each error as soon as it is found during conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// # use std::sync::{Arc, Mutex};
+    /// let found = Arc::new(Mutex::new(Vec::new()));
+    /// let config = OutputConfig::new().on_diagnostic({
+    ///     let found = found.clone();
+    ///     move |d| found.lock().unwrap().push(format!("{d:?}"))
+    /// });
+    ///
+    /// assert!(mintyml::convert("div> <(a", config).is_err());
+    /// assert_eq!(found.lock().unwrap().len(), 1);
+    /// ```
+    pub fn on_diagnostic(self, f: impl Fn(&Diagnostic) + Send + Sync + 'static) -> Self {
+        self.update(|c| c.on_diagnostic = Some(DiagnosticHandler::new(f)))
+    }
+
+    /// Whether the children of the root `html` element should be indented when `indent` is set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "html { body> Hi }",
+    ///     OutputConfig::new().indent("  ").indent_html_children(true),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, "<html>\n  <body>Hi</body>\n</html>\n");
+    /// ```
+    pub fn indent_html_children(self, enable: bool) -> Self {
+        self.update(|c| c.indent_html_children = Some(enable))
+    }
+
+    /// Wraps `img` elements in `picture` elements with the `(media, srcset)` pairs returned by `f`
+    /// for the image's `src`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "img[src=a.png]>",
+    ///     OutputConfig::new().responsive_image_resolver(|src| {
+    ///         Some(vec![("".into(), src.replace(".png", ".webp"))])
+    ///     }),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, r#"<picture><source srcset="a.webp"><img src="a.png"></picture>"#);
+    /// ```
+    pub fn responsive_image_resolver(
+        self,
+        f: impl Fn(&str) -> Option<Vec<(String, String)>> + Send + Sync + 'static,
+    ) -> Self {
+        self.update(|c| c.responsive_image_resolver = Some(ResponsiveImageResolver::new(f)))
+    }
+
+    /// If `complete_page` is not enabled, wraps all top-level content in a single element with the
+    /// given tag.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert("> one\n> two", OutputConfig::new().root_element("ul")).unwrap();
+    ///
+    /// assert_eq!(out, "<ul><li>one</li> <li>two</li></ul>");
+    /// ```
+    pub fn root_element(self, tag: impl Into<Src<'src>>) -> Self {
+        self.update(|c| c.root_element = Some(tag.into()))
+    }
+
+    /// Whether unrecognized escape sequences should be reported as errors even in text where
+    /// escape sequences aren't processed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// assert!(mintyml::convert(r"<`\q`>", OutputConfig::new()).is_ok());
+    /// assert!(mintyml::convert(r"<`\q`>", OutputConfig::new().strict_escapes(true)).is_err());
+    /// ```
+    pub fn strict_escapes(self, enable: bool) -> Self {
+        self.update(|c| c.strict_escapes = Some(enable))
+    }
+
+    /// Whether end tags like `</li>` and `</p>` should be left out where HTML allows it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "ul {\n> one\n> two\n}",
+    ///     OutputConfig::new().omit_optional_end_tags(true),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, "<ul><li>one <li>two</ul>");
+    /// ```
+    pub fn omit_optional_end_tags(self, enable: bool) -> Self {
+        self.update(|c| c.omit_optional_end_tags = Some(enable))
+    }
+
+    /// Whether top-level `head` and `body` elements should be wrapped in an `html` element
+    /// when `complete_page` is not enabled and there is no top-level `html` element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "head { title> Hi }\nbody { Hello }",
+    ///     OutputConfig::new().auto_html_wrap(true),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, "<html><head><title>Hi</title></head> <body><p>Hello</p></body></html>");
+    /// ```
+    pub fn auto_html_wrap(self, enable: bool) -> Self {
+        self.update(|c| c.auto_html_wrap = Some(enable))
+    }
+
+    /// Whether runs of spaces and tabs in text should be collapsed to a single space.
+    /// Text in raw and preformatted elements like `pre` is left alone.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "p> Hello     world",
+    ///     OutputConfig::new().collapse_text_whitespace(true),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, "<p>Hello world</p>");
+    /// ```
+    pub fn collapse_text_whitespace(self, enable: bool) -> Self {
+        self.update(|c| c.collapse_text_whitespace = Some(enable))
+    }
+
+    /// Whether `true` and `false` values of boolean-like attributes such as `contenteditable`,
+    /// `draggable`, `spellcheck`, and `aria-*` should be lowercased.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "div[draggable=False]> Drag me",
+    ///     OutputConfig::new().normalize_boolean_values(true),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, r#"<div draggable="false">Drag me</div>"#);
+    /// ```
+    pub fn normalize_boolean_values(self, enable: bool) -> Self {
+        self.update(|c| c.normalize_boolean_values = Some(enable))
+    }
+
+    /// Replaces [`StandardInfer`](crate::inference::StandardInfer) as the method used to infer the
+    /// tags of top-level elements and to choose the methods used for their children.
+    ///
+    /// Only the methods reachable from `method` are used, so custom rules for nested elements
+    /// should be added before falling back to the standard ones, and elements matched by a
+    /// standard rule use standard inference for their own children.
+    /// Because the same method is used with configs of any lifetime, `method` must implement
+    /// [`Infer<'cfg>`](Infer) for every `'cfg`, so it can't borrow any data of its own.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mintyml::{
+    ///     inference::{
+    ///         define_methods, define_tags, when::*, Infer, MethodDefinition, StandardInfer,
+    ///         TagDefinition,
+    ///     },
+    ///     OutputConfig,
+    /// };
+    ///
+    /// /// Infers a `header` for the first child of a `card` and `section`s for the rest.
+    /// #[derive(Debug)]
+    /// struct CardInfer;
+    ///
+    /// impl<'cfg> Infer<'cfg> for CardInfer {
+    ///     fn define_tags(&self) -> impl TagDefinition<'cfg> {
+    ///         define_tags()
+    ///             .when(!after(element()), "header")
+    ///             .default("section")
+    ///     }
+    /// }
+    ///
+    /// /// Standard inference, plus `card` elements.
+    /// #[derive(Debug)]
+    /// struct DesignSystem;
+    ///
+    /// impl<'cfg> Infer<'cfg> for DesignSystem {
+    ///     fn define_tags(&self) -> impl TagDefinition<'cfg> {
+    ///         StandardInfer::INSTANCE.define_tags()
+    ///     }
+    ///
+    ///     fn define_methods(&self) -> impl MethodDefinition<'cfg> {
+    ///         define_methods()
+    ///             .when(tag("card"), CardInfer)
+    ///             .apply_from(StandardInfer::INSTANCE)
+    ///     }
+    /// }
+    ///
+    /// let out = mintyml::convert(
+    ///     "card {\n  > Title\n  > Body\n}",
+    ///     OutputConfig::new().inference(DesignSystem),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, "<card><header>Title</header> <section>Body</section></card>");
+    /// ```
+    pub fn inference(self, method: impl for<'cfg> Infer<'cfg> + Send + Sync) -> Self {
+        self.update(|c| c.inference = Some(CustomInference::new(method)))
+    }
+
+    /// Whether a comment noting the number of errors should be added to the end of best-effort
+    /// output when a conversion fails, as with [`convert_forgiving`](crate::convert_forgiving).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let (out, _err) = mintyml::convert_forgiving(
+    ///     "div { Hello",
+    ///     OutputConfig::new().annotate_errors_in_output(true),
+    /// ).unwrap_err();
+    ///
+    /// assert_eq!(
+    ///     out.unwrap(),
+    ///     "<div><p>Hello</p></div> <!-- mintyml: 1 error, output may be incomplete -->",
+    /// );
+    /// ```
+    pub fn annotate_errors_in_output(self, enable: bool) -> Self {
+        self.update(|c| c.annotate_errors_in_output = Some(enable))
+    }
+
+    /// Whether the cells in the first row of a table should be inferred as `th` rather than `td`.
+    /// Cells in a `thead` are always inferred as `th`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(r#"
+    /// table {
+    ///     {
+    ///         > Name
+    ///         > Age
+    ///     }
+    ///     {
+    ///         > Ann
+    ///         > 30
+    ///     }
+    /// }
+    /// "#, OutputConfig::new().table_header_row(true)).unwrap();
+    ///
+    /// assert_eq!(out, concat!(
+    ///     "<table><tr><th>Name</th> <th>Age</th></tr>",
+    ///     " <tr><td>Ann</td> <td>30</td></tr></table>",
+    /// ));
+    /// ```
+    pub fn table_header_row(self, enable: bool) -> Self {
+        self.update(|c| c.table_header_row = Some(enable))
+    }
+
+    /// Sets additional `(open, close)` delimiter pairs whose contents should be copied to the
+    /// output verbatim and unescaped, bypassing MinTyML syntax, like template expressions.
+    ///
+    /// `{{ ... }}`, `{% ... %}`, `<% ... %>`, and `<? ... ?>` are always treated this way.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "p> Total: [[ a > b ]]",
+    ///     OutputConfig::new().opaque_spans([("[[", "]]")]),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, "<p>Total: [[ a > b ]]</p>");
+    /// ```
+    pub fn opaque_spans<O: Into<Src<'src>>, C: Into<Src<'src>>>(
+        self,
+        spans: impl IntoIterator<Item = (O, C)>,
+    ) -> Self {
+        self.update(|c| {
+            c.opaque_spans = Some(
+                spans
+                    .into_iter()
+                    .map(|(open, close)| (open.into(), close.into()))
+                    .collect(),
+            )
+        })
+    }
+
+    /// Wraps each `table` element in an element described by the given selector, like
+    /// `div.table-wrap`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "table { > > Cell }",
+    ///     OutputConfig::new().table_wrapper("div.table-wrap"),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, r#"<div class="table-wrap"><table><tr><td>Cell</td></tr></table></div>"#);
+    /// ```
+    pub fn table_wrapper(self, selector: impl Into<Src<'src>>) -> Self {
+        self.update(|c| c.table_wrapper = Some(selector.into()))
+    }
+
+    /// Sets whether a space is written between adjacent inline special elements like `<#a#>` and
+    /// `</b/>`, including inline element chains like `<(b> a)>`. Defaults to
+    /// [`SpacesPolicy::Preserve`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::{OutputConfig, SpacesPolicy};
+    /// let out = mintyml::convert(
+    ///     "<#bold#></italic/>",
+    ///     OutputConfig::new().adjacent_inline_special_space(SpacesPolicy::Always),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, "<p><strong>bold</strong> <em>italic</em></p>");
+    /// ```
+    pub fn adjacent_inline_special_space(self, policy: SpacesPolicy) -> Self {
+        self.update(|c| c.adjacent_inline_special_space = Some(policy))
+    }
+
+    /// Whether attributes should be written in sorted order after the ID and classes, regardless
+    /// of whether `xml` is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "a.link[title=x href=y]#home> Home",
+    ///     OutputConfig::new().sort_attributes(true),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, r#"<a id="home" class="link" href="y" title="x">Home</a>"#);
+    /// ```
+    pub fn sort_attributes(self, enable: bool) -> Self {
+        self.update(|c| c.sort_attributes = Some(enable))
+    }
+
+    /// Sets which elements receive the `lang` and `dir` attributes. Defaults to
+    /// [`LangScope::TopLevel`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::{LangScope, OutputConfig};
+    /// let out = mintyml::convert(
+    ///     "p> One\n\np> Two",
+    ///     OutputConfig::new().lang("en").lang_scope(LangScope::RootOnly),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, r#"<div lang="en"><p>One</p> <p>Two</p></div>"#);
+    /// ```
+    pub fn lang_scope(self, scope: LangScope) -> Self {
+        self.update(|c| c.lang_scope = Some(scope))
+    }
+
+    /// Whether the children of elements with the reserved `mty-sort` attribute should be sorted
+    /// by ID.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "ul[mty-sort] {\n    #c> C\n    #a> A\n    #b> B\n}",
+    ///     OutputConfig::new().sort_siblings_by_id(true),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, r#"<ul><li id="a">A</li> <li id="b">B</li> <li id="c">C</li></ul>"#);
+    /// ```
+    pub fn sort_siblings_by_id(self, enable: bool) -> Self {
+        self.update(|c| c.sort_siblings_by_id = Some(enable))
+    }
+
+    /// Whether attributes that are meaningless without a value, like `href` and `src`, should be
+    /// reported as errors when given none.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let config = OutputConfig::new().strict_attributes(true);
+    ///
+    /// assert!(mintyml::convert("a[href]> Home", &config).is_err());
+    /// assert!(mintyml::convert("input[disabled]>", &config).is_ok());
+    /// ```
+    pub fn strict_attributes(self, enable: bool) -> Self {
+        self.update(|c| c.strict_attributes = Some(enable))
+    }
+
+    /// Sets additional attribute names that `strict_attributes` requires to have a value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let config = OutputConfig::new()
+    ///     .strict_attributes(true)
+    ///     .value_required_attributes(["hx-get"]);
+    ///
+    /// assert!(mintyml::convert("button[hx-get]> Load", config).is_err());
+    /// ```
+    pub fn value_required_attributes<T: Into<Src<'src>>>(
+        self,
+        names: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.update(|c| {
+            c.value_required_attributes = Some(names.into_iter().map(Into::into).collect())
+        })
+    }
+
+    /// Sets `(property, content)` pairs, like `("og:title", "My Page")`, each added to the `head`
+    /// created by `complete_page` as a `<meta property="..." content="...">` element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "p> Hi",
+    ///     OutputConfig::new()
+    ///         .complete_page(true)
+    ///         .doctype("")
+    ///         .open_graph([("og:title", "Hi")]),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, concat!(
+    ///     r#"<html><head><meta property="og:title" content="Hi"></head>"#,
+    ///     r#" <body><p>Hi</p></body></html>"#,
+    /// ));
+    /// ```
+    pub fn open_graph<P: Into<Src<'src>>, C: Into<Src<'src>>>(
+        self,
+        properties: impl IntoIterator<Item = (P, C)>,
+    ) -> Self {
+        self.update(|c| {
+            c.open_graph = Some(
+                properties
+                    .into_iter()
+                    .map(|(property, content)| (property.into(), content.into()))
+                    .collect(),
+            )
+        })
+    }
+
+    /// Calls `f` for each element after inference to compute `(name, value)` attributes to add to
+    /// it. Attributes the element already has are left alone, and if `f` adds the same name more
+    /// than once, only the first is kept. Names are compared case-insensitively.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "> One\n> Two",
+    ///     OutputConfig::new().root_element("ol").element_visitor(|element, attributes| {
+    ///         if element.tag() == Some("li") {
+    ///             attributes.push(("value".into(), (element.index() + 1).to_string()));
+    ///         }
+    ///     }),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, r#"<ol><li value="1">One</li> <li value="2">Two</li></ol>"#);
+    /// ```
+    pub fn element_visitor(
+        self,
+        f: impl Fn(&ElementRef, &mut Vec<(String, String)>) + Send + Sync + 'static,
+    ) -> Self {
+        self.update(|c| c.element_visitor = Some(ElementVisitor::new(f)))
+    }
+
+    /// Whether bare `http://` and `https://` URLs in text should be wrapped in `a` elements
+    /// linking to them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "p> See https://example.com.",
+    ///     OutputConfig::new().autolink(true),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(
+    ///     out,
+    ///     r#"<p>See <a href="https://example.com">https://example.com</a>.</p>"#,
+    /// );
+    /// ```
+    pub fn autolink(self, enable: bool) -> Self {
+        self.update(|c| c.autolink = Some(enable))
+    }
+
+    /// Calls `f` once with each tag name as it's written. Returning `Some` replaces the tag in the
+    /// opening tag and in the closing tag, if one is written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "div> Hello",
+    ///     OutputConfig::new().tag_rewrite(|tag| (tag == "div").then_some("ui-box".into())),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, "<ui-box>Hello</ui-box>");
+    /// ```
+    pub fn tag_rewrite(self, f: impl Fn(&str) -> Option<Cow<str>> + Send + Sync + 'static) -> Self {
+        self.update(|c| c.tag_rewrite = Some(TagRewrite::new(f)))
+    }
+
+    /// Whether the output should be well-formed XHTML5 that strict XML parsers accept.
+    /// This enables everything `xml` does, and also adds the XHTML namespace to top-level `html`
+    /// elements without an `xmlns` attribute.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "p> Hi <(br>)>\ninput[disabled]>",
+    ///     OutputConfig::new().complete_page(true).xhtml_strict(true),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, concat!(
+    ///     r#"<html xmlns="http://www.w3.org/1999/xhtml"><head/>"#,
+    ///     r#" <body><p>Hi <br/></p> <input disabled=""/></body></html>"#,
+    /// ));
+    /// ```
+    pub fn xhtml_strict(self, enable: bool) -> Self {
+        self.update(|c| c.xhtml_strict = Some(enable))
+    }
+
+    /// Whether comments should be written to the output. Defaults to `true`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "p> Hello <! draft !> world",
+    ///     OutputConfig::new().emit_comments(false),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, "<p>Hello world</p>");
+    /// ```
+    pub fn emit_comments(self, enable: bool) -> Self {
+        self.update(|c| c.emit_comments = Some(enable))
+    }
+
+    /// Whether the output should end with a line break, regardless of `indent`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert("p> Hello", OutputConfig::new().final_newline(true)).unwrap();
+    /// assert_eq!(out, "<p>Hello</p>\n");
+    ///
+    /// let out = mintyml::convert(
+    ///     "p> Hello",
+    ///     OutputConfig::new().indent("  ").final_newline(false),
+    /// ).unwrap();
+    /// assert_eq!(out, "<p>Hello</p>");
+    /// ```
+    pub fn final_newline(self, enable: bool) -> Self {
+        self.update(|c| c.final_newline = Some(enable))
+    }
+
+    /// Defines variables that unquoted attribute values can refer to as `$name`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "a[href=$base/about]> About",
+    ///     OutputConfig::new().variables([("base", "https://example.com")]),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, r#"<a href="https://example.com/about">About</a>"#);
+    /// ```
+    pub fn variables<N: Into<Src<'src>>, V: Into<Src<'src>>>(
+        self,
+        variables: impl IntoIterator<Item = (N, V)>,
+    ) -> Self {
+        self.update(|c| {
+            c.variables = Some(
+                variables
+                    .into_iter()
+                    .map(|(name, value)| (name.into(), value.into()))
+                    .collect(),
+            )
+        })
+    }
+
+    /// Declares `(prefix, uri)` namespaces on the root element in XML mode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "svg> xlink:use[xlink:href=#icon]>",
+    ///     OutputConfig::new()
+    ///         .xml(true)
+    ///         .namespaces([("xlink", "http://www.w3.org/1999/xlink")]),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, concat!(
+    ///     r#"<svg xmlns:xlink="http://www.w3.org/1999/xlink">"#,
+    ///     r##"<xlink:use xlink:href="#icon"/></svg>"##,
+    /// ));
+    /// ```
+    pub fn namespaces<P: Into<Src<'src>>, U: Into<Src<'src>>>(
+        self,
+        namespaces: impl IntoIterator<Item = (P, U)>,
+    ) -> Self {
+        self.update(|c| {
+            c.namespaces = Some(
+                namespaces
+                    .into_iter()
+                    .map(|(prefix, uri)| (prefix.into(), uri.into()))
+                    .collect(),
+            )
+        })
+    }
+
+    /// Whether a forgiving conversion should produce no output if the document has unclosed,
+    /// misplaced, or mismatched delimiters. Defaults to `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let (out, _) = mintyml::convert_forgiving("p> <# a", OutputConfig::new()).unwrap_err();
+    /// assert_eq!(out.as_deref(), Some("<p><strong>a</strong></p>"));
+    ///
+    /// let (out, _) =
+    ///     mintyml::convert_forgiving("p> <# a", OutputConfig::new().strict(true)).unwrap_err();
+    /// assert_eq!(out, None);
+    /// ```
+    pub fn strict(self, enable: bool) -> Self {
+        self.update(|c| c.strict = Some(enable))
+    }
+
+    /// Whether boolean attributes like `disabled` and `checked` should be written without a
+    /// value when their value is empty or matches their name. Has no effect when `xml` is
+    /// enabled. Defaults to `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "input[disabled=disabled checked=\"\"]>",
+    ///     OutputConfig::new().minimize_boolean_attributes(true),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, "<input disabled checked>");
+    /// ```
+    pub fn minimize_boolean_attributes(self, enable: bool) -> Self {
+        self.update(|c| c.minimize_boolean_attributes = Some(enable))
+    }
+
+    /// Sets additional attribute names, matched case-insensitively, that
+    /// `minimize_boolean_attributes` treats as boolean attributes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "div[x-cloak=x-cloak]>",
+    ///     OutputConfig::new()
+    ///         .minimize_boolean_attributes(true)
+    ///         .boolean_attributes(["x-cloak"]),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, "<div x-cloak></div>");
+    /// ```
+    pub fn boolean_attributes<T: Into<Src<'src>>>(
+        self,
+        names: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.update(|c| c.boolean_attributes = Some(names.into_iter().map(Into::into).collect()))
+    }
+
+    /// Whether the space between adjacent elements on the same line should be written.
+    /// Defaults to `true`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "nav> <(a> One)> <(a> Two)>",
+    ///     OutputConfig::new().preserve_inline_space(false),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, "<nav><a>One</a><a>Two</a></nav>");
+    /// ```
+    pub fn preserve_inline_space(self, enable: bool) -> Self {
+        self.update(|c| c.preserve_inline_space = Some(enable))
+    }
+
+    /// Whether every non-ASCII character in text and attribute values should be written as a
+    /// numeric character reference. Defaults to `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert("p> Café", OutputConfig::new().ascii_only(true)).unwrap();
+    ///
+    /// assert_eq!(out, "<p>Caf&#233;</p>");
+    /// ```
+    pub fn ascii_only(self, enable: bool) -> Self {
+        self.update(|c| c.ascii_only = Some(enable))
+    }
+
+    /// Whether a class given more than once to an element should only be written the first time.
+    /// Defaults to `true`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let src = "div.a.b.a> Hi";
+    ///
+    /// let out = mintyml::convert(src, OutputConfig::new()).unwrap();
+    /// assert_eq!(out, r#"<div class="a b">Hi</div>"#);
+    ///
+    /// let out = mintyml::convert(src, OutputConfig::new().dedup_classes(false)).unwrap();
+    /// assert_eq!(out, r#"<div class="a b a">Hi</div>"#);
+    /// ```
+    pub fn dedup_classes(self, enable: bool) -> Self {
+        self.update(|c| c.dedup_classes = Some(enable))
+    }
+
+    /// Whether inline code should be wrapped in the code block container, as code blocks are.
+    /// Defaults to `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let src = "p> Run <`make`>.";
+    ///
+    /// let out = mintyml::convert(src, OutputConfig::new()).unwrap();
+    /// assert_eq!(out, "<p>Run <code>make</code>.</p>");
+    ///
+    /// let out = mintyml::convert(src, OutputConfig::new().inline_code_in_pre(true)).unwrap();
+    /// assert_eq!(out, "<p>Run <pre><code>make</code></pre>.</p>");
+    /// ```
+    pub fn inline_code_in_pre(self, enable: bool) -> Self {
+        self.update(|c| c.inline_code_in_pre = Some(enable))
+    }
+
+    /// Sets which element receives the classes and ID of an element that inference gives more
+    /// than one tag. Defaults to [`ClassPlacement::Outermost`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::{inference::*, ClassPlacement, OutputConfig};
+    /// #[derive(Debug)]
+    /// struct MenuInfer;
+    ///
+    /// impl<'cfg> Infer<'cfg> for MenuInfer {
+    ///     fn define_tags(&self) -> impl TagDefinition<'cfg> {
+    ///         define_tags().default(["li", "a"])
+    ///     }
+    /// }
+    ///
+    /// let config = OutputConfig::new()
+    ///     .inference(MenuInfer)
+    ///     .inferred_class_placement(ClassPlacement::Innermost);
+    /// let out = mintyml::convert(".active> Home", config).unwrap();
+    ///
+    /// assert_eq!(out, r#"<li><a class="active">Home</a></li>"#);
+    /// ```
+    pub fn inferred_class_placement(self, placement: ClassPlacement) -> Self {
+        self.update(|c| c.inferred_class_placement = Some(placement))
+    }
+
+    /// Whether lines indented inconsistently with tabs and spaces should be reported as errors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let src = "div {\n    p> One\n\tp> Two\n}";
+    ///
+    /// assert!(mintyml::convert(src, OutputConfig::new()).is_ok());
+    /// assert!(mintyml::convert(src, OutputConfig::new().check_indentation(true)).is_err());
+    /// ```
+    pub fn check_indentation(self, enable: bool) -> Self {
+        self.update(|c| c.check_indentation = Some(enable))
+    }
+
+    /// Sets replacements for characters in text and attribute values, which take precedence over
+    /// the built-in escapes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let out = mintyml::convert(
+    ///     "p> 1\u{a0}km & more",
+    ///     OutputConfig::new().extra_escapes([('\u{a0}', "&nbsp;")]),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(out, "<p>1&nbsp;km &amp; more</p>");
+    /// ```
+    pub fn extra_escapes<V: Into<Src<'src>>>(
+        self,
+        escapes: impl IntoIterator<Item = (char, V)>,
+    ) -> Self {
+        self.update(|c| {
+            c.extra_escapes = Some(
+                escapes
+                    .into_iter()
+                    .map(|(ch, value)| (ch, value.into()))
+                    .collect(),
+            )
+        })
+    }
+
+    /// Whether multiline text should have its first and last lines removed and its indentation
+    /// normalized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let src = "pre {\n    '''\n      x\n    '''\n}";
+    ///
+    /// let out = mintyml::convert(src, OutputConfig::new()).unwrap();
+    /// assert_eq!(out, "<pre>  x</pre>");
+    ///
+    /// let out = mintyml::convert(src, OutputConfig::new().trim_multiline(false)).unwrap();
+    /// assert_eq!(out, "<pre>&NewLine;      x&NewLine;    </pre>");
+    /// ```
+    pub fn trim_multiline(self, enable: bool) -> Self {
+        self.update(|c| c.trim_multiline = Some(enable))
+    }
+
+    /// Whether elements inside ones that can't contain them should be reported as errors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let src = "p> <(div> Hello)>";
+    ///
+    /// assert!(mintyml::convert(src, OutputConfig::new()).is_ok());
+    /// assert!(mintyml::convert(src, OutputConfig::new().strict_nesting(true)).is_err());
+    /// ```
+    pub fn strict_nesting(self, enable: bool) -> Self {
+        self.update(|c| c.strict_nesting = Some(enable))
+    }
+
+    /// Sets which empty elements are left out of the output.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::{DropEmptyPolicy, OutputConfig};
+    /// let src = "div>\n\n>\n\n.empty>";
+    ///
+    /// let out = mintyml::convert(src, OutputConfig::new().drop_empty(DropEmptyPolicy::Inferred));
+    /// assert_eq!(out.unwrap(), r#"<div></div> <p class="empty"></p>"#);
+    ///
+    /// let out = mintyml::convert(src, OutputConfig::new().drop_empty(DropEmptyPolicy::Always));
+    /// assert_eq!(out.unwrap(), r#"<p class="empty"></p>"#);
+    /// ```
+    pub fn drop_empty(self, policy: DropEmptyPolicy) -> Self {
+        self.update(|c| c.drop_empty = Some(policy))
+    }
+
+    /// Stops the conversion once more than `max` syntax and semantic errors have been found.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::{ConvertError, OutputConfig};
+    /// let src = "<`\\q`> <`\\q`> <`\\q`>";
+    /// let config = OutputConfig::new().strict_escapes(true).max_errors(2);
+    ///
+    /// let result = mintyml::convert(src, config);
+    /// let Err(ConvertError::Syntax { syntax_errors, truncated, .. }) = result else {
+    ///     panic!()
+    /// };
+    /// assert_eq!(syntax_errors.len(), 2);
+    /// assert!(truncated);
+    /// ```
+    pub fn max_errors(self, max: usize) -> Self {
+        self.update(|c| c.max_errors = Some(max))
+    }
+
+    /// Whether an ID given to more than one element should be reported as an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mintyml::OutputConfig;
+    /// let src = "p#intro> One\n\np[id=intro]> Two";
+    ///
+    /// assert!(mintyml::convert(src, OutputConfig::new()).is_ok());
+    /// assert!(mintyml::convert(src, OutputConfig::new().check_duplicate_ids(true)).is_err());
+    /// ```
+    pub fn check_duplicate_ids(self, enable: bool) -> Self {
+        self.update(|c| c.check_duplicate_ids = Some(enable))
+    }
 }
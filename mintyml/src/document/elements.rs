@@ -5,11 +5,17 @@ use core::mem;
 use alloc::{vec, vec::Vec};
 
 use derive_more::Display;
-use gramma::parse::LocationRange;
+use gramma::parse::{Location, LocationRange};
 
-use crate::{ast, error::UnclosedDelimiterKind, utils::default};
+use crate::{
+    ast,
+    error::{SyntaxError, SyntaxErrorKind, UnclosedDelimiterKind},
+    utils::default,
+};
 
-use super::{BuildContext, BuildResult, Content, Node, NodeType, Selector, TextSlice};
+use super::{
+    BuildContext, BuildResult, Content, Node, NodeType, Selector, SelectorItem, TextSlice,
+};
 
 pub use self::into_tags::IntoTags;
 
@@ -85,7 +91,32 @@ impl<'cfg> Element<'cfg> {
         self.is_raw
     }
 
-    fn split_at(&mut self, selector_index: usize) {
+    /// The tag of the first selector of this element, or `None` if it has no tag or no selectors,
+    /// as is the case for dissolved elements.
+    pub fn resolved_tag<'a>(&'a self, src: &'a str) -> Option<&'a str> {
+        Some(self.selectors.first()?.tag.name()?.as_str(src))
+    }
+
+    /// The range of this element in the source, including its selectors and delimiters.
+    pub fn full_span(&self) -> LocationRange {
+        self.range
+    }
+
+    /// The range of this element's content in the source, excluding its selectors and
+    /// delimiters. This is an empty range if the element has no content, as with void elements.
+    pub fn content_span(&self) -> LocationRange {
+        let range = self.content.range;
+        if self.content.nodes.is_empty() {
+            LocationRange {
+                start: range.start,
+                end: range.start,
+            }
+        } else {
+            range
+        }
+    }
+
+    pub(crate) fn split_at(&mut self, selector_index: usize) {
         let new_selectors = self.selectors.split_off(selector_index);
         let selector_start = new_selectors[0].range.start;
 
@@ -110,6 +141,24 @@ impl<'cfg> Element<'cfg> {
         self.format_inline = true;
     }
 
+    /// Moves the classes and ID of an element whose tags were all inferred from its first
+    /// selector to its last, so they end up on the innermost element once it's split.
+    pub(crate) fn move_inferred_classes_to_last(&mut self) {
+        let [first, .., last] = &mut self.selectors[..] else {
+            return;
+        };
+        if !(first.inferred && last.inferred) {
+            return;
+        }
+
+        // Selectors added by inference are empty, so only the first can hold classes.
+        let (moved, kept): (Vec<_>, _) = mem::take(&mut first.items)
+            .into_iter()
+            .partition(|item| matches!(item, SelectorItem::Class { .. } | SelectorItem::Id { .. }));
+        first.items = kept;
+        last.items.extend(moved);
+    }
+
     pub fn split_first(&mut self) -> bool {
         if self.selectors.len() > 1 {
             self.split_at(1);
@@ -133,6 +182,19 @@ impl<'cfg> Element<'cfg> {
     }
 
     pub fn apply_tags(&mut self, tags: impl IntoIterator<Item = TextSlice<'cfg>>) {
+        self.apply_tags_inner(tags, false)
+    }
+
+    /// Like [`apply_tags`](Self::apply_tags), but marks the new tags as assigned by inference.
+    pub(crate) fn apply_inferred_tags(&mut self, tags: impl IntoIterator<Item = TextSlice<'cfg>>) {
+        self.apply_tags_inner(tags, true)
+    }
+
+    fn apply_tags_inner(
+        &mut self,
+        tags: impl IntoIterator<Item = TextSlice<'cfg>>,
+        inferred: bool,
+    ) {
         let mut tags = tags.into_iter().filter(|t| !t.is_empty());
         let Some(first) = tags.next() else {
             if !self.selectors.is_empty() {
@@ -146,21 +208,34 @@ impl<'cfg> Element<'cfg> {
         if let Some(selector) = self.selectors.first_mut() {
             selector_location = selector.range.end;
             selector.tag = first.into();
+            selector.inferred = inferred;
         } else {
             selector_location = self.range.start;
-            self.selectors
-                .push(Selector::empty(selector_location).with_tag(first))
+            self.selectors.push(Selector {
+                inferred,
+                ..Selector::empty(selector_location).with_tag(first)
+            })
         }
 
         self.selectors.splice(
             1..1,
-            tags.map(|t| Selector::empty(selector_location).with_tag(t)),
+            tags.map(|t| Selector {
+                inferred,
+                ..Selector::empty(selector_location).with_tag(t)
+            }),
         );
     }
     pub fn with_tag<M>(mut self, tags: impl IntoTags<'cfg, M>) -> Self {
         self.apply_tags(tags.tags());
         self
     }
+
+    /// Sets the selectors of this element, making it raw if any of them were marked raw.
+    pub(crate) fn with_selectors(mut self, selectors: Vec<Selector<'cfg>>) -> Self {
+        self.is_raw |= selectors.iter().any(|s| s.raw);
+        self.selectors = selectors;
+        self
+    }
 }
 
 /// The type of a special element.
@@ -202,7 +277,11 @@ pub enum MultilineKind {
     Unescaped {},
     /// Backticks
     #[non_exhaustive]
-    Code {},
+    #[display(fmt = "Code")]
+    Code {
+        /// The language named after the opening backticks, like `rust` in ` ```rust `.
+        language: Option<LocationRange>,
+    },
 }
 
 impl<'cfg> From<Element<'cfg>> for Node<'cfg> {
@@ -214,6 +293,101 @@ impl<'cfg> From<Element<'cfg>> for Node<'cfg> {
     }
 }
 
+/// The opening and closing delimiters of inline elements, with the kind of special element they
+/// delimit, if any.
+const INLINE_DELIMITERS: [(&str, &str, Option<SpecialKind>); 6] = [
+    ("<(", ")>", None),
+    ("</", "/>", Some(SpecialKind::Emphasis)),
+    ("<#", "#>", Some(SpecialKind::Strong)),
+    ("<_", "_>", Some(SpecialKind::Underline)),
+    ("<~", "~>", Some(SpecialKind::Strike)),
+    ("<\"", "\">", Some(SpecialKind::Quote)),
+];
+
+fn delimiter_kind(special: Option<SpecialKind>) -> UnclosedDelimiterKind {
+    match special {
+        Some(kind) => UnclosedDelimiterKind::SpecialInline { kind },
+        None => UnclosedDelimiterKind::Inline {},
+    }
+}
+
+/// Returns the kind and range of the inline closing delimiter at `position` in `src`, if any,
+/// skipping any spaces before it.
+fn closer_at(src: &str, position: usize) -> Option<(UnclosedDelimiterKind, LocationRange)> {
+    let rest = src.get(position..)?;
+    let start = position + (rest.len() - rest.trim_start_matches([' ', '\t']).len());
+    let (_, closer, kind) = INLINE_DELIMITERS
+        .iter()
+        .find(|(_, closer, _)| src[start..].starts_with(closer))?;
+    let range = LocationRange {
+        start: Location { position: start },
+        end: Location {
+            position: start + closer.len(),
+        },
+    };
+    Some((delimiter_kind(*kind), range))
+}
+
+fn mismatched(
+    expected: UnclosedDelimiterKind,
+    (found, range): (UnclosedDelimiterKind, LocationRange),
+) -> SyntaxError {
+    SyntaxError {
+        range,
+        kind: SyntaxErrorKind::MismatchedDelimiter { expected, found },
+    }
+}
+
+/// Looks for an inline closing delimiter before `end` that doesn't match the innermost opening
+/// delimiter, which is a likely cause of a parse failure at `end`.
+///
+/// This only approximates the grammar: escapes, inline code, verbatim segments, and comments are
+/// skipped, but other delimiters are counted wherever they appear.
+pub(crate) fn find_mismatched_delimiter(src: &str, end: usize) -> Option<SyntaxError> {
+    let mut stack = Vec::new();
+    let mut position = 0;
+
+    while position <= end.min(src.len().saturating_sub(1)) {
+        let rest = &src[position..];
+        let skip_to = |close: &str| rest.find(close).map(|i| i + close.len());
+
+        let skip = match rest {
+            _ if rest.starts_with('\\') => rest.char_indices().nth(2).map(|(i, _)| i),
+            _ if rest.starts_with("<`") => skip_to("`>"),
+            _ if rest.starts_with("<[") => skip_to("]>"),
+            _ if rest.starts_with("<!") => skip_to("!>"),
+            _ => None,
+        };
+        if let Some(skip) = skip {
+            position += skip;
+            continue;
+        }
+
+        if let Some((_, _, kind)) = INLINE_DELIMITERS
+            .iter()
+            .find(|(open, ..)| rest.starts_with(open))
+        {
+            stack.push(delimiter_kind(*kind));
+            position += 2;
+            continue;
+        }
+
+        let closer = closer_at(src, position).filter(|(_, r)| r.start.position == position);
+        if let Some(found) = closer {
+            match stack.pop() {
+                Some(expected) if expected != found.0 => return Some(mismatched(expected, found)),
+                _ => {}
+            }
+            position += 2;
+            continue;
+        }
+
+        position += rest.chars().next().map_or(1, char::len_utf8);
+    }
+
+    None
+}
+
 impl<'cfg> BuildContext<'_, 'cfg> {
     fn build_inline_special(
         &mut self,
@@ -253,7 +427,11 @@ impl<'cfg> BuildContext<'_, 'cfg> {
         };
 
         if is_unclosed {
-            self.unclosed(open, UnclosedDelimiterKind::SpecialInline { kind })?;
+            let expected = UnclosedDelimiterKind::SpecialInline { kind };
+            match closer_at(self.src, range.end.position) {
+                Some(found) => self.errors.syntax([mismatched(expected, found)])?,
+                None => self.unclosed(open, expected)?,
+            }
         }
 
         Ok(Element {
@@ -360,6 +538,23 @@ impl<'cfg> BuildContext<'_, 'cfg> {
         })
     }
 
+    /// Finds the language token following the backticks that open a code block.
+    fn code_language(&self, open: LocationRange) -> Option<LocationRange> {
+        let hint = &open.slice(self.src)["```".len()..];
+        let offset = hint.len() - hint.trim_start().len();
+        let len = hint[offset..]
+            .find(char::is_whitespace)
+            .unwrap_or(hint.len() - offset);
+
+        (len > 0).then(|| {
+            let start = open.start + "```".len() + offset;
+            LocationRange {
+                start,
+                end: start + len,
+            }
+        })
+    }
+
     pub fn build_multiline(
         &mut self,
         range: LocationRange,
@@ -374,12 +569,14 @@ impl<'cfg> BuildContext<'_, 'cfg> {
         let kind = match ast {
             Escaped { .. } => MultilineKind::Escaped {},
             Unescaped { .. } => MultilineKind::Unescaped {},
-            Code { .. } => MultilineKind::Code {},
+            Code { open, .. } => MultilineKind::Code {
+                language: self.code_language(open.range),
+            },
         };
 
         let unescape_in = match kind {
             MultilineKind::Escaped {} => true,
-            MultilineKind::Unescaped {} | MultilineKind::Code {} => false,
+            MultilineKind::Unescaped {} | MultilineKind::Code { .. } => false,
         };
 
         let inner_range = LocationRange { start, end };
@@ -404,3 +601,45 @@ impl<'cfg> BuildContext<'_, 'cfg> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{document::Document, error::Errors, inference::engine::infer, OutputConfig};
+
+    use super::*;
+
+    #[test]
+    fn resolved_tag() {
+        let src = "ul {\n    foo\n}";
+        let config = OutputConfig::new();
+        let mut errors = Errors::new(&config);
+        let mut document = Document::parse(src, &config, &mut errors).unwrap();
+        infer(src, &mut document.content, None, default());
+
+        let list = document.content.nodes[0].as_element_mut().unwrap();
+        assert_eq!(list.resolved_tag(src), Some("ul"));
+
+        let item = list.content.nodes[0].as_element_mut().unwrap();
+        assert_eq!(item.resolved_tag(src), Some("li"));
+
+        let dissolved = Element::new(LocationRange::INVALID, ElementType::Unknown {});
+        assert_eq!(dissolved.resolved_tag(src), None);
+    }
+
+    #[test]
+    fn spans() {
+        let src = "div { hello }
+img>";
+        let config = OutputConfig::new();
+        let mut errors = Errors::new(&config);
+        let document = Document::parse(src, &config, &mut errors).unwrap();
+        let elements = Vec::from_iter(document.content.nodes.iter().filter_map(|n| n.as_element()));
+
+        assert_eq!(elements[0].full_span().slice(src), "div { hello }");
+        assert_eq!(elements[0].content_span().slice(src), "hello ");
+
+        assert_eq!(elements[1].full_span().slice(src), "img>");
+        let content = elements[1].content_span();
+        assert_eq!(content.start, content.end);
+    }
+}
@@ -119,9 +119,9 @@ impl<'cfg> BuildContext<'_, 'cfg> {
                                 block: node_range,
                             }
                             .into(),
-                            selectors: mem::take(selectors),
                             ..self.build_block(node_range, value, false)?
                         }
+                        .with_selectors(mem::take(selectors))
                         .into(),
                     );
                     return Ok(());
@@ -155,7 +155,6 @@ impl<'cfg> BuildContext<'_, 'cfg> {
 
         out_nodes.push(
             Element {
-                selectors: mem::take(selectors),
                 content: Content {
                     range: content_range,
                     nodes: children,
@@ -167,6 +166,7 @@ impl<'cfg> BuildContext<'_, 'cfg> {
                     },
                 )
             }
+            .with_selectors(mem::take(selectors))
             .into(),
         );
         Ok(())
@@ -210,9 +210,10 @@ impl<'cfg> BuildContext<'_, 'cfg> {
                     },
                 )?,
                 ast::NodeType::Text {
-                    text: ast::InlineText::Comment { comment },
+                    text:
+                        text @ (ast::InlineText::Comment { .. } | ast::InlineText::RawComment { .. }),
                 } => {
-                    out_nodes.push(self.build_comment_node(comment)?);
+                    out_nodes.push(self.build_inline_text(text)?);
 
                     return self.post_selector(
                         prefix_range,
@@ -236,8 +237,9 @@ impl<'cfg> BuildContext<'_, 'cfg> {
                 ast::NodeType::Element {
                     element: ast::Element::Block { value },
                 } => {
-                    let mut element = self.build_block(prefix_range, value, true)?;
-                    element.selectors = mem::take(selectors);
+                    let element = self
+                        .build_block(prefix_range, value, true)?
+                        .with_selectors(mem::take(selectors));
                     out_nodes.push(element.into());
                     return Ok(());
                 }
@@ -3,17 +3,18 @@ mod line;
 mod selectors;
 mod text;
 
-use alloc::{vec, vec::Vec};
+use alloc::{borrow::Cow, format, vec, vec::Vec};
 
 use gramma::parse::{Location, LocationRange};
 
 use crate::{
     ast,
     error::{
-        Errors, InternalError, InternalResult, ItemType, MisplacedKind, SyntaxError,
-        SyntaxErrorKind, UnclosedDelimiterKind,
+        Errors, InternalError, InternalResult, ItemType, MisplacedKind, SemanticError,
+        SemanticErrorKind, SyntaxError, SyntaxErrorKind, UnclosedDelimiterKind,
     },
     escape::escape_errors,
+    OutputConfig, Src,
 };
 
 pub use elements::*;
@@ -79,6 +80,15 @@ impl<'cfg> Node<'cfg> {
             }
         )
     }
+
+    pub fn is_comment(&self) -> bool {
+        matches!(
+            self.node_type,
+            NodeType::TextLike {
+                text_like: TextLike::Comment { .. },
+            }
+        )
+    }
 }
 
 #[non_exhaustive]
@@ -110,6 +120,10 @@ struct BuildContext<'cx, 'cfg> {
     pub src: &'cfg str,
     /// All syntax errors found while building so far.
     pub errors: &'cx mut Errors,
+    /// Whether to validate escape sequences even where they aren't processed.
+    pub strict_escapes: bool,
+    /// Whether to report lines indented inconsistently with tabs and spaces.
+    pub check_indentation: bool,
 }
 
 impl<'cfg> BuildContext<'_, 'cfg> {
@@ -119,12 +133,13 @@ impl<'cfg> BuildContext<'_, 'cfg> {
     }
 
     /// Extracts a slice of the source, validating any escape sequences within.
+    /// If `strict_escapes` is set, escape sequences are validated even if `escape` is false.
     fn escapable_slice(
         &mut self,
         range: LocationRange,
         escape: bool,
     ) -> BuildResult<TextSlice<'cfg>> {
-        if escape {
+        if escape || self.strict_escapes {
             self.errors
                 .syntax(escape_errors(range.slice(self.src), range.start))?;
         }
@@ -148,6 +163,36 @@ impl<'cfg> BuildContext<'_, 'cfg> {
         }])
     }
 
+    /// Reports the indentation of the line starting at `start` if it mixes tabs and spaces, or
+    /// uses a different one than `style`, the first indentation seen in the enclosing block.
+    fn check_indentation(&mut self, start: Location, style: &mut Option<char>) -> BuildResult {
+        let before = &self.src[..start.position];
+        let indent = &before[before.rfind('\n').map_or(0, |i| i + 1)..];
+
+        // The line doesn't start its own line of source, as in `div { p> ...`.
+        if !indent.chars().all(|c| c == ' ' || c == '\t') {
+            return Ok(());
+        }
+        let Some(first) = indent.chars().next() else {
+            return Ok(());
+        };
+
+        let style = *style.get_or_insert(first);
+        if indent.chars().all(|c| c == style) {
+            return Ok(());
+        }
+
+        self.errors.semantic([SemanticError {
+            range: LocationRange {
+                start: Location {
+                    position: start.position - indent.len(),
+                },
+                end: start,
+            },
+            kind: SemanticErrorKind::InconsistentIndentation {},
+        }])
+    }
+
     fn misplaced(&mut self, range: LocationRange, kind: MisplacedKind) -> BuildResult {
         self.errors.syntax([SyntaxError {
             range,
@@ -170,6 +215,7 @@ impl<'cfg> BuildContext<'_, 'cfg> {
         let mut node_buf = Vec::new();
         let range = LocationRange { start, end };
         let mut last_line_end = start;
+        let mut indent_style = None;
 
         for &ast::Line {
             start,
@@ -177,16 +223,21 @@ impl<'cfg> BuildContext<'_, 'cfg> {
             end,
         } in lines
         {
-            if nodes.is_empty() {
+            let line_range = LocationRange { start, end };
+            // A line with only a `\` ends the paragraph like an empty line would.
+            if nodes.is_empty() || line_range.slice(self.src).trim() == "\\" {
                 out_nodes.push(self.paragraph_end(last_line_end, end)?);
             } else {
+                if self.check_indentation {
+                    self.check_indentation(start, &mut indent_style)?;
+                }
                 let mut nodes = &nodes[..];
                 node_buf = self.build_line(&mut nodes, node_buf)?;
 
                 self.add_line(
                     &mut out_nodes,
                     &mut node_buf,
-                    LocationRange { start, end },
+                    line_range,
                     last_line_end,
                     form_paragraphs,
                 )?;
@@ -259,9 +310,15 @@ impl<'cfg> Document<'cfg> {
     pub(crate) fn from_ast(
         src: &'cfg str,
         ast: &ast::Document,
+        config: &OutputConfig,
         errors: &mut Errors,
     ) -> InternalResult<Self> {
-        let mut cx = BuildContext { src, errors };
+        let mut cx = BuildContext {
+            src,
+            errors,
+            strict_escapes: config.strict_escapes.unwrap_or(false),
+            check_indentation: config.check_indentation.unwrap_or(false),
+        };
         let content = cx.build_content(&ast.content, true)?;
 
         Ok(Self {
@@ -275,13 +332,156 @@ impl<'cfg> Document<'cfg> {
         })
     }
 
-    pub(crate) fn parse(src: &'cfg str, errors: &mut Errors) -> InternalResult<Self> {
-        match ast::parse(src) {
-            Ok(ast) => Self::from_ast(src, &ast, errors),
+    pub(crate) fn parse(
+        src: &'cfg str,
+        config: &OutputConfig,
+        errors: &mut Errors,
+    ) -> InternalResult<Self> {
+        let (masked, short_spans) = match config.opaque_spans {
+            Some(ref spans) => mask_opaque_spans(src, spans),
+            None => (src.into(), Vec::new()),
+        };
+
+        match ast::parse(&masked) {
+            Ok(ast) => {
+                let mut document = Self::from_ast(src, &ast, config, errors)?;
+                if !short_spans.is_empty() {
+                    split_short_opaque_spans(&mut document.content, &short_spans);
+                }
+                Ok(document)
+            }
             Err(e) => {
-                errors.syntax([e])?;
+                let error = elements::find_mismatched_delimiter(&masked, e.location.position)
+                    .unwrap_or_else(|| e.into());
+                errors.syntax([error])?;
                 return Err(InternalError);
             }
         }
     }
 }
+
+/// Replaces each span of `src` delimited by one of the `(open, close)` pairs in `spans` with an
+/// interpolation of the same length, so it's parsed as an opaque text node.
+///
+/// An interpolation needs at least four bytes for its delimiters, so shorter spans are filled with
+/// [`SHORT_SPAN_FILLER`] instead, which is parsed as ordinary text. Their ranges are returned so
+/// they can be split out of that text with [`split_short_opaque_spans`].
+///
+/// Nodes built from the result still refer to the original text through their ranges.
+fn mask_opaque_spans<'src>(
+    src: &'src str,
+    spans: &[(Src, Src)],
+) -> (Cow<'src, str>, Vec<LocationRange>) {
+    let find_open = |position: usize| {
+        spans
+            .iter()
+            .filter(|(open, close)| !open.is_empty() && !close.is_empty())
+            .filter_map(|(open, close)| {
+                let start = position + src[position..].find(&**open)?;
+                Some((start, start + open.len(), close))
+            })
+            .min_by_key(|&(start, ..)| start)
+    };
+
+    let mut out = Cow::Borrowed(src);
+    let mut short_spans = Vec::new();
+    let mut position = 0;
+
+    while let Some((start, body_start, close)) = find_open(position) {
+        let Some(end) = src[body_start..]
+            .find(&**close)
+            .map(|i| body_start + i + close.len())
+        else {
+            // Other spans may still be closed after this opener.
+            position = body_start;
+            continue;
+        };
+
+        let masked = if end - start >= 4 {
+            format!("{{{{{}}}}}", "x".repeat(end - start - 4))
+        } else {
+            short_spans.push(LocationRange {
+                start: Location { position: start },
+                end: Location { position: end },
+            });
+            SHORT_SPAN_FILLER.repeat(end - start)
+        };
+        out.to_mut().replace_range(start..end, &masked);
+
+        position = end;
+    }
+
+    (out, short_spans)
+}
+
+/// Fills opaque spans too short to be masked as interpolations. This is parsed as ordinary text,
+/// but unlike letters, it can't be mistaken for a selector.
+const SHORT_SPAN_FILLER: &str = "\u{1}";
+
+/// Splits each of the ranges in `spans` out of the text node containing it in `content` as an
+/// opaque text node, as though it had been parsed as an interpolation.
+fn split_short_opaque_spans(content: &mut Content, spans: &[LocationRange]) {
+    let nodes = core::mem::take(&mut content.nodes);
+    content.nodes.reserve(nodes.len());
+
+    for mut node in nodes {
+        let range = match node.node_type {
+            NodeType::Element { ref mut element } => {
+                split_short_opaque_spans(&mut element.content, spans);
+                None
+            }
+            NodeType::TextLike {
+                text_like:
+                    TextLike::Text {
+                        text:
+                            Text {
+                                slice: TextSlice::FromSource { range },
+                                unescape_in: true,
+                                multiline: false,
+                                raw: false,
+                                ..
+                            },
+                    },
+            } => Some(range),
+            NodeType::TextLike { .. } => None,
+        };
+        let mut contained = spans
+            .iter()
+            .filter(|s| range.is_some_and(|r| r.start <= s.start && s.end <= r.end))
+            .peekable();
+        let (Some(range), Some(_)) = (range, contained.peek()) else {
+            content.nodes.push(node);
+            continue;
+        };
+
+        let text_node = |start: Location, end: Location, opaque: bool| {
+            let range = LocationRange { start, end };
+            Node {
+                range,
+                node_type: NodeType::TextLike {
+                    text_like: TextLike::Text {
+                        text: Text {
+                            slice: TextSlice::FromSource { range },
+                            unescape_in: !opaque,
+                            escape_out: !opaque,
+                            raw: opaque,
+                            multiline: false,
+                        },
+                    },
+                },
+            }
+        };
+
+        let mut start = range.start;
+        for span in contained {
+            if start < span.start {
+                content.nodes.push(text_node(start, span.start, false));
+            }
+            content.nodes.push(text_node(span.start, span.end, true));
+            start = span.end;
+        }
+        if start < range.end {
+            content.nodes.push(text_node(start, range.end, false));
+        }
+    }
+}
@@ -4,7 +4,9 @@ use gramma::parse::{Location, LocationRange};
 
 use crate::{
     ast::{self, AttributeAssignment, SelectorSegment},
-    error::{ItemType, UnclosedDelimiterKind},
+    error::{
+        ItemType, SemanticError, SemanticErrorKind, UnclosedDelimiterKind, Warning, WarningKind,
+    },
     utils::default,
 };
 
@@ -16,6 +18,10 @@ pub struct Selector<'cfg> {
     pub range: LocationRange,
     pub tag: Tag<'cfg>,
     pub items: Vec<SelectorItem<'cfg>>,
+    /// Whether the tag was assigned by inference rather than written in the source.
+    pub(crate) inferred: bool,
+    /// Whether the selector was marked raw with `!`, as in `xmp!>`.
+    pub(crate) raw: bool,
 }
 
 impl<'cfg> Selector<'cfg> {
@@ -54,6 +60,28 @@ impl<'cfg> Selector<'cfg> {
 }
 
 impl<'cfg> Selector<'cfg> {
+    /// Removes every attribute named `name`, matched case-insensitively, from this selector,
+    /// returning the value of the first.
+    pub(crate) fn take_attribute(&mut self, src: &str, name: &str) -> Option<TextSlice<'cfg>> {
+        let mut out = None;
+
+        for item in &mut self.items {
+            if let SelectorItem::Attributes { attributes, .. } = item {
+                attributes.retain_mut(|a| {
+                    if !a.name.as_str(src).eq_ignore_ascii_case(name) {
+                        return true;
+                    }
+                    if out.is_none() {
+                        out = a.value.take();
+                    }
+                    false
+                });
+            }
+        }
+
+        out
+    }
+
     pub(crate) fn empty(location: Location) -> Self {
         Self {
             range: LocationRange {
@@ -151,9 +179,12 @@ impl<'cfg> BuildContext<'_, 'cfg> {
             },
             None => None,
         };
-        let name = self.slice(name.range);
+        // Names and values are always unescaped on output, so their escapes are validated here.
+        let name = self.escapable_slice(name.range, true)?;
 
-        let value = value_range.map(|r| self.slice(r));
+        let value = value_range
+            .map(|r| self.escapable_slice(r, true))
+            .transpose()?;
 
         Ok(Attribute {
             range: LocationRange { start, end },
@@ -184,7 +215,7 @@ impl<'cfg> BuildContext<'_, 'cfg> {
     fn build_tag(&mut self, ast: &Option<ast::ElementSelector>) -> BuildResult<Tag<'cfg>> {
         Ok(match ast {
             Some(ast::ElementSelector::Name { name }) => Tag::Explicit {
-                value: self.slice(name.range),
+                value: self.escapable_slice(name.range, true)?,
             },
             Some(ast::ElementSelector::Star { star }) => Tag::Wildcard { range: star.range },
             None => Tag::Implicit {},
@@ -198,11 +229,11 @@ impl<'cfg> BuildContext<'_, 'cfg> {
         Ok(match ast {
             ast::ClassLike::Class { value } => Some(SelectorItem::Class {
                 dot: value.dot.range,
-                value: self.slice(value.ident.range),
+                value: self.escapable_slice(value.ident.range, true)?,
             }),
             ast::ClassLike::Id { value } => Some(SelectorItem::Id {
                 hash: value.hash.range,
-                value: self.slice(value.ident.range),
+                value: self.escapable_slice(value.ident.range, true)?,
             }),
             &ast::ClassLike::Invalid { range } => {
                 self.invalid(range, ItemType::Selector {})?;
@@ -228,6 +259,7 @@ impl<'cfg> BuildContext<'_, 'cfg> {
             start,
             first: ref selector_start,
             ref segments,
+            ref raw,
             end,
         }: &ast::Selector,
     ) -> BuildResult<Selector<'cfg>> {
@@ -273,10 +305,56 @@ impl<'cfg> BuildContext<'_, 'cfg> {
             };
         }
 
-        Ok(Selector {
+        let selector = Selector {
             range,
             tag,
             items: selector_items,
-        })
+            inferred: false,
+            raw: raw.is_some(),
+        };
+
+        self.check_duplicate_attributes(&selector);
+        self.check_multiple_ids(&selector)?;
+
+        Ok(selector)
+    }
+
+    /// Warns about any attribute whose name was already given earlier in the same selector.
+    fn check_duplicate_attributes(&mut self, selector: &Selector<'cfg>) {
+        let src = self.src;
+        let names = Vec::from_iter(selector.attributes().map(|(name, _)| name));
+
+        for (i, name) in names.iter().enumerate() {
+            let &&TextSlice::FromSource { range } = name else {
+                continue;
+            };
+            let name = name.as_str(src);
+
+            if names[..i]
+                .iter()
+                .any(|prev| prev.as_str(src).eq_ignore_ascii_case(name))
+            {
+                self.errors.warn([Warning {
+                    range,
+                    kind: WarningKind::DuplicateAttribute {},
+                }]);
+            }
+        }
+    }
+
+    /// Reports each ID after the first in the same selector, since an element has only one.
+    fn check_multiple_ids(&mut self, selector: &Selector<'cfg>) -> BuildResult {
+        let ids = selector.items.iter().filter_map(|item| match item {
+            SelectorItem::Id { hash, value } => Some(match value {
+                TextSlice::FromSource { range } => hash.combine(*range),
+                TextSlice::Provided { .. } => *hash,
+            }),
+            _ => None,
+        });
+
+        self.errors.semantic(ids.skip(1).map(|range| SemanticError {
+            range,
+            kind: SemanticErrorKind::MultipleIds {},
+        }))
     }
 }
@@ -119,6 +119,9 @@ pub enum Space<'cfg> {
 pub enum Comment<'cfg> {
     #[non_exhaustive]
     Tag { slice: TextSlice<'cfg> },
+    /// A comment whose body is written without escaping, like an IE conditional comment.
+    #[non_exhaustive]
+    Raw { slice: TextSlice<'cfg> },
 }
 
 impl<'cfg> BuildContext<'_, 'cfg> {
@@ -192,6 +195,7 @@ impl<'cfg> BuildContext<'_, 'cfg> {
                 self.build_text_node(value.range, true, true, false, false)
             }
             ast::InlineText::Verbatim { value } => self.build_verbatim_node(value),
+            ast::InlineText::RawComment { comment } => self.build_raw_comment_node(comment),
             ast::InlineText::Comment { comment } => self.build_comment_node(comment),
             ast::InlineText::Interpolation { interpolation } => {
                 self.build_text_node(interpolation.range, false, false, true, false)
@@ -212,7 +216,16 @@ impl<'cfg> BuildContext<'_, 'cfg> {
         let range = LocationRange { start, end };
 
         if close.is_none() {
-            self.unclosed(open.range, crate::error::UnclosedDelimiterKind::Comment {})?;
+            // A `<!!` without a matching `!!>` is parsed as an ordinary comment starting with `!`.
+            if self.src[open.range.end.position..].starts_with('!') {
+                let open = LocationRange {
+                    start: open.range.start,
+                    end: open.range.end + 1,
+                };
+                self.unclosed(open, crate::error::UnclosedDelimiterKind::RawComment {})?;
+            } else {
+                self.unclosed(open.range, crate::error::UnclosedDelimiterKind::Comment {})?;
+            }
         }
 
         Ok(Node {
@@ -227,6 +240,26 @@ impl<'cfg> BuildContext<'_, 'cfg> {
         })
     }
 
+    fn build_raw_comment_node(&mut self, comment: &ast::RawComment) -> BuildResult<Node<'cfg>> {
+        let range = comment.range;
+        // Trim off the `<!!` and `!!>`.
+        let inner = LocationRange {
+            start: range.start + 3,
+            end: range.end - 3,
+        };
+
+        Ok(Node {
+            range,
+            node_type: NodeType::TextLike {
+                text_like: TextLike::Comment {
+                    comment: Comment::Raw {
+                        slice: self.slice(inner),
+                    },
+                },
+            },
+        })
+    }
+
     pub fn exact_space(&mut self, range: LocationRange) -> BuildResult<Node<'cfg>> {
         Ok(Node {
             range,
@@ -1,8 +1,9 @@
 use core::fmt::{self, Display};
 
-use alloc::{borrow::Cow, vec::Vec};
+use alloc::{borrow::Cow, string::String, vec::Vec};
 
 use derive_more::Display;
+use either::Either;
 use gramma::{error::ExpectedParse, ParseError};
 
 use crate::{
@@ -10,7 +11,7 @@ use crate::{
     escape::EscapeError,
     output::OutputError,
     utils::{default, join_display, DisplayFn},
-    OutputConfig, Src,
+    DiagnosticHandler, OutputConfig, Src,
 };
 
 pub use gramma::parse::{Location, LocationRange};
@@ -44,7 +45,7 @@ impl SyntaxError {
         src: &'data str,
         options: &DisplayWithSrcOptions,
     ) -> impl fmt::Display + 'data {
-        let show_location = options.show_location;
+        let location = LocationSuffix::new(self.range, src, options);
         DisplayFn(move |f| {
             let mut inner = |sample| {
                 match &self.kind {
@@ -64,13 +65,7 @@ impl SyntaxError {
                     kind => write!(f, "{kind}"),
                 }?;
 
-                if show_location {
-                    write!(f, " at character {}", self.range.start.position)?;
-                    if self.range.end > self.range.start {
-                        write!(f, "..<{}", self.range.end.position)?;
-                    }
-                }
-                Ok(())
+                write!(f, "{location}")
             };
 
             if self.range.start.position >= src.len() {
@@ -113,6 +108,17 @@ pub enum SyntaxErrorKind {
     InvalidItem { item: ItemType },
     #[non_exhaustive]
     MisplacedItem { kind: MisplacedKind },
+    /// A closing delimiter does not match the innermost opening delimiter, as in `<# a </ b #>`.
+    #[non_exhaustive]
+    #[display(
+        fmt = "Mismatched delimiter: expected end of {}, found end of {}",
+        expected,
+        found
+    )]
+    MismatchedDelimiter {
+        expected: UnclosedDelimiterKind,
+        found: UnclosedDelimiterKind,
+    },
 }
 
 impl From<EscapeError> for SyntaxError {
@@ -154,6 +160,10 @@ pub enum UnclosedDelimiterKind {
     #[display(fmt = "coment")]
     #[non_exhaustive]
     Comment {},
+    /// "<!!"
+    #[display(fmt = "raw comment")]
+    #[non_exhaustive]
+    RawComment {},
     /// "["
     #[display(fmt = "attribute list")]
     #[non_exhaustive]
@@ -285,29 +295,47 @@ pub enum MisplacedKind {
     },
 }
 
+const TRUNCATED_NOTE: &str = "; too many errors, stopping";
+
 /// Represents an error that occurred while converting MinTyML.
 #[non_exhaustive]
 #[derive(Display, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "error-trait", derive(derive_more::Error))]
 pub enum ConvertError<'src> {
     /// The conversion failed due to one or more syntax errors.
+    ///
+    /// If `truncated`, the conversion stopped early because [`OutputConfig::max_errors`] was
+    /// reached, and there may be more errors than were reported.
     #[display(
-        fmt = "{}",
+        fmt = "{}{}",
         r#"crate::utils::join_display(syntax_errors.iter().map(|x| x.display_with_src(src, &default())), "; ")"#,
+        r#"if *truncated { TRUNCATED_NOTE } else { "" }"#,
     )]
+    #[non_exhaustive]
     Syntax {
         syntax_errors: Vec<SyntaxError>,
         src: Src<'src>,
+        truncated: bool,
     },
     /// The conversion failed due to one or more semantic errors.
+    ///
+    /// If `truncated`, the conversion stopped early because [`OutputConfig::max_errors`] was
+    /// reached, and there may be more errors than were reported.
     #[display(
-        fmt = "{}",
+        fmt = "{}{}",
         r#"crate::utils::join_display(semantic_errors.iter().map(|x| x.display_with_src(src, &default())), "; ")"#,
+        r#"if *truncated { TRUNCATED_NOTE } else { "" }"#,
     )]
+    #[non_exhaustive]
     Semantic {
         semantic_errors: Vec<SemanticError>,
         src: Src<'src>,
+        truncated: bool,
     },
+    /// The conversion was aborted because the output exceeded
+    /// [`OutputConfig::max_output_bytes`].
+    #[display(fmt = "Output exceeded the maximum size of {} bytes", limit)]
+    OutputTooLarge { limit: usize },
     /// The conversion failed for some other reason.
     Unknown,
 }
@@ -323,26 +351,89 @@ impl<'src> ConvertError<'src> {
     /// Copies all borrowed data so the error can outlive the source str.
     pub fn to_static(self) -> ConvertError<'static> {
         match self {
-            ConvertError::Syntax { syntax_errors, src } => ConvertError::Syntax {
+            ConvertError::Syntax {
+                syntax_errors,
+                src,
+                truncated,
+            } => ConvertError::Syntax {
                 syntax_errors,
                 src: src.into_owned().into(),
+                truncated,
             },
             ConvertError::Semantic {
                 semantic_errors,
                 src,
+                truncated,
             } => ConvertError::Semantic {
                 semantic_errors,
                 src: src.into_owned().into(),
+                truncated,
             },
+            Self::OutputTooLarge { limit } => ConvertError::OutputTooLarge { limit },
             Self::Unknown => ConvertError::Unknown,
         }
     }
+
+    /// Iterates over the syntax or semantic errors that caused the conversion to fail, if any.
+    pub fn errors(&self) -> impl Iterator<Item = ErrorRef<'_>> {
+        let (syntax_errors, semantic_errors): (&[_], &[_]) = match self {
+            Self::Syntax { syntax_errors, .. } => (syntax_errors, &[]),
+            Self::Semantic {
+                semantic_errors, ..
+            } => (&[], semantic_errors),
+            Self::OutputTooLarge { .. } | Self::Unknown => (&[], &[]),
+        };
+
+        syntax_errors
+            .iter()
+            .map(|error| ErrorRef::Syntax { error })
+            .chain(
+                semantic_errors
+                    .iter()
+                    .map(|error| ErrorRef::Semantic { error }),
+            )
+    }
+}
+
+/// A reference to one of the errors in a [`ConvertError`], returned by [`ConvertError::errors`].
+#[non_exhaustive]
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorRef<'a> {
+    #[non_exhaustive]
+    #[display(fmt = "{}", error)]
+    Syntax { error: &'a SyntaxError },
+    #[non_exhaustive]
+    #[display(fmt = "{}", error)]
+    Semantic { error: &'a SemanticError },
+}
+
+impl<'a> ErrorRef<'a> {
+    /// The range of the source where the error occurred.
+    pub fn range(&self) -> LocationRange {
+        match self {
+            Self::Syntax { error } => error.range,
+            Self::Semantic { error } => error.range,
+        }
+    }
+
+    /// Displays the error with line and column information from `src`.
+    pub fn display_with_src<'data>(
+        &'data self,
+        src: &'data str,
+        options: &DisplayWithSrcOptions,
+    ) -> impl fmt::Display + 'data {
+        match self {
+            Self::Syntax { error } => Either::Left(error.display_with_src(src, options)),
+            Self::Semantic { error } => Either::Right(error.display_with_src(src, options)),
+        }
+    }
 }
 
 impl From<OutputError> for ConvertError<'_> {
     fn from(value: OutputError) -> Self {
         match value {
             OutputError::WriteError(fmt::Error) => Self::Unknown,
+            OutputError::TooLarge { limit } => Self::OutputTooLarge { limit },
         }
     }
 }
@@ -367,26 +458,225 @@ pub struct SemanticError {
 pub enum SemanticErrorKind {
     #[default]
     Unknown,
+    /// An ID was given to more than one element.
+    #[non_exhaustive]
+    #[display(fmt = "Duplicate ID {:?}", id)]
+    DuplicateId { id: String },
+    /// An attribute that requires a value, like `href`, was given none.
+    #[non_exhaustive]
+    #[display(fmt = "Attribute {:?} requires a value", name)]
+    MissingAttributeValue { name: String },
+    /// An attribute value referred to a variable, like `$base`, that isn't defined.
+    #[non_exhaustive]
+    #[display(fmt = "Undefined variable {:?}", name)]
+    UndefinedVariable { name: String },
+    /// A line's indentation mixed tabs and spaces, or used a different one than earlier lines in
+    /// the same block.
+    #[non_exhaustive]
+    #[display(fmt = "Inconsistent indentation")]
+    InconsistentIndentation {},
+    /// An element was placed inside one that can't contain it, like a `section` inside a `p`.
+    #[non_exhaustive]
+    #[display(fmt = "{:?} can't be inside {:?}", child, parent)]
+    InvalidNesting { parent: String, child: String },
+    /// An element selector gave more than one ID. Only the first is written.
+    #[non_exhaustive]
+    #[display(fmt = "Element has more than one ID")]
+    MultipleIds {},
 }
 
 #[non_exhaustive]
 #[derive(Default)]
 pub struct DisplayWithSrcOptions {
+    /// Append the character offsets of the error to the message.
     pub show_location: bool,
+    /// Append the line and column number of the error to the message.
+    pub show_line_column: bool,
+    /// A precomputed index of the source's lines, used to find line and column numbers
+    /// without rescanning the source for every message.
+    pub line_index: Option<LineIndex>,
+}
+
+/// A 1-based line and column number in a source string.
+/// Columns are counted in characters.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[display(fmt = "line {line}, column {column}")]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl LineColumn {
+    /// Finds the line and column of `position` by scanning `src`.
+    pub fn find(src: &str, position: usize) -> Self {
+        let before = src.get(..position).unwrap_or(src);
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+/// The offsets of each line in a source string, so that many positions in the same source can be
+/// converted to line and column numbers without rescanning it.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(src: &str) -> Self {
+        Self {
+            line_starts: core::iter::once(0)
+                .chain(src.match_indices('\n').map(|(i, _)| i + 1))
+                .collect(),
+        }
+    }
+
+    /// Finds the line and column of `position` in `src`, which must be the string this index
+    /// was created from.
+    pub fn line_column(&self, src: &str, position: usize) -> LineColumn {
+        let line = self
+            .line_starts
+            .partition_point(|&start| start <= position)
+            .max(1);
+        let line_start = self.line_starts[line - 1];
+
+        LineColumn {
+            line,
+            column: src
+                .get(line_start..position)
+                .map_or(position - line_start, |s| s.chars().count())
+                + 1,
+        }
+    }
+}
+
+/// Describes where an error occurred, formatted according to [DisplayWithSrcOptions].
+struct LocationSuffix {
+    range: LocationRange,
+    show_location: bool,
+    line_column: Option<LineColumn>,
+}
+
+impl LocationSuffix {
+    fn new(range: LocationRange, src: &str, options: &DisplayWithSrcOptions) -> Self {
+        let position = range.start.position;
+        Self {
+            range,
+            show_location: options.show_location,
+            line_column: options.show_line_column.then(|| match &options.line_index {
+                Some(index) => index.line_column(src, position),
+                None => LineColumn::find(src, position),
+            }),
+        }
+    }
+
+    fn write_characters(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "character {}", self.range.start.position)?;
+        if self.range.end > self.range.start {
+            write!(f, "..<{}", self.range.end.position)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for LocationSuffix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line_column, self.show_location) {
+            (Some(line_column), true) => {
+                write!(f, " at {line_column} (")?;
+                self.write_characters(f)?;
+                f.write_str(")")
+            }
+            (Some(line_column), false) => write!(f, " at {line_column}"),
+            (None, true) => {
+                f.write_str(" at ")?;
+                self.write_characters(f)
+            }
+            (None, false) => Ok(()),
+        }
+    }
 }
 
 impl SemanticError {
     pub fn display_with_src<'data>(
         &'data self,
-        _src: &'data str,
-        _options: &DisplayWithSrcOptions,
+        src: &'data str,
+        options: &DisplayWithSrcOptions,
     ) -> impl fmt::Display + 'data {
+        let location = LocationSuffix::new(self.range, src, options);
         DisplayFn(move |f| match self.kind {
-            ref kind => write!(f, "{kind}"),
+            ref kind => write!(f, "{kind}{location}"),
         })
     }
 }
 
+/// Represents a likely mistake in otherwise valid MinTyML source.
+/// Unlike errors, warnings do not cause the conversion to fail.
+#[non_exhaustive]
+#[derive(Debug, Display, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[display(
+    fmt = "{kind} at character {}..<{}",
+    "range.start.position",
+    "range.end.position"
+)]
+pub struct Warning {
+    /// The [LocationRange] of the source that caused the warning.
+    pub range: LocationRange,
+    pub kind: WarningKind,
+}
+
+/// Indicates what caused a warning.
+#[non_exhaustive]
+#[derive(Debug, Display, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WarningKind {
+    #[default]
+    Unknown,
+    /// An attribute was given more than once in the same element selector.
+    #[non_exhaustive]
+    #[display(fmt = "Duplicate attribute")]
+    DuplicateAttribute {},
+    /// An `img` element has no `alt` attribute.
+    #[non_exhaustive]
+    #[display(fmt = "Image is missing alt text")]
+    MissingAltText {},
+}
+
+impl Warning {
+    pub fn display_with_src<'data>(
+        &'data self,
+        src: &'data str,
+        options: &DisplayWithSrcOptions,
+    ) -> impl fmt::Display + 'data {
+        let location = LocationSuffix::new(self.range, src, options);
+        DisplayFn(move |f| {
+            let sample = src
+                .get(self.range.start.position..self.range.end.position)
+                .unwrap_or_default();
+
+            match self.kind {
+                WarningKind::DuplicateAttribute {} => write!(f, "Duplicate attribute {sample:?}"),
+                ref kind => write!(f, "{kind}"),
+            }?;
+
+            write!(f, "{location}")
+        })
+    }
+}
+
+/// An error passed to [`OutputConfig::on_diagnostic`] as soon as it is found.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy)]
+pub enum Diagnostic<'a> {
+    #[non_exhaustive]
+    Syntax { error: &'a SyntaxError },
+    #[non_exhaustive]
+    Semantic { error: &'a SemanticError },
+}
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct InternalError;
 pub(crate) type InternalResult<T = ()> = Result<T, InternalError>;
@@ -394,29 +684,56 @@ pub(crate) type InternalResult<T = ()> = Result<T, InternalError>;
 #[derive(Debug)]
 pub(crate) struct Errors {
     fail_fast: bool,
+    max_errors: Option<usize>,
+    /// Whether an error was dropped because `max_errors` was reached.
+    truncated: bool,
+    on_diagnostic: Option<DiagnosticHandler>,
     syntax_errors: Vec<SyntaxError>,
     semantic_errors: Vec<SemanticError>,
     unknown_error: bool,
+    output_limit: Option<usize>,
+    warnings: Vec<Warning>,
 }
 
 impl Errors {
     pub fn new(config: &OutputConfig) -> Self {
         Self {
             fail_fast: config.fail_fast.unwrap_or(false),
+            // A failed conversion always reports at least one error.
+            max_errors: config.max_errors.map(|max| max.max(1)),
+            truncated: false,
+            on_diagnostic: config.on_diagnostic.clone(),
             syntax_errors: default(),
             semantic_errors: default(),
             unknown_error: false,
+            output_limit: None,
+            warnings: default(),
         }
     }
 
     pub fn count(&self) -> usize {
-        self.syntax_errors.len() + self.semantic_errors.len() + self.unknown_error as usize
+        self.syntax_errors.len()
+            + self.semantic_errors.len()
+            + self.output_limit.is_some() as usize
+            + self.unknown_error as usize
     }
 
     pub fn is_empty(&self) -> bool {
         self.count() == 0
     }
 
+    /// Whether any syntax error was recovered from by guessing where a delimiter should go.
+    pub fn has_delimiter_errors(&self) -> bool {
+        self.syntax_errors.iter().any(|e| {
+            matches!(
+                e.kind,
+                SyntaxErrorKind::Unclosed { .. }
+                    | SyntaxErrorKind::MisplacedItem { .. }
+                    | SyntaxErrorKind::MismatchedDelimiter { .. }
+            )
+        })
+    }
+
     pub fn to_convert_error<'src>(
         self,
         src: impl Into<Cow<'src, str>>,
@@ -427,29 +744,67 @@ impl Errors {
             syntax_errors,
             semantic_errors,
             unknown_error,
+            output_limit,
+            truncated,
             ..
         } = self;
 
         Err(
-            match (syntax_errors.len(), semantic_errors.len(), unknown_error) {
-                (0, 0, false) => return Ok(()),
-                (1.., _, _) => ConvertError::Syntax { syntax_errors, src },
-                (_, 1.., _) => ConvertError::Semantic {
+            match (
+                syntax_errors.len(),
+                semantic_errors.len(),
+                output_limit,
+                unknown_error,
+            ) {
+                (0, 0, None, false) => return Ok(()),
+                (1.., _, _, _) => ConvertError::Syntax {
+                    syntax_errors,
+                    src,
+                    truncated,
+                },
+                (_, 1.., _, _) => ConvertError::Semantic {
                     semantic_errors,
                     src,
+                    truncated,
                 },
-                (_, _, true) => ConvertError::Unknown,
+                (_, _, Some(limit), _) => ConvertError::OutputTooLarge { limit },
+                (_, _, _, true) => ConvertError::Unknown,
             },
         )
     }
 
+    /// The number of syntax and semantic errors that can be recorded before reaching
+    /// `max_errors`.
+    fn remaining(&self) -> usize {
+        self.max_errors.map_or(usize::MAX, |max| {
+            max.saturating_sub(self.syntax_errors.len() + self.semantic_errors.len())
+        })
+    }
+
+    /// Records that an error was dropped for exceeding `max_errors`, if `dropped`. This always
+    /// aborts the conversion.
+    fn check_truncated(&mut self, dropped: bool) -> InternalResult {
+        if dropped {
+            self.truncated = true;
+            return Err(InternalError);
+        }
+        Ok(())
+    }
+
     pub fn syntax<E>(&mut self, errors: impl IntoIterator<Item = E>) -> InternalResult
     where
         E: Into<SyntaxError>,
     {
         let old_len = self.syntax_errors.len();
+        let mut errors = errors.into_iter();
         self.syntax_errors
-            .extend(errors.into_iter().map(Into::into));
+            .extend(errors.by_ref().take(self.remaining()).map(Into::into));
+        if let Some(ref handler) = self.on_diagnostic {
+            for error in &self.syntax_errors[old_len..] {
+                handler.call(&Diagnostic::Syntax { error });
+            }
+        }
+        self.check_truncated(errors.next().is_some())?;
         if self.fail_fast && old_len < self.syntax_errors.len() {
             return Err(InternalError);
         }
@@ -461,14 +816,37 @@ impl Errors {
         E: Into<SemanticError>,
     {
         let old_len = self.semantic_errors.len();
+        let mut errors = errors.into_iter();
         self.semantic_errors
-            .extend(errors.into_iter().map(Into::into));
+            .extend(errors.by_ref().take(self.remaining()).map(Into::into));
+        if let Some(ref handler) = self.on_diagnostic {
+            for error in &self.semantic_errors[old_len..] {
+                handler.call(&Diagnostic::Semantic { error });
+            }
+        }
+        self.check_truncated(errors.next().is_some())?;
         if self.fail_fast && old_len < self.semantic_errors.len() {
             return Err(InternalError);
         }
         Ok(())
     }
 
+    /// Records a warning. Warnings never abort the conversion, even when failing fast.
+    pub fn warn(&mut self, warnings: impl IntoIterator<Item = Warning>) {
+        self.warnings.extend(warnings);
+    }
+
+    /// Removes and returns all warnings recorded so far.
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        core::mem::take(&mut self.warnings)
+    }
+
+    /// Records that the output exceeded `limit` bytes. This always aborts the conversion.
+    pub fn output_too_large(&mut self, limit: usize) -> InternalResult {
+        self.output_limit = Some(limit);
+        Err(InternalError)
+    }
+
     pub fn unknown(&mut self) -> InternalResult {
         self.unknown_error = true;
         if self.fail_fast {
@@ -15,6 +15,22 @@ pub enum UnescapePart<'src> {
 
 const ESCAPE_CHAR: char = '\\';
 
+/// Advances `cursor` past up to `max` hexadecimal digits, returning how many there were and their
+/// value. The value saturates rather than overflowing.
+fn hex_digits(cursor: &mut StrCursor, max: usize) -> (usize, u32) {
+    let mut len = 0;
+    let mut value = 0u32;
+    while let Some(digit) = cursor.peek(0).and_then(|c| c.to_digit(16)) {
+        if len == max {
+            break;
+        }
+        let _ = cursor.next();
+        len += 1;
+        value = value.saturating_mul(16).saturating_add(digit);
+    }
+    (len, value)
+}
+
 pub fn unescape_parts<'src>(
     slice: &'src str,
     slice_offset: impl Into<Option<Location>>,
@@ -44,31 +60,28 @@ pub fn unescape_parts<'src>(
             't' => Ok('\t'),
             'n' => Ok('\n'),
             'r' => Ok('\r'),
-            'x' => cursor
-                .advance_by(2)
-                .ok()
-                .and_then(|digits| u8::from_str_radix(digits, 16).ok().filter(|&x| x <= 0x7f))
-                .map(char::from)
-                .ok_or(()),
+            'x' => match hex_digits(&mut cursor, 2) {
+                (2, value) if value <= 0x7f => Ok(char::from(value as u8)),
+                _ => Err(()),
+            },
             'u' => 'out: {
                 if cursor.peek(0) != Some('{') {
                     break 'out Err(());
                 };
                 let _ = cursor.next();
 
-                let digits = match cursor.advance_to_char('}') {
-                    Ok(digits) if (1..=6).contains(&digits.len()) => digits,
-                    _ => break 'out Err(()),
-                };
-
+                let (len, value) = hex_digits(&mut cursor, usize::MAX);
+                if cursor.peek(0) != Some('}') {
+                    break 'out Err(());
+                }
                 let _ = cursor.next();
 
-                u32::from_str_radix(digits, 16)
-                    .ok()
+                Some(value)
+                    .filter(|_| (1..=6).contains(&len))
                     .and_then(char::from_u32)
                     .ok_or(())
             }
-            ch @ ('<' | '>' | '{' | '}' | '"' | '\'' | '\\' | '[' | ']') => Ok(ch),
+            ch @ ('<' | '>' | '{' | '}' | '"' | '\'' | '\\' | '[' | ']' | '$') => Ok(ch),
             _ => Err(()),
         };
 
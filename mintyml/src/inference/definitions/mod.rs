@@ -3,13 +3,22 @@ mod table;
 
 use super::engine::{define_methods, define_tags, when::*, Infer, MethodDefinition, TagDefinition};
 
+/// Elements that contain phrasing content but can't appear inside it.
+fn is_phrasing_block(tag: &str) -> bool {
+    is_heading(tag) || matches!(tag, "p" | "pre")
+}
+
+fn is_heading(tag: &str) -> bool {
+    matches!(tag, "h1" | "h2" | "h3" | "h4" | "h5" | "h6")
+}
+
 #[rustfmt::skip]
 fn contains_phrasing(tag: &str) -> bool {
-    matches!(tag,
-        | "p" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "span" | "b" | "i"| "q" | "s" | "u"
-        | "button" | "caption" | "cite" | "code" | "data" | "dfn" | "dt" | "em" | "kbd" | "legend"
-        | "mark" | "meter" | "option" | "output" | "pre" | "progress" | "samp" | "small" | "strong"
-        | "sub" | "summary" | "sup" | "textarea" | "time" | "var"
+    is_phrasing_block(tag) || matches!(tag,
+        | "span" | "b" | "i"| "q" | "s" | "u" | "button" | "caption" | "cite" | "code" | "data"
+        | "dfn" | "dt" | "em" | "kbd" | "legend" | "mark" | "meter" | "option" | "output"
+        | "progress" | "samp" | "small" | "strong" | "sub" | "summary" | "sup" | "textarea"
+        | "time" | "var"
     )
 }
 
@@ -21,8 +30,39 @@ fn contains_blocks(tag: &str) -> bool {
     )
 }
 
+/// Elements that can't appear where only phrasing content is allowed.
+#[rustfmt::skip]
+fn is_flow_only(tag: &str) -> bool {
+    contains_blocks(tag) || is_phrasing_block(tag) || matches!(tag,
+        | "ul" | "ol" | "menu" | "dl" | "table" | "form" | "fieldset" | "hr" | "address"
+        | "details"
+    )
+}
+
+/// Elements that HTML only allows to contain phrasing content.
+///
+/// Unlike [`contains_phrasing`], which decides how content is inferred, this leaves out elements
+/// like `caption`, `dt`, and `legend` that also allow some flow content, and ones like `option`
+/// and `textarea` that only contain text.
 #[rustfmt::skip]
-fn is_void(tag: &str) -> bool {
+fn allows_only_phrasing(tag: &str) -> bool {
+    is_phrasing_block(tag) || matches!(tag,
+        | "span" | "b" | "i" | "q" | "s" | "u" | "button" | "cite" | "code" | "data" | "dfn"
+        | "em" | "kbd" | "mark" | "meter" | "output" | "progress" | "samp" | "small" | "strong"
+        | "sub" | "summary" | "sup" | "time" | "var"
+    )
+}
+
+/// Whether HTML forbids an element with the lowercase tag `child` directly inside one with the
+/// lowercase tag `parent`.
+pub(crate) fn is_invalid_child(parent: &str, child: &str) -> bool {
+    // `summary` allows headings alongside its phrasing content.
+    let heading_in_summary = parent == "summary" && (is_heading(child) || child == "hgroup");
+    allows_only_phrasing(parent) && is_flow_only(child) && !heading_in_summary
+}
+
+#[rustfmt::skip]
+pub(crate) fn is_void(tag: &str) -> bool {
     matches!(tag,
         | "area" | "base" | "br" | "col" | "embed" | "hr" | "img" | "input" | "link" | "meta"
         | "param" | "source" | "track" | "wbr"
@@ -58,10 +98,17 @@ fn common_methods<'cfg>() -> impl MethodDefinition<'cfg> {
         .when(tag_in(["style", "script"]), &RawInfer {})
 }
 
+/// The inference method used for the root of a document unless
+/// [`OutputConfig::inference`](crate::OutputConfig::inference) is set.
 #[non_exhaustive]
 #[derive(Debug)]
 pub struct StandardInfer {}
 
+impl StandardInfer {
+    /// A shared instance, for use with methods like [`MethodDefinition::apply_from`].
+    pub const INSTANCE: &'static Self = &Self {};
+}
+
 impl<'cfg> Infer<'cfg> for StandardInfer {
     fn define_tags(&self) -> impl TagDefinition<'cfg> {
         define_tags().when(line() | paragraph(), "p").default("div")
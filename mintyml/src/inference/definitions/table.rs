@@ -16,8 +16,14 @@ pub struct RowInfer {}
 
 impl<'cfg> Infer<'cfg> for RowInfer {
     fn define_tags(&self) -> impl TagDefinition<'cfg> {
+        let first_row_of_table = tag("tr") & child_of(tag("table")) & !after(tag("tr"));
+
         define_tags()
             .when(child_of(child_of(tag("thead"))), "th")
+            .when(
+                table_header_row_enabled() & child_of(first_row_of_table),
+                "th",
+            )
             .default("td")
     }
 }
@@ -13,12 +13,17 @@ use either::IntoEither;
 use crate::{
     document::{Content, Element, IntoTags, Node, NodeType},
     utils::default,
+    ClassPlacement,
 };
 
 use self::when::*;
 
 use super::definitions::StandardInfer;
 
+/// An attribute on an element without a tag that gives the tag to use instead of inferring one,
+/// e.g. `[mty:as=figcaption]> ...`
+const FORCE_TAG_ATTR: &str = "mty:as";
+
 mod sealed {
     use super::*;
     pub trait InferTest<'cfg, T: ?Sized> {
@@ -75,13 +80,15 @@ pub trait TagDefinition<'cfg, _Bound = &'cfg Self>:
 
     fn when<M>(
         self,
-        pred: impl InferencePredicate,
+        pred: impl InferencePredicate + 'cfg,
         value: impl IntoTags<'cfg, M>,
     ) -> Self::Append<impl TagDefinition<'cfg>> {
         let tags = value.tags();
         self.append(InferenceDefinitionPair {
             pred,
-            value: TagFn(move |element: &mut Element<'cfg>| element.apply_tags(tags.clone())),
+            value: TagFn(move |element: &mut Element<'cfg>| {
+                element.apply_inferred_tags(tags.clone())
+            }),
             next: EmptyInferenceDefinition {},
         })
     }
@@ -216,7 +223,7 @@ pub trait Infer<'cfg, _Bound = &'cfg Self>: 'cfg + fmt::Debug {
     }
 }
 
-trait DynInfer<'cfg>: fmt::Debug {
+pub(crate) trait DynInfer<'cfg>: fmt::Debug {
     fn infer(&self, inferrer: &mut Inferrer<'cfg, '_>);
     fn root_is_raw(&self) -> bool;
 }
@@ -319,8 +326,18 @@ pub(crate) enum ChildInference<'cfg> {
     WithMethod(InferenceMethod<'cfg>),
 }
 
+/// Options from [`OutputConfig`](crate::OutputConfig) that affect inference.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct InferenceOptions {
+    /// Whether the cells in the first row of a table are headers.
+    pub table_header_row: bool,
+    /// Which element receives the classes and ID of an element inferred as a chain of tags.
+    pub class_placement: ClassPlacement,
+}
+
 pub struct InferencePredicateContext<'cfg, 'infer> {
     src: &'cfg str,
+    options: InferenceOptions,
     nodes: &'infer [Node<'cfg>],
     parent: Option<&'infer InferencePredicateContext<'cfg, 'infer>>,
     index: usize,
@@ -389,8 +406,9 @@ struct InferenceState {
     result: Result<(), usize>,
 }
 
-struct Inferrer<'cfg, 'infer> {
+pub(crate) struct Inferrer<'cfg, 'infer> {
     src: &'cfg str,
+    options: InferenceOptions,
     nodes: &'infer mut [Node<'cfg>],
     parent_context: Option<&'infer InferencePredicateContext<'cfg, 'infer>>,
     states: &'infer mut Vec<InferenceState>,
@@ -419,9 +437,24 @@ impl<'cfg, 'infer> Inferrer<'cfg, 'infer> {
             };
         }
 
+        if let Some(element) = self.nodes[index].as_element_mut() {
+            // An explicit override takes the place of whatever would have been inferred.
+            let forced = element
+                .selectors
+                .first_mut()
+                .and_then(|s| s.take_attribute(self.src, FORCE_TAG_ATTR));
+
+            if let Some(tag) = forced {
+                element.apply_tags([tag]);
+                self.states[index].result = Ok(());
+                return true;
+            }
+        }
+
         let result = match define_tags.test(
             &InferencePredicateContext {
                 src: self.src,
+                options: self.options,
                 nodes: &*self.nodes,
                 parent: self.parent_context,
                 index,
@@ -485,12 +518,14 @@ impl<'cfg, 'infer> Inferrer<'cfg, 'infer> {
 
     fn get_predicate_context<'lt>(
         src: &'cfg str,
+        options: InferenceOptions,
         nodes: &'lt &mut [Node<'cfg>],
         &parent: &'lt Option<&InferencePredicateContext<'cfg, 'lt>>,
         index: usize,
     ) -> InferencePredicateContext<'cfg, 'lt> {
         InferencePredicateContext {
             src,
+            options,
             nodes,
             parent,
             index,
@@ -502,12 +537,20 @@ impl<'cfg, 'infer> Inferrer<'cfg, 'infer> {
             let Some(element) = self.nodes[index].as_element_mut() else {
                 return;
             };
+            if self.options.class_placement == ClassPlacement::Innermost {
+                element.move_inferred_classes_to_last();
+            }
             element.split_first();
             mem::take(&mut element.content.nodes)
         };
 
-        let predicate_context =
-            Self::get_predicate_context(self.src, &self.nodes, &self.parent_context, index);
+        let predicate_context = Self::get_predicate_context(
+            self.src,
+            self.options,
+            &self.nodes,
+            &self.parent_context,
+            index,
+        );
 
         let method = {
             let mut index = 0;
@@ -528,6 +571,7 @@ impl<'cfg, 'infer> Inferrer<'cfg, 'infer> {
         {
             method.get_inner().infer(&mut Inferrer {
                 src: self.src,
+                options: self.options,
                 nodes: &mut nodes,
                 parent_context: Some(&predicate_context),
                 states: self.states,
@@ -544,9 +588,16 @@ impl<'cfg, 'infer> Inferrer<'cfg, 'infer> {
     }
 }
 
-pub fn infer<'cfg>(src: &'cfg str, content: &mut Content<'cfg>) {
-    StandardInfer {}.infer(&mut Inferrer {
+/// Infers the tags in `content` using `method`, or [`StandardInfer`] if `None`.
+pub fn infer<'cfg>(
+    src: &'cfg str,
+    content: &mut Content<'cfg>,
+    method: Option<&dyn DynInfer<'cfg>>,
+    options: InferenceOptions,
+) {
+    method.unwrap_or(&StandardInfer {}).infer(&mut Inferrer {
         src,
+        options,
         nodes: &mut content.nodes,
         parent_context: None,
         states: &mut default(),
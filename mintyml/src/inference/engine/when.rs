@@ -1,6 +1,6 @@
 mod ops;
 use crate::{
-    document::{Element, ElementDelimiter, ElementType, NodeType, Tag},
+    document::{Element, ElementDelimiter, ElementType, Node, NodeType, Tag},
     utils::default,
 };
 
@@ -148,6 +148,35 @@ pub fn tag_where<'tag>(
     })
 }
 
+/// Matches elements whose selector has an attribute named `name`, with any value.
+pub fn attribute(name: &str) -> InferWhen<impl InferencePredicate + '_> {
+    attribute_where(name, |_| true)
+}
+
+/// Matches elements whose selector has an attribute named `name` with the value `value`.
+pub fn attribute_eq<'a>(name: &'a str, value: &'a str) -> InferWhen<impl InferencePredicate + 'a> {
+    attribute_where(name, move |v| v == Some(value))
+}
+
+fn attribute_where<'a>(
+    name: &'a str,
+    mut pred: impl FnMut(Option<&str>) -> bool + 'a,
+) -> InferWhen<impl InferencePredicate + 'a> {
+    pred_impl(move |cx| {
+        cx.match_this_element(|e| {
+            Ok(e.selectors.first().is_some_and(|s| {
+                s.attributes()
+                    .any(|(n, v)| n.as_str(cx.src) == name && pred(v.map(|v| v.as_str(cx.src))))
+            }))
+        })
+    })
+}
+
+/// Matches when [`OutputConfig::table_header_row`](crate::OutputConfig::table_header_row) is set.
+pub(crate) fn table_header_row_enabled() -> InferWhen<impl InferencePredicate> {
+    pred_impl(|cx| Ok(cx.options.table_header_row))
+}
+
 pub fn any() -> InferWhen<impl InferencePredicate> {
     pred_impl(|_| Ok(true))
 }
@@ -166,6 +195,34 @@ pub fn last() -> InferWhen<impl InferencePredicate> {
     !before(any())
 }
 
+/// Whether `node` is skipped by [`first_child`] and [`last_child`].
+fn is_hidden(node: &Node) -> bool {
+    node.is_space() || node.is_comment()
+}
+
+/// Matches the first of its siblings, not counting spaces and comments.
+///
+/// Unlike [`first`], this only depends on the kinds of the preceding nodes rather than their
+/// inferred tags, so it doesn't change the direction precedence of the predicates it's combined
+/// with.
+pub fn first_child() -> InferWhen<impl InferencePredicate> {
+    pred_impl(|cx| {
+        let visible = cx.match_this_node(|n| Ok(!is_hidden(n)))?;
+        Ok(visible && cx.nodes[..cx.index].iter().all(is_hidden))
+    })
+}
+
+/// Matches the last of its siblings, not counting spaces and comments.
+///
+/// Like [`first_child`], this doesn't change the direction precedence of the predicates it's
+/// combined with.
+pub fn last_child() -> InferWhen<impl InferencePredicate> {
+    pred_impl(|cx| {
+        let visible = cx.match_this_node(|n| Ok(!is_hidden(n)))?;
+        Ok(visible && cx.nodes[cx.index + 1..].iter().all(is_hidden))
+    })
+}
+
 pub fn descendant_of(pred: impl InferencePredicate) -> InferWhen<impl InferencePredicate> {
     pred_impl_with(pred, |pred, mut cx| {
         while let Some(parent) = cx.parent {
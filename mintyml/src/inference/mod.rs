@@ -1,2 +1,20 @@
+//! Building blocks for customizing how MinTyML infers the tags of elements that don't have one.
+//!
+//! An [`Infer`] implementation decides which tag each untagged child of an element gets
+//! ([`Infer::define_tags`]) and which method is used to infer the tags of each child's own
+//! children ([`Infer::define_methods`]). A custom root method can be set with
+//! [`OutputConfig::inference`](crate::OutputConfig::inference).
+//!
+//! Definitions are generic over the `'cfg` lifetime of the document being converted, and anything
+//! they borrow, like tag names, must outlive it. A method passed to
+//! [`OutputConfig::inference`](crate::OutputConfig::inference) must implement
+//! [`Infer<'cfg>`](Infer) for every `'cfg`, typically with `impl<'cfg> Infer<'cfg> for MyInfer`.
+
 pub(crate) mod definitions;
 pub(crate) mod engine;
+
+pub use definitions::StandardInfer;
+pub use engine::{
+    define_methods, define_tags, when, Infer, InferenceDefinition, InferenceMethod,
+    InferencePredicate, IntoInferenceMethod, MethodDefinition, TagDefinition,
+};
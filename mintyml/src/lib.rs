@@ -14,27 +14,47 @@ pub(crate) mod config;
 pub(crate) mod document;
 pub mod error;
 pub(crate) mod escape;
-pub(crate) mod inference;
+pub mod inference;
 pub(crate) mod output;
+pub(crate) mod stats;
+pub(crate) mod streaming;
 pub(crate) mod transform;
+pub mod tree;
 pub(crate) mod utils;
 
-use alloc::{borrow::Cow, string::String};
-use core::{borrow::Borrow, fmt};
-use error::{Errors, InternalError};
+use alloc::{borrow::Cow, collections::BTreeMap, string::String, vec, vec::Vec};
+use core::{borrow::Borrow, fmt, ops};
+use error::{Errors, InternalError, InternalResult, Warning};
+use gramma::parse::LocationRange;
 
-use document::Document;
+use document::{Content, Document};
 use output::OutputError;
+use streaming::CachedBlock;
 
-pub use config::{MetadataConfig, OutputConfig, SpecialTagConfig};
+pub use config::{
+    CharsetMetaStyle, ClassPlacement, CustomInference, DiagnosticHandler, Direction,
+    DropEmptyPolicy, ElementVisitor, LangScope, LineEnding, MetadataConfig, OutputConfig,
+    ResponsiveImageResolver, SpacesPolicy, SpecialTag, SpecialTagConfig, TagCase, TagRewrite,
+};
 
+pub use document::SpecialKind;
 pub use error::ConvertError;
 #[deprecated]
 #[doc(hidden)]
 pub use error::{SyntaxError, SyntaxErrorKind};
+pub use stats::ConvertStats;
+pub use streaming::ConvertCache;
+pub use transform::ElementRef;
 
 type Src<'src> = Cow<'src, str>;
 
+/// The range of the output each element was written to, paired with the range of the source it
+/// came from, as returned by [`convert_with_source_map`].
+pub type SourceMap = Vec<(ops::Range<usize>, LocationRange)>;
+
+/// The result of a forgiving conversion, which may include a best-effort output on failure.
+type ForgivingResult<'src> = Result<String, (Option<String>, ConvertError<'src>)>;
+
 /// Converts the given MinTyML string `src` using `config` for configuration options.
 /// If successful, returns a string containing the converted HTML document.
 ///
@@ -74,6 +94,83 @@ pub fn convert<'src>(
     Ok(out)
 }
 
+/// Converts each `(src, config)` pair in `inputs` independently with [`convert`], returning the
+/// results in the same order.
+///
+/// An error in one document does not affect the conversion of the others.
+///
+/// # Example
+///
+/// ```
+/// # use mintyml::OutputConfig;
+/// let config = OutputConfig::new();
+/// let results = mintyml::convert_all([("p> One", &config), ("p> Two", &config)]);
+///
+/// assert_eq!(results[0].as_deref().unwrap(), "<p>One</p>");
+/// assert_eq!(results[1].as_deref().unwrap(), "<p>Two</p>");
+/// ```
+pub fn convert_all<'src, C: Borrow<OutputConfig<'src>>>(
+    inputs: impl IntoIterator<Item = (&'src str, C)>,
+) -> Vec<Result<String, ConvertError<'src>>> {
+    inputs
+        .into_iter()
+        .map(|(src, config)| convert(src, config))
+        .collect()
+}
+
+/// Similar to [`convert`], but returns the HTML for each top-level element separately.
+///
+/// The whole document is parsed and inferred at once, so each element is converted in the context
+/// of its siblings. Top-level nodes that aren't elements, like comments, are left out.
+///
+/// # Example
+///
+/// ```
+/// # use mintyml::OutputConfig;
+/// let fragments = mintyml::convert_fragment_list(
+///     "h1> Title\n\n<! note !>\n\nHello, world!",
+///     OutputConfig::new(),
+/// ).unwrap();
+///
+/// assert_eq!(fragments, ["<h1>Title</h1>", "<p>Hello, world!</p>"]);
+/// ```
+pub fn convert_fragment_list<'src>(
+    src: &'src str,
+    config: impl Borrow<OutputConfig<'src>>,
+) -> Result<Vec<String>, ConvertError<'src>> {
+    let config = config.borrow();
+    let mut errors = Errors::new(config);
+    let mut fragments = Vec::new();
+
+    let (Ok(()) | Err(InternalError)) = (|| {
+        let document = parse_and_transform(src, config, &mut errors)?;
+        if !errors.is_empty() {
+            return Ok(());
+        }
+
+        for node in document.content.nodes {
+            if node.as_element().is_none() {
+                continue;
+            }
+            let fragment = Document {
+                range: node.range,
+                content: Content {
+                    range: node.range,
+                    nodes: vec![node],
+                },
+            };
+
+            let mut out = String::new();
+            write_html(src, &fragment, &mut out, config, &mut errors, None)?;
+            fragments.push(out);
+        }
+        Ok(())
+    })();
+
+    errors.to_convert_error(src)?;
+    Ok(fragments)
+}
+
 /// Similar to [`convert`], but may return a best-effort conversion of an ill-formed document
 /// in the event of an error.
 ///
@@ -104,13 +201,205 @@ pub fn convert<'src>(
 pub fn convert_forgiving<'src>(
     src: &'src str,
     config: impl Borrow<OutputConfig<'src>>,
-) -> Result<String, (Option<String>, ConvertError<'src>)> {
+) -> ForgivingResult<'src> {
+    convert_forgiving_with_warnings(src, config).0
+}
+
+/// Similar to [`convert_forgiving`], but also returns any warnings found in the document,
+/// regardless of whether the conversion succeeded.
+///
+/// # Example
+///
+/// ```
+/// # use mintyml::OutputConfig;
+/// let (result, warnings) = mintyml::convert_forgiving_with_warnings(
+///     "a[href=foo href=bar]> Link",
+///     OutputConfig::new(),
+/// );
+///
+/// assert_eq!(result.unwrap(), r#"<a href="foo" href="bar">Link</a>"#);
+/// assert_eq!(warnings.len(), 1);
+/// ```
+pub fn convert_forgiving_with_warnings<'src>(
+    src: &'src str,
+    config: impl Borrow<OutputConfig<'src>>,
+) -> (ForgivingResult<'src>, Vec<Warning>) {
     let mut out = String::new();
-    match convert_to_internal(src, config.borrow(), &mut out, true) {
+    let mut errors = Errors::new(config.borrow());
+    convert_with_errors(
+        src,
+        config.borrow(),
+        &mut out,
+        true,
+        None,
+        None,
+        &mut errors,
+    );
+    let warnings = errors.take_warnings();
+
+    let result = match errors.to_convert_error(src) {
         Ok(()) => Ok(out),
         Err(err) if out.is_empty() => Err((None, err)),
         Err(err) => Err((Some(out), err)),
-    }
+    };
+
+    (result, warnings)
+}
+
+/// Similar to [`convert`], but also returns the document's plain text content.
+///
+/// The text has all markup removed and escape sequences decoded.
+/// Block elements are separated by line breaks, and inline content by spaces.
+///
+/// # Example
+///
+/// ```
+/// # use mintyml::OutputConfig;
+/// let (html, text) = mintyml::convert_with_text(r#"
+/// section {
+///     h1> Title
+///
+///     Hello <#there#>!
+/// }
+/// "#, OutputConfig::new()).unwrap();
+///
+/// assert_eq!(html, "<section><h1>Title</h1> <p>Hello <strong>there</strong>!</p></section>");
+/// assert_eq!(text, "Title\nHello there!");
+/// ```
+pub fn convert_with_text<'src>(
+    src: &'src str,
+    config: impl Borrow<OutputConfig<'src>>,
+) -> Result<(String, String), ConvertError<'src>> {
+    let config = config.borrow();
+    let mut out = String::new();
+    let mut text = String::new();
+    let mut errors = Errors::new(config);
+    convert_with_errors(
+        src,
+        config,
+        &mut out,
+        false,
+        Some(&mut text),
+        None,
+        &mut errors,
+    );
+    errors.to_convert_error(src)?;
+    Ok((out, text))
+}
+
+/// Similar to [`convert`], but also returns counts of the elements in the document,
+/// including how many of each kind of special element were used.
+///
+/// # Example
+///
+/// ```
+/// # use mintyml::{OutputConfig, SpecialKind};
+/// let (html, stats) = mintyml::convert_with_stats(
+///     "p> Hello </there/>, <#world#>!",
+///     OutputConfig::new(),
+/// ).unwrap();
+///
+/// assert_eq!(html, "<p>Hello <em>there</em>, <strong>world</strong>!</p>");
+/// assert_eq!(stats.special_tags[&SpecialKind::Emphasis], 1);
+/// assert_eq!(stats.elements, 3);
+/// ```
+pub fn convert_with_stats<'src>(
+    src: &'src str,
+    config: impl Borrow<OutputConfig<'src>>,
+) -> Result<(String, ConvertStats), ConvertError<'src>> {
+    let config = config.borrow();
+    let mut out = String::new();
+    let mut stats = ConvertStats::default();
+    let mut errors = Errors::new(config);
+    convert_with_errors(
+        src,
+        config,
+        &mut out,
+        false,
+        None,
+        Some(&mut stats),
+        &mut errors,
+    );
+    errors.to_convert_error(src)?;
+    Ok((out, stats))
+}
+
+/// Similar to [`convert`], but also returns a source map pairing the range of the output each
+/// element was written to with the range of the source it came from.
+///
+/// Entries are ordered by where they start in the output, with each element before the elements
+/// inside it. Elements without a tag, which don't appear in the output, are left out, as are
+/// elements added by a transform (such as those added by [`OutputConfig::complete_page`]),
+/// which don't appear in the source.
+///
+/// # Example
+///
+/// ```
+/// # use mintyml::OutputConfig;
+/// let src = "section {\n  p> Hi\n}";
+/// let (html, source_map) = mintyml::convert_with_source_map(src, OutputConfig::new()).unwrap();
+///
+/// let (output, input) = &source_map[1];
+/// assert_eq!(&html[output.clone()], "<p>Hi</p>");
+/// assert_eq!(&src[input.start.position..input.end.position], "p> Hi");
+/// ```
+pub fn convert_with_source_map<'src>(
+    src: &'src str,
+    config: impl Borrow<OutputConfig<'src>>,
+) -> Result<(String, SourceMap), ConvertError<'src>> {
+    let config = config.borrow();
+    let mut out = String::new();
+    let mut source_map = Vec::new();
+    let mut errors = Errors::new(config);
+
+    let (Ok(()) | Err(InternalError)) = (|| {
+        let document = parse_and_transform(src, config, &mut errors)?;
+        if !errors.is_empty() {
+            return Ok(());
+        }
+        write_html(
+            src,
+            &document,
+            &mut out,
+            config,
+            &mut errors,
+            Some(&mut source_map),
+        )
+    })();
+
+    errors.to_convert_error(src)?;
+    Ok((out, source_map))
+}
+
+/// Similar to [`convert`], but produces a canonical form of the HTML suited to snapshot tests,
+/// so that documents differing only in style convert to the same string.
+///
+/// The output has block elements on their own lines indented by two spaces, attributes in sorted
+/// order, runs of whitespace in text collapsed to a single space, and `"\n"` line endings.
+/// Other options in `config` are respected.
+///
+/// # Example
+///
+/// ```
+/// # use mintyml::OutputConfig;
+/// let a = mintyml::convert_normalized("div[title=x]#a { p>   Hi }", OutputConfig::new());
+/// let b = mintyml::convert_normalized("#a[title=x] {\n    Hi\n}", OutputConfig::new());
+///
+/// assert_eq!(a.unwrap(), b.unwrap());
+/// ```
+pub fn convert_normalized<'src>(
+    src: &'src str,
+    config: impl Borrow<OutputConfig<'src>>,
+) -> Result<String, ConvertError<'src>> {
+    let mut config = config
+        .borrow()
+        .clone()
+        .indent("  ")
+        .line_ending(LineEnding::Lf)
+        .sort_attributes(true)
+        .collapse_text_whitespace(true);
+    config.max_width = None;
+    convert(src, config)
 }
 
 /// Converts the given MinTyML string `src` using `config` for configuration options.
@@ -152,6 +441,212 @@ pub fn convert_to<'src>(
     convert_to_internal(src, config.borrow(), out, true)
 }
 
+/// Converts the given MinTyML string `src` using `config` for configuration options.
+/// The converted HTML document will be written to the byte sink `out` as UTF-8.
+///
+/// As with [`convert_to`], if `src` has errors, the best-effort output is still written to `out`.
+/// If writing to `out` fails, this returns [`ConvertError::Unknown`].
+///
+/// # Example
+///
+/// ```
+/// # use mintyml::OutputConfig;
+/// let mut out = Vec::<u8>::new();
+/// mintyml::convert_to_io("p> Hello", OutputConfig::new(), &mut out).unwrap();
+///
+/// assert_eq!(out, b"<p>Hello</p>");
+/// ```
+#[cfg(feature = "std")]
+pub fn convert_to_io<'src>(
+    src: &'src str,
+    config: impl Borrow<OutputConfig<'src>>,
+    out: &mut impl std::io::Write,
+) -> Result<(), ConvertError<'src>> {
+    use std::io::Write;
+
+    let mut writer = utils::IoWriter(std::io::BufWriter::new(out));
+    let result = convert_to_internal(src, config.borrow(), &mut writer, true);
+    writer.0.flush().map_err(|_| ConvertError::Unknown)?;
+    result
+}
+
+/// Converts the given MinTyML string `src` using `config` for configuration options,
+/// writing the HTML for each top-level block to `out` as soon as it has been converted.
+///
+/// The document is split at blank lines between top-level elements, and each block is parsed
+/// and converted on its own, so only one block needs to be held in memory at a time.
+/// Because inference may depend on sibling nodes, blocks are never smaller than a
+/// single top-level node. The output is the same as [`convert_to`].
+///
+/// Options that need the whole document at once ([`OutputConfig::complete_page`],
+/// [`OutputConfig::root_element`], [`OutputConfig::auto_html_wrap`], [`OutputConfig::metadata`],
+/// [`OutputConfig::max_width`], [`OutputConfig::max_output_bytes`],
+/// [`OutputConfig::on_diagnostic`], [`OutputConfig::lang_scope`] set to
+/// [`LangScope::RootOnly`], [`OutputConfig::element_visitor`], [`OutputConfig::final_newline`],
+/// [`OutputConfig::check_duplicate_ids`], [`OutputConfig::namespaces`],
+/// [`OutputConfig::omit_optional_end_tags`], and [`OutputConfig::emit_comments`] set to `false`)
+/// fall back to converting the document in one pass.
+/// If a block fails to parse on its own, the rest of the document is converted in one pass.
+///
+/// If an error is found, the output of the blocks before it is left in `out`. Unlike
+/// [`convert_to`], no best-effort output is written for the block with the error or anything
+/// after it, but the remaining blocks are still converted so their errors are reported too.
+///
+/// # Example
+///
+/// ```
+/// # use mintyml::OutputConfig;
+/// let mut out = String::new();
+/// mintyml::convert_streaming(r#"
+/// h1> Title
+///
+/// section {
+///     Hello, world!
+/// }
+///
+/// Goodbye.
+/// "#, OutputConfig::new(), &mut out).unwrap();
+///
+/// assert_eq!(out, concat!(
+///     "<h1>Title</h1>",
+///     " <section><p>Hello, world!</p></section>",
+///     " <p>Goodbye.</p>",
+/// ));
+/// ```
+pub fn convert_streaming<'src>(
+    src: &'src str,
+    config: impl Borrow<OutputConfig<'src>>,
+    out: &mut impl fmt::Write,
+) -> Result<(), ConvertError<'src>> {
+    let config = config.borrow();
+    if !streaming::can_stream(config) {
+        return convert_to_internal(src, config, out, false);
+    }
+
+    convert_blocks(src, config, out, |block, buf| {
+        convert_to_internal(block, config, buf, false)
+    })
+}
+
+/// Converts `src` like [`convert`], reusing the output of top-level blocks that are unchanged
+/// since the last conversion with `cache`.
+///
+/// This is meant for editors that convert the same document again after each edit. The document
+/// is split into blocks the same way as in [`convert_streaming`], so only the blocks touched by
+/// an edit are parsed and converted again. With options that need the whole document at once
+/// (any config that [`convert_streaming`] would convert in one pass), the whole document is
+/// converted like [`convert`], and the output is only reused if the document hasn't changed at
+/// all.
+///
+/// The cache only holds the blocks of the most recent conversion, and errors are never cached.
+///
+/// # Example
+///
+/// ```
+/// # use mintyml::{ConvertCache, OutputConfig};
+/// let mut cache = ConvertCache::new();
+/// let config = OutputConfig::new();
+///
+/// let out = mintyml::convert_with_cache("h1> Title\n\np> One", &config, &mut cache).unwrap();
+/// assert_eq!(out, "<h1>Title</h1> <p>One</p>");
+///
+/// // Only the second block is converted again.
+/// let out = mintyml::convert_with_cache("h1> Title\n\np> Two", &config, &mut cache).unwrap();
+/// assert_eq!(out, "<h1>Title</h1> <p>Two</p>");
+/// ```
+pub fn convert_with_cache<'src>(
+    src: &'src str,
+    config: impl Borrow<OutputConfig<'src>>,
+    cache: &mut ConvertCache,
+) -> Result<String, ConvertError<'src>> {
+    let config = config.borrow();
+    let mut blocks = BTreeMap::new();
+    let mut out = String::new();
+
+    let mut convert_block = |block: &'src str, buf: &mut String| {
+        let hash = streaming::hash(block);
+        let cached = match cache.take(hash, block) {
+            Some(cached) => cached,
+            None => {
+                let mut output = String::new();
+                convert_to_internal(block, config, &mut output, false)?;
+                CachedBlock {
+                    src: block.into(),
+                    output,
+                }
+            }
+        };
+        buf.push_str(&cached.output);
+        blocks.insert(hash, cached);
+        Ok(())
+    };
+
+    if streaming::can_stream(config) {
+        convert_blocks(src, config, &mut out, convert_block)?;
+    } else {
+        convert_block(src, &mut out)?;
+    }
+
+    cache.replace(blocks);
+    Ok(out)
+}
+
+/// Splits `src` into top-level blocks that can be converted on their own, converting each with
+/// `convert_block` and writing the results to `out`.
+fn convert_blocks<'src>(
+    src: &'src str,
+    config: &OutputConfig<'src>,
+    out: &mut impl fmt::Write,
+    mut convert_block: impl FnMut(&'src str, &mut String) -> Result<(), ConvertError<'src>>,
+) -> Result<(), ConvertError<'src>> {
+    let separator = if config.indent.is_some() { "" } else { " " };
+    let mut buf = String::new();
+    let mut wrote_any = false;
+    let mut error = None::<ConvertError<'src>>;
+    let mut start = 0;
+
+    while start < src.len() {
+        let mut end = streaming::next_boundary(src, start);
+
+        let block = loop {
+            let block = &src[start..end.unwrap_or(src.len())];
+            buf.clear();
+            if block.trim().is_empty() {
+                break block;
+            }
+
+            match convert_block(block, &mut buf) {
+                Ok(()) => break block,
+                // The block may have been split in the wrong place, so convert the rest of the
+                // document at once rather than trying each longer prefix in turn.
+                Err(ConvertError::Syntax { .. }) if end.is_some() => end = None,
+                Err(err) => {
+                    let err = streaming::shift_error(err, src, start);
+                    error = Some(match error {
+                        Some(first) => streaming::merge_errors(first, err, config.max_errors),
+                        None => err,
+                    });
+                    break block;
+                }
+            }
+        };
+
+        // Once a block has failed, the rest are only converted to report their errors.
+        if error.is_none() && !buf.is_empty() {
+            if wrote_any {
+                out.write_str(separator)
+                    .map_err(|_| ConvertError::Unknown)?;
+            }
+            out.write_str(&buf).map_err(|_| ConvertError::Unknown)?;
+            wrote_any = true;
+        }
+
+        start += block.len();
+    }
+
+    error.map_or(Ok(()), Err)
+}
+
 fn convert_to_internal<'src>(
     src: &'src str,
     config: &OutputConfig<'src>,
@@ -159,21 +654,61 @@ fn convert_to_internal<'src>(
     forgive: bool,
 ) -> Result<(), ConvertError<'src>> {
     let mut errors = Errors::new(config);
+    convert_with_errors(src, config, out, forgive, None, None, &mut errors);
+    errors.to_convert_error(src)
+}
 
+fn convert_with_errors<'src>(
+    src: &'src str,
+    config: &OutputConfig<'src>,
+    out: &mut impl fmt::Write,
+    forgive: bool,
+    text_out: Option<&mut String>,
+    stats_out: Option<&mut ConvertStats>,
+    errors: &mut Errors,
+) {
     let (Ok(()) | Err(InternalError)) = (|| {
-        let mut document = Document::parse(src, &mut errors)?;
-        document = transform::transform_document(document, src, config, &mut errors)?;
+        let document = parse_and_transform(src, config, errors)?;
 
         if errors.is_empty() || forgive {
-            output::output_html_to(src, &document, out, config)
-                .map_err(|e| match e {
-                    OutputError::WriteError(fmt::Error) => ConvertError::Unknown,
-                })
-                .or_else(|_| errors.unknown())?;
+            write_html(src, &document, out, config, errors, None)?;
+        }
+
+        if let Some(text_out) = text_out {
+            output::output_text_to(src, &document, text_out).or_else(|_| errors.unknown())?;
+        }
+
+        if let Some(stats_out) = stats_out {
+            *stats_out = stats::collect_stats(&document);
         }
 
         Ok(())
     })();
+}
 
-    errors.to_convert_error(src)
+fn parse_and_transform<'src>(
+    src: &'src str,
+    config: &OutputConfig<'src>,
+    errors: &mut Errors,
+) -> InternalResult<Document<'src>> {
+    let document = Document::parse(src, config, errors)?;
+    if config.strict.unwrap_or(false) && errors.has_delimiter_errors() {
+        return Err(InternalError);
+    }
+    transform::transform_document(document, src, config, errors)
+}
+
+fn write_html<'src>(
+    src: &'src str,
+    document: &Document<'src>,
+    out: &mut impl fmt::Write,
+    config: &OutputConfig<'src>,
+    errors: &mut Errors,
+    source_map: Option<&mut SourceMap>,
+) -> InternalResult {
+    match output::output_html_to(src, document, out, config, errors.count(), source_map) {
+        Ok(()) => Ok(()),
+        Err(OutputError::WriteError(fmt::Error)) => errors.unknown(),
+        Err(OutputError::TooLarge { limit }) => errors.output_too_large(limit),
+    }
 }
@@ -0,0 +1,37 @@
+use core::fmt::{self, Write};
+
+/// A writer that fails once writing would exceed a maximum number of bytes.
+pub struct LimitWriter<W> {
+    inner: W,
+    limit: Option<usize>,
+    written: usize,
+    exceeded: bool,
+}
+
+impl<W: Write> LimitWriter<W> {
+    pub fn new(inner: W, limit: Option<usize>) -> Self {
+        Self {
+            inner,
+            limit,
+            written: 0,
+            exceeded: false,
+        }
+    }
+
+    /// The limit, if a write was rejected for exceeding it.
+    pub fn exceeded_limit(&self) -> Option<usize> {
+        self.limit.filter(|_| self.exceeded)
+    }
+}
+
+impl<W: Write> Write for LimitWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let written = self.written + s.len();
+        if self.limit.is_some_and(|limit| written > limit) {
+            self.exceeded = true;
+            return Err(fmt::Error);
+        }
+        self.written = written;
+        self.inner.write_str(s)
+    }
+}
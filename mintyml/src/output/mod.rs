@@ -1,22 +1,34 @@
+mod limit;
+mod text;
 mod utils;
+mod wrap;
 
 use core::{
     fmt::{self, Write},
     mem,
 };
 
-use alloc::string::String;
+use alloc::{borrow::Cow, collections::BTreeMap, format, string::String, vec::Vec};
+
+use gramma::parse::LocationRange;
 
 use crate::{
     document::{
-        Comment, Content, Document, Element, Node, NodeType, Selector, Space, TextLike, TextSlice,
+        Comment, Content, Document, Element, ElementType, Node, NodeType, Selector, Space,
+        TextLike, TextSlice,
     },
-    escape::{unescape_parts, UnescapePart},
+    escape::{unescape_parts, EscapeError, UnescapePart},
     utils::{default, to_lowercase},
-    OutputConfig,
+    OutputConfig, SourceMap, SpacesPolicy, TagCase,
+};
+
+use self::{
+    limit::LimitWriter,
+    utils::{collapse_whitespace, trim_multiline},
+    wrap::WrapWriter,
 };
 
-use self::utils::trim_multiline;
+pub use self::text::output_text_to;
 
 fn is_void(tag: &str) -> bool {
     match tag {
@@ -26,6 +38,180 @@ fn is_void(tag: &str) -> bool {
     }
 }
 
+/// Whether the lowercase `tag` is a standard void element or one of
+/// [`OutputConfig::void_elements`].
+pub(crate) fn is_configured_void(tag: &str, config: &OutputConfig) -> bool {
+    is_void(tag)
+        || config
+            .void_elements
+            .iter()
+            .flatten()
+            .any(|custom| custom.eq_ignore_ascii_case(tag))
+}
+
+/// Standard attributes whose presence alone means `true`.
+const BOOLEAN_ATTRIBUTES: &[&str] = &[
+    "allowfullscreen",
+    "async",
+    "autofocus",
+    "autoplay",
+    "checked",
+    "controls",
+    "default",
+    "defer",
+    "disabled",
+    "formnovalidate",
+    "hidden",
+    "inert",
+    "ismap",
+    "itemscope",
+    "loop",
+    "multiple",
+    "muted",
+    "nomodule",
+    "novalidate",
+    "open",
+    "playsinline",
+    "readonly",
+    "required",
+    "reversed",
+    "selected",
+];
+
+/// Whether `name` is a standard boolean attribute or one of [`OutputConfig::boolean_attributes`].
+fn is_configured_boolean_attribute(name: &str, config: &OutputConfig) -> bool {
+    BOOLEAN_ATTRIBUTES
+        .iter()
+        .copied()
+        .chain(config.boolean_attributes.iter().flatten().map(|n| &**n))
+        .any(|n| n.eq_ignore_ascii_case(name))
+}
+
+/// Sort key for an attribute name in canonical XML order: namespace declarations,
+/// then unprefixed attributes, then prefixed attributes grouped by prefix.
+fn canonical_attr_key(name: &str) -> (u8, &str, &str) {
+    match name.split_once(':') {
+        None if name == "xmlns" => (0, "", ""),
+        Some(("xmlns", local)) => (1, "", local),
+        None => (2, "", name),
+        Some((prefix, local)) => (3, prefix, local),
+    }
+}
+
+/// Elements whose whitespace should not be changed.
+fn is_preformatted(tag: &str) -> bool {
+    matches!(tag, "pre" | "textarea")
+}
+
+/// Elements whose raw text should be wrapped in a CDATA section in XML mode.
+fn needs_cdata(tag: &str) -> bool {
+    matches!(tag, "script" | "style")
+}
+
+/// Whether `node` is whitespace within a line, like the space in `<(a)> <(b)>`.
+fn is_inline_space(node: &Node) -> bool {
+    matches!(
+        node.node_type,
+        NodeType::TextLike {
+            text_like: TextLike::Space {
+                space: Space::Inline { .. }
+            }
+        }
+    )
+}
+
+/// Whether `node` is an inline special element like `<#strong#>` or an inline element chain like
+/// `<(b> bold)>`.
+fn is_special(node: &Node) -> bool {
+    node.as_element().is_some_and(|e| {
+        matches!(
+            e.element_type,
+            ElementType::Special { .. } | ElementType::Inline { .. }
+        )
+    })
+}
+
+/// What comes after an element among its siblings.
+#[derive(Debug, Clone)]
+enum Following<'a> {
+    /// Nothing; the element is the last content of its parent.
+    End,
+    /// An element with the given output tag.
+    Element(Cow<'a, str>),
+    /// Text, a comment, or an element with no tag.
+    Other,
+}
+
+fn is_any(tag: &str, tags: &[&str]) -> bool {
+    tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+}
+
+/// Whether HTML allows the end tag of the lowercase `tag` to be omitted when it's followed by
+/// `following` within the element `parent`.
+///
+/// `parent` is `None` if the parent element isn't known, e.g. at the top level of a fragment.
+/// See <https://html.spec.whatwg.org/multipage/syntax.html#optional-tags>.
+fn can_omit_end_tag(tag: &str, following: Following, parent: Option<&str>) -> bool {
+    const P_CLOSERS: &[&str] = &[
+        "address",
+        "article",
+        "aside",
+        "blockquote",
+        "details",
+        "dialog",
+        "div",
+        "dl",
+        "fieldset",
+        "figcaption",
+        "figure",
+        "footer",
+        "form",
+        "h1",
+        "h2",
+        "h3",
+        "h4",
+        "h5",
+        "h6",
+        "header",
+        "hgroup",
+        "hr",
+        "main",
+        "menu",
+        "nav",
+        "ol",
+        "p",
+        "pre",
+        "search",
+        "section",
+        "table",
+        "ul",
+    ];
+    const P_KEEP_IN: &[&str] = &["a", "audio", "del", "ins", "map", "noscript", "video"];
+
+    let at_end = matches!(following, Following::End) && parent.is_some();
+    let followed_by =
+        |tags: &[&str]| matches!(&following, Following::Element(next) if is_any(next, tags));
+
+    match tag {
+        "li" => followed_by(&["li"]) || at_end,
+        "dt" => followed_by(&["dt", "dd"]),
+        "dd" => followed_by(&["dd", "dt"]) || at_end,
+        "p" => {
+            followed_by(P_CLOSERS)
+                || at_end && parent.is_some_and(|p| !is_any(p, P_KEEP_IN) && !p.contains('-'))
+        }
+        "rt" | "rp" => followed_by(&["rt", "rp"]) || at_end,
+        "optgroup" => followed_by(&["optgroup", "hr"]) || at_end,
+        "option" => followed_by(&["option", "optgroup", "hr"]) || at_end,
+        "thead" => followed_by(&["tbody", "tfoot"]),
+        "tbody" => followed_by(&["tbody", "tfoot"]) || at_end,
+        "tfoot" => at_end,
+        "tr" => followed_by(&["tr"]) || at_end,
+        "td" | "th" => followed_by(&["td", "th"]) || at_end,
+        _ => false,
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 struct TagInfo {
     pub is_void: bool,
@@ -35,29 +221,59 @@ struct TagInfo {
 struct OutputContext<'cx, 'cfg, Out> {
     src: &'cfg str,
     string_buf: String,
-    out: &'cx mut Out,
+    /// The output. It tracks the current column on every write, so that soft breaks can be
+    /// replaced with line breaks once a line exceeds `max_width`.
+    out: WrapWriter<'cx, Out>,
     config: &'cx OutputConfig<'cfg>,
     indent_level: u32,
     element: Option<&'cx Element<'cfg>>,
+    /// The output tag of the innermost element being written, if it has a tag.
+    parent_tag: Option<Cow<'cx, str>>,
     follows_space: bool,
     is_raw: bool,
+    is_preformatted: bool,
+    /// Whether raw text should be wrapped in a CDATA section, which is the case inside `script`
+    /// and `style` elements in XML mode.
+    in_cdata: bool,
+    /// The number of errors found before output, for [`OutputConfig::annotate_errors_in_output`].
+    error_count: usize,
+    /// If `Some`, the marks at the start and end of each element written, along with the range of
+    /// the source it came from.
+    source_map: Option<Vec<(usize, usize, LocationRange)>>,
+    /// The replacements from [`OutputConfig::extra_escapes`], or `None` if there are none.
+    extra_escapes: Option<ExtraEscapes<'cx>>,
 }
 
+/// The UTF-8 leading byte of every character in `'\u{80}'..='\u{bf}'`.
+const LATIN1_SUPPLEMENT_LEAD: u8 = 0xc2;
+
 trait Escape {
-    fn get_escape(&self, ch: char) -> Option<EscapeKind>;
+    fn get_escape(&self, ch: char) -> Option<EscapeKind<'static>>;
     fn write_escape(&self, esc: EscapeKind, out: &mut impl Write) -> fmt::Result;
+
+    /// Whether every non-ASCII character is replaced, as with [`OutputConfig::ascii_only`].
+    fn ascii_only(&self) -> bool;
+
+    /// Returns `false` if `byte` can't be the first byte of a character that [`Escape::get_escape`]
+    /// would replace.
+    fn may_escape(&self, byte: u8) -> bool {
+        byte.is_ascii_control()
+            || matches!(byte, b'&' | b'<' | b'>' | b'"' | LATIN1_SUPPLEMENT_LEAD)
+            || self.ascii_only() && !byte.is_ascii()
+    }
 }
 
-enum EscapeKind {
+enum EscapeKind<'e> {
     Number(u32),
-    Special(&'static str),
+    Special(&'e str),
 }
 
-#[derive(Default)]
-struct HtmlEscape<const QUOTE: bool> {}
+struct HtmlEscape<const QUOTE: bool> {
+    ascii_only: bool,
+}
 
 impl<const QUOTE: bool> Escape for HtmlEscape<QUOTE> {
-    fn get_escape(&self, ch: char) -> Option<EscapeKind> {
+    fn get_escape(&self, ch: char) -> Option<EscapeKind<'static>> {
         match ch {
             '&' => Some(EscapeKind::Special("&amp;")),
             '<' => Some(EscapeKind::Special("&lt;")),
@@ -66,6 +282,7 @@ impl<const QUOTE: bool> Escape for HtmlEscape<QUOTE> {
             '\n' => Some(EscapeKind::Special("&NewLine;")),
             '"' if QUOTE => Some(EscapeKind::Special("&quot;")),
             '\u{0}'..='\u{1f}' | '\u{7f}'..='\u{9f}' => Some(EscapeKind::Number(ch as u32)),
+            _ if self.ascii_only && !ch.is_ascii() => Some(EscapeKind::Number(ch as u32)),
             _ => None,
         }
     }
@@ -76,19 +293,25 @@ impl<const QUOTE: bool> Escape for HtmlEscape<QUOTE> {
             EscapeKind::Special(s) => out.write_str(s),
         }
     }
+
+    fn ascii_only(&self) -> bool {
+        self.ascii_only
+    }
 }
 
-#[derive(Default)]
-struct XmlEscape<const QUOTE: bool> {}
+struct XmlEscape<const QUOTE: bool> {
+    ascii_only: bool,
+}
 
 impl<const QUOTE: bool> Escape for XmlEscape<QUOTE> {
-    fn get_escape(&self, ch: char) -> Option<EscapeKind> {
+    fn get_escape(&self, ch: char) -> Option<EscapeKind<'static>> {
         match ch {
             '&' => Some(EscapeKind::Special("&amp;")),
             '<' => Some(EscapeKind::Special("&lt;")),
             '>' => Some(EscapeKind::Special("&gt;")),
             '"' if QUOTE => Some(EscapeKind::Special("&quot;")),
             '\u{0}'..='\u{1f}' | '\u{7f}'..='\u{9f}' => Some(EscapeKind::Number(ch as u32)),
+            _ if self.ascii_only && !ch.is_ascii() => Some(EscapeKind::Number(ch as u32)),
             _ => None,
         }
     }
@@ -99,40 +322,90 @@ impl<const QUOTE: bool> Escape for XmlEscape<QUOTE> {
             EscapeKind::Special(s) => out.write_str(s),
         }
     }
+
+    fn ascii_only(&self) -> bool {
+        self.ascii_only
+    }
+}
+
+/// The replacements from [`OutputConfig::extra_escapes`], built once for each output.
+struct ExtraEscapes<'e> {
+    replacements: &'e BTreeMap<char, Cow<'e, str>>,
+    /// Whether each byte is the first byte of a character in `replacements`.
+    leads: [bool; 256],
 }
 
-struct EscapeWriter<W, E> {
+impl<'e> ExtraEscapes<'e> {
+    fn new(replacements: &'e BTreeMap<char, Cow<'e, str>>) -> Self {
+        let mut leads = [false; 256];
+        for &ch in replacements.keys() {
+            leads[ch.encode_utf8(&mut [0; 4]).as_bytes()[0] as usize] = true;
+        }
+
+        Self {
+            replacements,
+            leads,
+        }
+    }
+}
+
+struct EscapeWriter<'e, W, E> {
     inner: W,
     escape: E,
+    /// Replacements from [`OutputConfig::extra_escapes`], which take precedence over `escape`.
+    extra: Option<&'e ExtraEscapes<'e>>,
 }
 
-impl<W, E> EscapeWriter<W, E> {
-    fn new(inner: W) -> Self
-    where
-        E: Default,
-    {
+impl<'e, W, E: Escape> EscapeWriter<'e, W, E> {
+    fn new(inner: W, escape: E, extra: Option<&'e ExtraEscapes<'e>>) -> Self {
         Self {
             inner,
-            escape: default(),
+            escape,
+            extra,
+        }
+    }
+
+    fn may_escape(&self, byte: u8) -> bool {
+        self.extra.is_some_and(|e| e.leads[byte as usize]) || self.escape.may_escape(byte)
+    }
+
+    fn get_escape(&self, ch: char) -> Option<EscapeKind<'e>> {
+        match self.extra.and_then(|e| e.replacements.get(&ch)) {
+            Some(value) => Some(EscapeKind::Special(value)),
+            None => self.escape.get_escape(ch),
         }
     }
 }
 
-impl<W: Write, E: Escape> Write for EscapeWriter<W, E> {
+impl<W: Write, E: Escape> Write for EscapeWriter<'_, W, E> {
     fn write_str(&mut self, s: &str) -> fmt::Result {
-        let last_match = s
-            .char_indices()
-            .filter_map(|(idx, ch)| Some((idx, ch, self.escape.get_escape(ch)?)))
-            .try_fold(0, |last, (idx, ch, esc)| {
-                Ok({
-                    let slice = s.get(last..idx).unwrap_or_default();
-                    if !slice.is_empty() {
-                        self.inner.write_str(slice)?;
-                    }
-                    self.escape.write_escape(esc, &mut self.inner)?;
-                    idx + ch.len_utf8()
-                })
-            })?;
+        let bytes = s.as_bytes();
+        let mut last_match = 0;
+        let mut idx = 0;
+
+        // Skip over runs of bytes that can't begin an escaped character so unescaped
+        // text is written with as few calls as possible.
+        while let Some(offset) = bytes
+            .get(idx..)
+            .and_then(|rest| rest.iter().position(|&b| self.may_escape(b)))
+        {
+            idx += offset;
+
+            let Some(ch) = s.get(idx..).and_then(|rest| rest.chars().next()) else {
+                break;
+            };
+
+            if let Some(esc) = self.get_escape(ch) {
+                let slice = s.get(last_match..idx).unwrap_or_default();
+                if !slice.is_empty() {
+                    self.inner.write_str(slice)?;
+                }
+                self.escape.write_escape(esc, &mut self.inner)?;
+                last_match = idx + ch.len_utf8();
+            }
+
+            idx += ch.len_utf8();
+        }
 
         let rest = s.get(last_match..).unwrap_or_default();
         if !rest.is_empty() {
@@ -142,17 +415,20 @@ impl<W: Write, E: Escape> Write for EscapeWriter<W, E> {
     }
 
     fn write_char(&mut self, ch: char) -> fmt::Result {
-        match self.escape.get_escape(ch) {
+        match self.get_escape(ch) {
             Some(esc) => self.escape.write_escape(esc, &mut self.inner),
             None => self.inner.write_char(ch),
         }
     }
 }
 
+/// Writes `src` with its escape sequences replaced. Invalid escape sequences have already been
+/// reported as errors, so they're written as-is to keep forgiving output complete.
 fn write_unescaped(src: &str, mut out: impl Write) -> fmt::Result {
-    unescape_parts(src, None).try_for_each(|part| match part.map_err(|_| default())? {
-        UnescapePart::Slice(s) => out.write_str(s),
-        UnescapePart::Char(c) => out.write_char(c),
+    unescape_parts(src, None).try_for_each(|part| match part {
+        Ok(UnescapePart::Slice(s)) => out.write_str(s),
+        Ok(UnescapePart::Char(c)) => out.write_char(c),
+        Err(EscapeError { range }) => out.write_str(range.slice(src)),
     })
 }
 
@@ -161,7 +437,7 @@ where
     Out: Write,
 {
     fn is_xml(&self) -> bool {
-        self.config.xml == Some(true)
+        self.config.xml == Some(true) || self.config.xhtml_strict == Some(true)
     }
 
     fn format_inline(&self) -> bool {
@@ -172,47 +448,89 @@ where
         self.is_raw
     }
 
+    /// Whether whitespace may be replaced with line breaks to keep lines within `max_width`.
+    fn can_wrap(&self) -> bool {
+        !self.is_raw && !self.is_preformatted
+    }
+
+    /// Writes `space`, which may be replaced with a line break if the line is too long.
+    fn soft_space(&mut self, space: &str) -> OutputResult {
+        if self.can_wrap() {
+            self.out.soft_break(space, self.indent_level + 1)?;
+        } else {
+            self.out.write_str(space)?;
+        }
+        Ok(())
+    }
+
     fn slice<'s>(&self, s: &'s TextSlice<'cfg>) -> &'s str {
         s.as_str(self.src)
     }
 
+    /// The tag name to write for `tag`, according to [`OutputConfig::tag_case`] and
+    /// [`OutputConfig::tag_rewrite`].
+    fn output_tag<'s>(&self, tag: &'s TextSlice<'cfg>) -> Cow<'s, str> {
+        let tag = self.slice(tag);
+        let tag: Cow<str> = match self.config.tag_case.unwrap_or_default() {
+            TagCase::Lower if tag.bytes().any(|b| b.is_ascii_uppercase()) => {
+                tag.to_ascii_lowercase().into()
+            }
+            _ => tag.into(),
+        };
+        match self.config.tag_rewrite.as_ref().and_then(|r| r.call(&tag)) {
+            Some(rewritten) => rewritten.into_owned().into(),
+            None => tag,
+        }
+    }
+
     fn write_escape_unescape(&mut self, src: &str, quote: bool) -> OutputResult {
+        let ascii_only = self.config.ascii_only == Some(true);
+        let extra = self.extra_escapes.as_ref();
         if self.is_xml() {
             if quote {
-                write_unescaped(src, EscapeWriter::<_, XmlEscape<true>>::new(&mut *self.out))
+                write_unescaped(
+                    src,
+                    EscapeWriter::new(&mut self.out, XmlEscape::<true> { ascii_only }, extra),
+                )
             } else {
                 write_unescaped(
                     src,
-                    EscapeWriter::<_, XmlEscape<false>>::new(&mut *self.out),
+                    EscapeWriter::new(&mut self.out, XmlEscape::<false> { ascii_only }, extra),
                 )
             }
         } else {
             if quote {
                 write_unescaped(
                     src,
-                    EscapeWriter::<_, HtmlEscape<true>>::new(&mut *self.out),
+                    EscapeWriter::new(&mut self.out, HtmlEscape::<true> { ascii_only }, extra),
                 )
             } else {
                 write_unescaped(
                     src,
-                    EscapeWriter::<_, HtmlEscape<false>>::new(&mut *self.out),
+                    EscapeWriter::new(&mut self.out, HtmlEscape::<false> { ascii_only }, extra),
                 )
             }
         }
         .map_err(Into::into)
     }
     fn write_escape(&mut self, src: &str, quote: bool) -> OutputResult {
+        let ascii_only = self.config.ascii_only == Some(true);
+        let extra = self.extra_escapes.as_ref();
         if self.is_xml() {
             if quote {
-                EscapeWriter::<_, XmlEscape<true>>::new(&mut *self.out).write_str(src)
+                EscapeWriter::new(&mut self.out, XmlEscape::<true> { ascii_only }, extra)
+                    .write_str(src)
             } else {
-                EscapeWriter::<_, XmlEscape<false>>::new(&mut *self.out).write_str(src)
+                EscapeWriter::new(&mut self.out, XmlEscape::<false> { ascii_only }, extra)
+                    .write_str(src)
             }
         } else {
             if quote {
-                EscapeWriter::<_, HtmlEscape<true>>::new(&mut *self.out).write_str(src)
+                EscapeWriter::new(&mut self.out, HtmlEscape::<true> { ascii_only }, extra)
+                    .write_str(src)
             } else {
-                EscapeWriter::<_, HtmlEscape<false>>::new(&mut *self.out).write_str(src)
+                EscapeWriter::new(&mut self.out, HtmlEscape::<false> { ascii_only }, extra)
+                    .write_str(src)
             }
         }
         .map_err(Into::into)
@@ -247,19 +565,24 @@ where
     }
 
     fn write_unescape(&mut self, src: &str) -> OutputResult {
-        write_unescaped(src, &mut *self.out).map_err(Into::into)
+        write_unescaped(src, &mut self.out).map_err(Into::into)
     }
 
     fn get_info(&mut self, tag: &str) -> TagInfo {
         let is_root = tag.eq_ignore_ascii_case("html");
-        let is_void = !self.is_xml() && is_void(to_lowercase(tag, &mut self.string_buf));
+        let is_void = !self.is_xml()
+            && is_configured_void(to_lowercase(tag, &mut self.string_buf), self.config);
 
         TagInfo { is_void, is_root }
     }
 
+    fn line_ending(&self) -> &'static str {
+        self.config.line_ending.unwrap_or_default().as_str()
+    }
+
     fn _line(&mut self) -> OutputResult {
         if let Some(indent) = self.config.indent.as_deref() {
-            self.out.write_char('\n')?;
+            self.out.write_str(self.line_ending())?;
             for _ in 0..self.indent_level {
                 self.out.write_str(indent)?;
             }
@@ -280,15 +603,20 @@ where
     fn space(&mut self, space: &Space) -> OutputResult {
         if !self.follows_space {
             match space {
-                Space::Inline { slice: Some(slice) } => self.out.write_str(self.slice(slice))?,
+                Space::Inline { slice: Some(slice) }
+                    if !(self.config.collapse_text_whitespace.unwrap_or(false)
+                        && self.can_wrap()) =>
+                {
+                    self.soft_space(self.slice(slice))?
+                }
                 Space::LineEnd { .. } | Space::ParagraphEnd { .. }
                     if !self.format_inline() && self.config.indent.is_some() =>
                 {
                     self._line()?
                 }
-                Space::Inline { slice: None }
-                | Space::LineEnd { .. }
-                | Space::ParagraphEnd { .. } => self.out.write_str(" ")?,
+                Space::Inline { .. } | Space::LineEnd { .. } | Space::ParagraphEnd { .. } => {
+                    self.soft_space(" ")?
+                }
             }
             self.follows_space = true;
         }
@@ -314,38 +642,65 @@ where
         }
     }
 
+    /// Runs `f` with the content of `element`, whose innermost output tag is `tag`, as the
+    /// content being written.
     fn in_content<T>(
         &mut self,
         element: &'cx Element<'cfg>,
+        mut tag: Option<Cow<'cx, str>>,
         f: impl FnOnce(&mut Self) -> OutputResult<T>,
     ) -> OutputResult<T> {
         let mut is_raw = self.is_raw || element.is_raw();
+        let mut is_preformatted = self.is_preformatted
+            || element.selectors.iter().any(|s| {
+                s.tag.name().is_some_and(|tag| {
+                    is_preformatted(to_lowercase(self.slice(tag), &mut self.string_buf))
+                })
+            });
+        let mut in_cdata = self.in_cdata
+            || self.is_xml()
+                && element.is_raw()
+                && element.selectors.last().is_some_and(|s| {
+                    s.tag.name().is_some_and(|tag| {
+                        needs_cdata(to_lowercase(self.slice(tag), &mut self.string_buf))
+                    })
+                });
         let mut element = Some(element);
         mem::swap(&mut element, &mut self.element);
+        mem::swap(&mut tag, &mut self.parent_tag);
         mem::swap(&mut self.is_raw, &mut is_raw);
+        mem::swap(&mut self.is_preformatted, &mut is_preformatted);
+        mem::swap(&mut self.in_cdata, &mut in_cdata);
         let out = f(self);
+        self.in_cdata = in_cdata;
+        self.is_preformatted = is_preformatted;
         self.is_raw = is_raw;
+        self.parent_tag = tag;
         self.element = element;
         out
     }
 
-    fn write_open_tag(
-        &mut self,
-        tag: &str,
-        selector: &Selector<'cfg>,
-        self_close: bool,
-    ) -> OutputResult {
-        write!(self.out, "<{tag}")?;
+    fn write_id(&mut self, id: &TextSlice<'cfg>) -> OutputResult {
+        self.out.soft_break(" ", self.indent_level + 1)?;
+        self.out.write_str("id=\"")?;
+        self.write_escape_unescape(self.slice(id), true)?;
+        self.out.write_char('"')?;
+        Ok(())
+    }
 
-        if let Some(id) = selector.id() {
-            self.out.write_str(" id=\"")?;
-            self.write_escape_unescape(self.slice(id), true)?;
-            self.out.write_char('"')?;
-        }
+    fn write_class(&mut self, selector: &Selector<'cfg>) -> OutputResult {
+        let (src, dedup) = (self.src, self.config.dedup_classes.unwrap_or(true));
+        let mut written = Vec::<&str>::new();
+        let mut class_names = selector.class_names().filter(|class| {
+            let class = class.as_str(src);
+            let is_new = !dedup || !written.contains(&class);
+            written.push(class);
+            is_new
+        });
 
-        let mut class_names = selector.class_names();
         if let Some(first) = class_names.next() {
-            self.out.write_str(" class=\"")?;
+            self.out.soft_break(" ", self.indent_level + 1)?;
+            self.out.write_str("class=\"")?;
             self.write_escape_unescape(self.slice(first), true)?;
 
             for class in class_names {
@@ -355,17 +710,99 @@ where
 
             self.out.write_char('"')?;
         }
+        Ok(())
+    }
 
-        for (name, value) in selector.attributes() {
-            self.out.write_char(' ')?;
-            self.write_unescape(self.slice(name))?;
-
-            if let Some(value) = value {
-                self.out.write_str("=\"")?;
-                self.write_escape_unescape(self.slice(value), true)?;
-                self.out.write_char('"')?;
-            } else if self.is_xml() {
-                self.out.write_str("=\"\"")?;
+    fn write_attribute(
+        &mut self,
+        name: &TextSlice<'cfg>,
+        value: Option<&TextSlice<'cfg>>,
+    ) -> OutputResult {
+        self.out.soft_break(" ", self.indent_level + 1)?;
+        self.write_unescape(self.slice(name))?;
+
+        let value = value.filter(|value| {
+            let (name, value) = (self.slice(name), self.slice(value));
+            !(self.config.minimize_boolean_attributes == Some(true)
+                && !self.is_xml()
+                && (value.is_empty() || value.eq_ignore_ascii_case(name))
+                && is_configured_boolean_attribute(name, self.config))
+        });
+
+        if let Some(value) = value {
+            self.out.write_str("=\"")?;
+            self.write_escape_unescape(self.slice(value), true)?;
+            self.out.write_char('"')?;
+        } else if self.is_xml() {
+            self.out.write_str("=\"\"")?;
+        }
+        Ok(())
+    }
+
+    /// Writes the id, classes, and attributes of `selector` sorted by [`canonical_attr_key`].
+    fn write_canonical_attributes(&mut self, selector: &Selector<'cfg>) -> OutputResult {
+        enum Attr<'s, 'cfg> {
+            Id(&'s TextSlice<'cfg>),
+            Class,
+            Other(&'s TextSlice<'cfg>, Option<&'s TextSlice<'cfg>>),
+        }
+
+        let src = self.src;
+        let mut attrs = Vec::from_iter(
+            selector
+                .id()
+                .map(|id| ("id", Attr::Id(id)))
+                .into_iter()
+                .chain(
+                    selector
+                        .class_names()
+                        .next()
+                        .map(|_| ("class", Attr::Class)),
+                )
+                .chain(
+                    selector
+                        .attributes()
+                        .map(|(n, v)| (n.as_str(src), Attr::Other(n, v))),
+                ),
+        );
+        attrs.sort_by(|(a, _), (b, _)| canonical_attr_key(a).cmp(&canonical_attr_key(b)));
+
+        for (_, attr) in attrs {
+            match attr {
+                Attr::Id(id) => self.write_id(id)?,
+                Attr::Class => self.write_class(selector)?,
+                Attr::Other(name, value) => self.write_attribute(name, value)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn write_open_tag(
+        &mut self,
+        tag: &str,
+        selector: &Selector<'cfg>,
+        self_close: bool,
+    ) -> OutputResult {
+        write!(self.out, "<{tag}")?;
+
+        if self.is_xml() && self.config.xml_canonical_attrs == Some(true) {
+            self.write_canonical_attributes(selector)?;
+        } else {
+            if let Some(id) = selector.id() {
+                self.write_id(id)?;
+            }
+            self.write_class(selector)?;
+
+            let src = self.src;
+            let mut attributes = Vec::from_iter(selector.attributes());
+            if self.config.sort_attributes == Some(true) {
+                attributes.sort_by(|(a, _), (b, _)| {
+                    canonical_attr_key(a.as_str(src)).cmp(&canonical_attr_key(b.as_str(src)))
+                });
+            }
+
+            for (name, value) in attributes {
+                self.write_attribute(name, value)?;
             }
         }
 
@@ -383,85 +820,196 @@ where
         Ok(())
     }
 
-    fn process_element(&mut self, element: &'cx Element<'cfg>) -> OutputResult {
-        let get_valid_tags =
-            |e: &'cx Element<'cfg>| e.selectors.iter().filter_map(|s| Some((s.tag.name()?, s)));
+    /// Whether `content` has no nodes, or only comments and spaces when comments aren't emitted.
+    fn is_empty(&self, content: &Content) -> bool {
+        content.nodes.is_empty()
+            || !self.config.emit_comments.unwrap_or(true)
+                && content.nodes.iter().all(|n| n.is_space() || n.is_comment())
+    }
+
+    /// The output tag of each tag of `element`, from [`Self::output_tag`].
+    ///
+    /// [`OutputConfig::tag_rewrite`] is called once for each tag, so this should only be called
+    /// once for each element written.
+    fn output_tags(&self, element: &'cx Element<'cfg>) -> Vec<Cow<'cx, str>> {
+        Vec::from_iter(
+            element
+                .selectors
+                .iter()
+                .filter_map(|s| Some(self.output_tag(s.tag.name()?))),
+        )
+    }
 
-        let mut opening_tags = get_valid_tags(element).peekable();
+    /// Writes `element`, whose output tags are `tags`, from [`Self::output_tags`].
+    fn process_element(
+        &mut self,
+        element: &'cx Element<'cfg>,
+        following: Following<'cx>,
+        tags: Vec<Cow<'cx, str>>,
+    ) -> OutputResult {
+        let selectors = element.selectors.iter().filter(|s| s.tag.name().is_some());
+        let mut opening_tags = tags.iter().zip(selectors).peekable();
 
         if opening_tags.peek().is_none() {
-            return self.in_content(element, |this| this.process_content(&element.content));
+            return self.in_content(element, None, |this| this.process_content(&element.content));
         }
 
-        let self_close_last = element.content.nodes.is_empty() && self.is_xml();
+        let self_close_last = self.is_empty(&element.content) && self.is_xml();
         let mut last_tag_info = TagInfo::default();
+        let start = self.source_map.is_some().then(|| self.out.mark_next());
 
         while let Some((tag, selector)) = opening_tags.next() {
-            let tag = self.slice(tag);
             let is_last = opening_tags.peek().is_none();
+            let tag_info = self.get_info(tag);
             if is_last {
-                last_tag_info = self.get_info(tag);
+                last_tag_info = tag_info;
             }
-            let self_closing = is_last && self_close_last;
-            self.write_open_tag(tag, &selector, self_closing)?;
+            // Void tags are only identified in HTML mode, so this won't affect XML output.
+            let self_closing = (is_last && self_close_last)
+                || (tag_info.is_void && self.config.self_close_void == Some(true));
+            self.write_open_tag(tag, selector, self_closing)?;
         }
 
-        self.in_content(element, |this| {
-            if !element.content.nodes.is_empty() {
-                this.indent(if last_tag_info.is_root { 0 } else { 1 }, |this| {
+        let omit_end_tags = self.config.omit_optional_end_tags == Some(true) && !self.is_xml();
+        let parent = self.parent_tag.clone();
+
+        self.in_content(element, tags.last().cloned(), |this| {
+            // The closing tags go on their own line after the content, if any are written.
+            let mut needs_line = false;
+            if !this.is_empty(&element.content) {
+                let indent_root = this.config.indent_html_children == Some(true);
+                let by = if last_tag_info.is_root && !indent_root {
+                    0
+                } else {
+                    1
+                };
+                this.indent(by, |this| {
                     this.line()?;
                     this.process_content(&element.content)
                 })?;
-                this.line()?;
+                needs_line = true;
             }
-            for (tag, _) in get_valid_tags(element)
+
+            for (i, tag) in tags
+                .iter()
+                .enumerate()
                 .rev()
                 .skip(if self_close_last { 1 } else { 0 })
             {
-                let tag = this.slice(tag);
+                // Each tag but the outermost is the last content of the tag outside it.
+                let (following, parent) = match i {
+                    0 => (following.clone(), parent.as_deref()),
+                    _ => (Following::End, Some(&*tags[i - 1])),
+                };
                 let tag_info = this.get_info(tag);
-                if !self_close_last && !tag_info.is_void {
+                let omit = omit_end_tags
+                    && can_omit_end_tag(to_lowercase(tag, &mut this.string_buf), following, parent);
+
+                if !self_close_last && !tag_info.is_void && !omit {
+                    if mem::take(&mut needs_line) {
+                        this.line()?;
+                    }
                     this.write_close_tag(tag)?;
                 }
             }
             Ok(())
-        })
+        })?;
+
+        if let Some(start) = start {
+            let end = self.out.mark_end();
+            let source_map = self.source_map.as_mut().unwrap();
+            source_map.push((start, end, element.range));
+        }
+        Ok(())
     }
 
-    fn process_node(&mut self, node: &'cx Node<'cfg>) -> OutputResult {
+    /// Writes `node`. If it's an element, `tags` are its output tags, from [`Self::output_tags`].
+    fn process_node(
+        &mut self,
+        node: &'cx Node<'cfg>,
+        following: Following<'cx>,
+        tags: Vec<Cow<'cx, str>>,
+    ) -> OutputResult {
         let out = match &node.node_type {
-            NodeType::Element { element } => self.process_element(element)?,
+            NodeType::Element { element } => self.process_element(element, following, tags)?,
             NodeType::TextLike { text_like } => match text_like {
                 TextLike::Text { text } if text.slice.is_empty() => {}
                 TextLike::Text { text } => {
-                    let is_raw = text.raw || !self.is_xml() && (!text.escape_out || self.is_raw());
-                    let slice = self.slice(&text.slice);
-
-                    let mut write = |value| match (text.unescape_in, is_raw) {
-                        (true, true) => self.write_unescape(value),
-                        (true, false) => self.write_escape_unescape(value, false),
-                        (false, true) => self.out.write_str(value).map_err(Into::into),
-                        (false, false) => self.write_escape(value, false),
+                    let cdata = !text.raw && self.in_cdata;
+                    let is_raw =
+                        text.raw || cdata || !self.is_xml() && (!text.escape_out || self.is_raw());
+                    let mut slice = self.slice(&text.slice);
+                    let collapsed;
+                    if self.config.collapse_text_whitespace.unwrap_or(false)
+                        && !text.multiline
+                        && !is_raw
+                        && self.can_wrap()
+                    {
+                        collapsed = collapse_whitespace(slice);
+                        slice = &collapsed;
+                    }
+                    let wrap_words = self.config.max_width.is_some() && !is_raw && self.can_wrap();
+                    let indent_level = self.indent_level + 1;
+
+                    let write_part = |this: &mut Self, value: &str| match (text.unescape_in, is_raw)
+                    {
+                        (true, true) => this.write_unescape(value),
+                        (true, false) => this.write_escape_unescape(value, false),
+                        (false, true) => this.out.write_str(value).map_err(Into::into),
+                        (false, false) => this.write_escape(value, false),
                     };
 
+                    let write = |this: &mut Self, value: &str| {
+                        if !cdata {
+                            return write_part(this, value);
+                        }
+                        // `]]>` would end the CDATA section, so split it across two sections.
+                        for (i, part) in value.split("]]>").enumerate() {
+                            if i > 0 {
+                                this.out.write_str("]]]]><![CDATA[>")?;
+                            }
+                            write_part(this, part)?;
+                        }
+                        Ok(())
+                    };
+
+                    if cdata {
+                        self.out.write_str("/* <![CDATA[ */")?;
+                    }
+
                     let mut last_line = slice;
 
-                    if text.multiline {
+                    if text.multiline && self.config.trim_multiline == Some(false) {
+                        write(self, slice)?;
+                    } else if text.multiline {
                         for line in trim_multiline(slice) {
-                            write(line)?;
+                            write(self, line)?;
                             last_line = line
                         }
+                    } else if wrap_words {
+                        for (i, word) in slice.split(' ').enumerate() {
+                            if i > 0 {
+                                self.out.soft_break(" ", indent_level)?;
+                            }
+                            write(self, word)?;
+                        }
                     } else {
-                        write(slice)?;
+                        write(self, slice)?;
+                    }
+
+                    if cdata {
+                        self.out.write_str("/* ]]> */")?;
                     }
 
                     self.follows_space = last_line.ends_with([' ', '\t']);
                 }
-                TextLike::Comment {
-                    comment: Comment::Tag { slice: value },
-                } => {
+                TextLike::Comment { comment } => {
                     self.out.write_str("<!--")?;
-                    self.write_comment_body(self.slice(value))?;
+                    match comment {
+                        Comment::Tag { slice } => self.write_comment_body(self.slice(slice))?,
+                        // Raw comments are written as-is, even if that makes them invalid.
+                        Comment::Raw { slice } => self.out.write_str(self.slice(slice))?,
+                    }
                     self.out.write_str("-->")?;
                     self.follows_space = false;
                 }
@@ -484,9 +1032,137 @@ where
             return Ok(());
         };
 
-        content.nodes[first..=last]
-            .iter()
-            .try_for_each(|node| self.process_node(node))
+        let nodes = &content.nodes[first..=last];
+        let special_space = self
+            .config
+            .adjacent_inline_special_space
+            .unwrap_or_default();
+        let preserve_inline_space = self.config.preserve_inline_space.unwrap_or(true);
+        let strip_comments = !self.config.emit_comments.unwrap_or(true);
+        let is_skipped = |n: &Node| strip_comments && n.is_comment();
+        // The output tags of each element, computed when it or the node before it is written.
+        let mut tags = Vec::from_iter(nodes.iter().map(|_| None::<Vec<Cow<'cx, str>>>));
+
+        for (i, node) in nodes.iter().enumerate() {
+            if is_skipped(node) {
+                continue;
+            }
+            if strip_comments && node.is_space() {
+                // Keep only the last of the spaces around stripped comments, and none at the ends.
+                let prev = nodes[..i].iter().rfind(|n| !is_skipped(n));
+                let next = nodes[i + 1..].iter().find(|n| !is_skipped(n));
+                if prev.is_none() || next.is_none_or(Node::is_space) {
+                    continue;
+                }
+            }
+
+            if !preserve_inline_space
+                && is_inline_space(node)
+                && i > 0
+                && nodes[i - 1].as_element().is_some()
+                && nodes.get(i + 1).and_then(Node::as_element).is_some()
+            {
+                continue;
+            }
+
+            let follows_special = i > 0 && is_special(&nodes[i - 1]);
+            match special_space {
+                SpacesPolicy::Never
+                    if follows_special
+                        && node.is_space()
+                        && nodes.get(i + 1).is_some_and(is_special) =>
+                {
+                    continue;
+                }
+                SpacesPolicy::Always
+                    if follows_special && is_special(node) && !self.follows_space =>
+                {
+                    self.soft_space(" ")?;
+                    self.follows_space = true;
+                }
+                _ => {}
+            }
+
+            let next = nodes[i + 1..]
+                .iter()
+                .position(|n| !n.is_space() && !is_skipped(n))
+                .map(|j| i + 1 + j);
+            let following = match next {
+                None => Following::End,
+                Some(j) => match nodes[j].as_element() {
+                    Some(e) => tags[j]
+                        .get_or_insert_with(|| self.output_tags(e))
+                        .first()
+                        .map_or(Following::Other, |tag| Following::Element(tag.clone())),
+                    None => Following::Other,
+                },
+            };
+            let node_tags = match node.as_element() {
+                Some(e) => tags[i].take().unwrap_or_else(|| self.output_tags(e)),
+                None => Vec::new(),
+            };
+            self.process_node(node, following, node_tags)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes a comment noting that the output may be incomplete because of errors.
+    fn write_error_annotation(&mut self) -> OutputResult {
+        if !self.follows_space {
+            if self.config.indent.is_some() {
+                self._line()?;
+            } else {
+                self.out.write_char(' ')?;
+            }
+        }
+
+        let plural = if self.error_count == 1 { "" } else { "s" };
+        let body = format!(
+            " mintyml: {} error{plural}, output may be incomplete ",
+            self.error_count,
+        );
+
+        self.out.write_str("<!--")?;
+        self.write_comment_body(&body)?;
+        self.out.write_str("-->")?;
+        self.follows_space = false;
+        Ok(())
+    }
+
+    fn write_document(&mut self, document: &'cx Document<'cfg>) -> OutputResult {
+        if self.config.complete_page.unwrap_or(false) {
+            let doctype = match self.config.doctype.as_deref() {
+                Some(doctype) => doctype,
+                None if self.is_xml() => "",
+                None => "<!DOCTYPE html>",
+            };
+
+            if !doctype.is_empty() {
+                self.out.write_str(doctype)?;
+                self.out.write_str(self.line_ending())?;
+            }
+        }
+
+        self.process_content(&document.content)?;
+
+        if self.config.annotate_errors_in_output.unwrap_or(false) && self.error_count > 0 {
+            self.write_error_annotation()?;
+        }
+
+        self.line()?;
+        self.out.finish(self.config.final_newline)?;
+
+        Ok(())
+    }
+
+    fn write_element(&mut self, element: &'cx Element<'cfg>) -> OutputResult {
+        let tags = self.output_tags(element);
+        self.process_element(element, Following::End, tags)?;
+        self.line()?;
+        self.out.finish(self.config.final_newline)?;
+
+        Ok(())
     }
 }
 
@@ -494,6 +1170,8 @@ where
 #[derive(Debug)]
 pub enum OutputError {
     WriteError(fmt::Error),
+    /// The output would have exceeded [`OutputConfig::max_output_bytes`].
+    TooLarge { limit: usize },
 }
 
 impl From<fmt::Error> for OutputError {
@@ -509,26 +1187,89 @@ pub fn output_html_to<'cx, 'cfg>(
     document: &'cx Document<'cfg>,
     out: &'cx mut impl Write,
     config: &'cx OutputConfig<'cfg>,
+    error_count: usize,
+    source_map: Option<&mut SourceMap>,
+) -> OutputResult {
+    output_with(src, out, config, error_count, source_map, |cx| {
+        cx.write_document(document)
+    })
+}
+
+/// Renders `element` and its content on its own, as if it were the only node in the document.
+///
+/// Inference and other transforms should already have been applied to `element`.
+pub(crate) fn render_element<'cfg>(
+    src: &'cfg str,
+    element: &Element<'cfg>,
+    config: &OutputConfig<'cfg>,
+) -> OutputResult<String> {
+    let mut out = String::new();
+    output_with(src, &mut out, config, 0, None, |cx| {
+        cx.write_element(element)
+    })?;
+    Ok(out)
+}
+
+fn output_with<'cx, 'cfg, W: Write>(
+    src: &'cfg str,
+    out: &'cx mut W,
+    config: &'cx OutputConfig<'cfg>,
+    error_count: usize,
+    source_map: Option<&mut SourceMap>,
+    write: impl FnOnce(&mut OutputContext<'cx, 'cfg, LimitWriter<&'cx mut W>>) -> OutputResult,
 ) -> OutputResult {
     match (OutputContext::<'cx, 'cfg> {
         src,
         string_buf: default(),
-        out,
+        out: WrapWriter::new(
+            LimitWriter::new(out, config.max_output_bytes),
+            config.max_width,
+            config.indent.as_deref(),
+            config.line_ending.unwrap_or_default().as_str(),
+        ),
         config,
         indent_level: 0,
         element: None,
+        parent_tag: None,
         follows_space: true,
         is_raw: false,
+        in_cdata: false,
+        is_preformatted: false,
+        error_count,
+        source_map: source_map.is_some().then(Vec::new),
+        extra_escapes: config
+            .extra_escapes
+            .as_ref()
+            .filter(|e| !e.is_empty())
+            .map(ExtraEscapes::new),
     }) {
         mut cx => {
-            if cx.config.complete_page.unwrap_or(false) {
-                cx.out.write_str("<!DOCTYPE html>\n")?;
+            let result = write(&mut cx);
+
+            if let Some(out) = source_map {
+                let len = out.len();
+                // Elements added by transforms have no range in `src`, so leave them out.
+                let in_src = |r: &LocationRange| {
+                    r.start.position <= r.end.position && r.end.position <= src.len()
+                };
+                for &(start, end, range) in cx.source_map.iter().flatten() {
+                    if !in_src(&range) {
+                        continue;
+                    }
+                    if let (Some(start), Some(end)) =
+                        (cx.out.mark_position(start), cx.out.mark_position(end))
+                    {
+                        out.push((start..end, range));
+                    }
+                }
+                // Elements are recorded as they're closed, so put outer elements first.
+                out[len..].sort_by_key(|(r, _)| (r.start, core::cmp::Reverse(r.end)));
             }
 
-            cx.process_content(&document.content)?;
-            cx.line()?;
-
-            Ok(())
+            match cx.out.get_ref().exceeded_limit() {
+                Some(limit) => Err(OutputError::TooLarge { limit }),
+                None => result,
+            }
         }
     }
 }
@@ -611,12 +1352,41 @@ section {
     };
 
     let mut errors = crate::error::Errors::new(&config);
-    let document = Document::parse(src, &mut errors).unwrap();
+    let document = Document::parse(src, &config, &mut errors).unwrap();
     errors.to_convert_error(src).unwrap();
 
-    output_html_to(src, &document, &mut out, &config).unwrap();
+    output_html_to(src, &document, &mut out, &config, 0, None).unwrap();
     #[cfg(feature = "std")]
     {
         ::std::println!("{out}");
     }
 }
+
+#[test]
+fn render_nested_element() {
+    let src = "p> Intro\n\nsection {\n    h2> Title\n\n    ul {\n        one\n\n        <#two#>\n    }\n}\n";
+    let config = OutputConfig::new().indent("  ");
+
+    let mut errors = crate::error::Errors::new(&config);
+    let document = crate::parse_and_transform(src, &config, &mut errors).unwrap();
+    let section = document
+        .content
+        .nodes
+        .iter()
+        .filter_map(|n| n.as_element())
+        .nth(1)
+        .unwrap();
+
+    assert_eq!(
+        render_element(src, section, &config).unwrap(),
+        concat!(
+            "<section>\n",
+            "  <h2>Title</h2>\n",
+            "  <ul>\n",
+            "    <li>one</li>\n",
+            "    <li><strong>two</strong></li>\n",
+            "  </ul>\n",
+            "</section>\n",
+        ),
+    );
+}
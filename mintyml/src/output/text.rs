@@ -0,0 +1,158 @@
+use core::fmt::Write;
+
+use crate::{
+    document::{Content, Document, Element, NodeType, Space, TextLike},
+    utils::to_lowercase,
+};
+
+use alloc::string::String;
+
+use super::{utils::trim_multiline, write_unescaped, OutputResult};
+
+/// Elements whose text content flows with the surrounding text.
+fn is_inline(tag: &str) -> bool {
+    matches!(
+        tag,
+        "a" | "abbr"
+            | "b"
+            | "bdi"
+            | "bdo"
+            | "cite"
+            | "code"
+            | "data"
+            | "del"
+            | "dfn"
+            | "em"
+            | "i"
+            | "img"
+            | "ins"
+            | "kbd"
+            | "label"
+            | "mark"
+            | "q"
+            | "s"
+            | "samp"
+            | "small"
+            | "span"
+            | "strong"
+            | "sub"
+            | "sup"
+            | "time"
+            | "u"
+            | "var"
+    )
+}
+
+/// Elements whose content isn't meant to be read as text.
+fn is_hidden(tag: &str) -> bool {
+    matches!(tag, "head" | "script" | "style" | "template")
+}
+
+/// Whitespace that has not yet been written, because it may be trimmed or combined with
+/// adjacent whitespace.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Break {
+    None,
+    Space,
+    Line,
+}
+
+struct TextContext<'cx, 'cfg, Out> {
+    src: &'cfg str,
+    out: &'cx mut Out,
+    string_buf: String,
+    pending: Break,
+    /// Whether any text has been written yet.
+    started: bool,
+}
+
+impl<'cx, 'cfg, Out: Write> TextContext<'cx, 'cfg, Out> {
+    fn request(&mut self, brk: Break) {
+        self.pending = self.pending.max(brk);
+    }
+
+    fn flush(&mut self) -> OutputResult {
+        if self.started {
+            match self.pending {
+                Break::None => {}
+                Break::Space => self.out.write_char(' ')?,
+                Break::Line => self.out.write_char('\n')?,
+            }
+        }
+        self.pending = Break::None;
+        self.started = true;
+        Ok(())
+    }
+
+    fn process_element(&mut self, element: &'cx Element<'cfg>) -> OutputResult {
+        let mut inline = true;
+
+        for tag in element.selectors.iter().filter_map(|s| s.tag.name()) {
+            let tag = to_lowercase(tag.as_str(self.src), &mut self.string_buf);
+            if is_hidden(tag) {
+                return Ok(());
+            }
+            inline &= is_inline(tag);
+        }
+
+        if !inline {
+            self.request(Break::Line);
+        }
+        self.process_content(&element.content)?;
+        if !inline {
+            self.request(Break::Line);
+        }
+        Ok(())
+    }
+
+    fn process_content(&mut self, content: &'cx Content<'cfg>) -> OutputResult {
+        for node in &content.nodes {
+            match &node.node_type {
+                NodeType::Element { element } => self.process_element(element)?,
+                NodeType::TextLike { text_like } => match text_like {
+                    TextLike::Text { text } if text.slice.is_empty() => {}
+                    TextLike::Text { text } => {
+                        self.flush()?;
+                        let slice = text.slice.as_str(self.src);
+                        let write = |out: &mut Out, value| match text.unescape_in {
+                            true => write_unescaped(value, &mut *out),
+                            false => out.write_str(value),
+                        };
+
+                        if text.multiline {
+                            for line in trim_multiline(slice) {
+                                write(self.out, line)?;
+                            }
+                        } else {
+                            write(self.out, slice)?;
+                        }
+                    }
+                    TextLike::Space {
+                        space: Space::ParagraphEnd { .. },
+                    } => self.request(Break::Line),
+                    TextLike::Space { .. } => self.request(Break::Space),
+                    TextLike::Comment { .. } => {}
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes the text content of `document` to `out`, without any markup.
+///
+/// Block elements are separated by line breaks, and inline content by spaces.
+pub fn output_text_to<'cx, 'cfg>(
+    src: &'cfg str,
+    document: &'cx Document<'cfg>,
+    out: &'cx mut impl Write,
+) -> OutputResult {
+    TextContext {
+        src,
+        out,
+        string_buf: String::new(),
+        pending: Break::None,
+        started: false,
+    }
+    .process_content(&document.content)
+}
@@ -1,3 +1,5 @@
+use alloc::{borrow::Cow, string::String};
+
 pub fn trim_multiline<'src>(src: &'src str) -> impl Iterator<Item = &'src str> {
     src.split_once('\n')
         .and_then(|(_, src)| src.rsplit_once('\n'))
@@ -16,3 +18,27 @@ pub fn trim_multiline<'src>(src: &'src str) -> impl Iterator<Item = &'src str> {
         .into_iter()
         .flatten()
 }
+
+/// Replaces each run of spaces and tabs in `src` with a single space.
+pub fn collapse_whitespace(src: &str) -> Cow<'_, str> {
+    let is_blank = |c: char| c == ' ' || c == '\t';
+
+    if !src.contains('\t') && !src.contains("  ") {
+        return src.into();
+    }
+
+    let mut out = String::with_capacity(src.len());
+    let mut in_blank = false;
+    for c in src.chars() {
+        if is_blank(c) {
+            if !in_blank {
+                out.push(' ');
+            }
+            in_blank = true;
+        } else {
+            out.push(c);
+            in_blank = false;
+        }
+    }
+    out.into()
+}
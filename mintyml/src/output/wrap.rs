@@ -0,0 +1,220 @@
+use core::{
+    fmt::{self, Write},
+    mem,
+};
+
+use alloc::{string::String, vec::Vec};
+
+/// Counts the number of columns `s` occupies.
+fn width(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// A writer that tracks the current column and, if a maximum width is provided,
+/// replaces soft breaks with line breaks when a line would exceed that width.
+pub struct WrapWriter<'cx, W> {
+    inner: W,
+    max_width: Option<usize>,
+    indent: &'cx str,
+    line_ending: &'static str,
+    /// The column of the next character to be written.
+    current_column: usize,
+    pending: Option<PendingBreak>,
+    /// Output written after the pending break.
+    buf: String,
+    /// The number of line breaks written since the last other output, held back so trailing
+    /// line breaks can be dropped by [`finish`](Self::finish).
+    held_lines: usize,
+    /// The number of bytes written to `inner`.
+    written: usize,
+    /// The output position of each mark, or `None` if it's still in `buf`.
+    marks: Vec<Option<usize>>,
+    /// Marks in `buf`, with their offsets from the start of `buf`.
+    pending_marks: Vec<(usize, usize)>,
+}
+
+/// A soft break that has not yet been resolved to either a space or a line break.
+struct PendingBreak {
+    space: String,
+    indent_level: u32,
+    /// The column at which the break occurs.
+    column: usize,
+}
+
+impl<'cx, W: Write> WrapWriter<'cx, W> {
+    pub fn new(
+        inner: W,
+        max_width: Option<usize>,
+        indent: Option<&'cx str>,
+        line_ending: &'static str,
+    ) -> Self {
+        Self {
+            inner,
+            // Wrapping is only meaningful when line breaks are allowed.
+            max_width: max_width.filter(|_| indent.is_some()),
+            indent: indent.unwrap_or_default(),
+            line_ending,
+            current_column: 0,
+            pending: None,
+            buf: String::new(),
+            held_lines: 0,
+            written: 0,
+            marks: Vec::new(),
+            pending_marks: Vec::new(),
+        }
+    }
+
+    fn emit(&mut self, s: &str) -> fmt::Result {
+        self.written += s.len();
+        self.inner.write_str(s)
+    }
+
+    fn add_mark(&mut self, held_lines: usize) -> usize {
+        let id = self.marks.len();
+        let offset = held_lines * self.line_ending.len();
+        if self.pending.is_some() {
+            self.marks.push(None);
+            self.pending_marks.push((id, self.buf.len() + offset));
+        } else {
+            self.marks.push(Some(self.written + offset));
+        }
+        id
+    }
+
+    /// Marks the position where the next output other than a line break will be written.
+    /// The position can be read with [`mark_position`](Self::mark_position) once it's known.
+    pub fn mark_next(&mut self) -> usize {
+        self.add_mark(self.held_lines)
+    }
+
+    /// Marks the position just after the output written so far.
+    pub fn mark_end(&mut self) -> usize {
+        self.add_mark(0)
+    }
+
+    /// The output position marked by `id`, if it has been written.
+    pub fn mark_position(&self, id: usize) -> Option<usize> {
+        self.marks.get(id).copied().flatten()
+    }
+
+    /// Writes any line breaks held back by `write_str`.
+    fn release_lines(&mut self) -> fmt::Result {
+        for _ in 0..mem::take(&mut self.held_lines) {
+            self.write_inner(self.line_ending)?;
+        }
+        Ok(())
+    }
+
+    fn advance(&mut self, s: &str) {
+        match s.rsplit_once('\n') {
+            Some((_, last)) => self.current_column = width(last),
+            None => self.current_column += width(s),
+        }
+    }
+
+    /// Writes `space`, or a line break followed by `indent_level` levels of indentation if the line
+    /// would otherwise exceed the maximum width.
+    pub fn soft_break(&mut self, space: &str, indent_level: u32) -> fmt::Result {
+        self.release_lines()?;
+
+        if self.max_width.is_none() {
+            self.emit(space)?;
+            self.advance(space);
+            return Ok(());
+        }
+
+        self.resolve(false)?;
+
+        self.pending = Some(PendingBreak {
+            space: space.into(),
+            indent_level,
+            column: self.current_column,
+        });
+        self.current_column += width(space);
+        Ok(())
+    }
+
+    /// Writes the pending break, if any, as a line break if `wrap` is true or as a space otherwise.
+    fn resolve(&mut self, wrap: bool) -> fmt::Result {
+        let Some(pending) = self.pending.take() else {
+            return Ok(());
+        };
+
+        if wrap {
+            self.emit(self.line_ending)?;
+            for _ in 0..pending.indent_level {
+                self.emit(self.indent)?;
+            }
+            self.current_column = self.current_column - pending.column - width(&pending.space)
+                + width(self.indent) * pending.indent_level as usize;
+        } else {
+            self.emit(&pending.space)?;
+        }
+
+        for (id, offset) in self.pending_marks.drain(..) {
+            self.marks[id] = Some(self.written + offset);
+        }
+
+        let buf = mem::take(&mut self.buf);
+        self.emit(&buf)?;
+        self.buf = buf;
+        self.buf.clear();
+        Ok(())
+    }
+
+    /// The writer this wraps.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Writes any pending output.
+    ///
+    /// If `final_newline` is `Some`, trailing line breaks are replaced with exactly one line break
+    /// if it's `true`, or removed if it's `false`.
+    pub fn finish(&mut self, final_newline: Option<bool>) -> fmt::Result {
+        match final_newline {
+            None => self.release_lines()?,
+            Some(true) => self.held_lines = 1,
+            Some(false) => self.held_lines = 0,
+        }
+        self.resolve(false)?;
+        self.release_lines()
+    }
+
+    fn write_inner(&mut self, s: &str) -> fmt::Result {
+        if self.pending.is_none() {
+            self.emit(s)?;
+            self.advance(s);
+            return Ok(());
+        }
+
+        let (line, rest) = s.split_at(s.find('\n').unwrap_or(s.len()));
+
+        self.buf.push_str(line);
+        self.advance(line);
+
+        if self.max_width.is_some_and(|w| self.current_column > w) {
+            self.resolve(true)?;
+        } else if !rest.is_empty() {
+            self.resolve(false)?;
+        }
+
+        if !rest.is_empty() {
+            self.emit(rest)?;
+            self.advance(rest);
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for WrapWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s == self.line_ending {
+            self.held_lines += 1;
+            return Ok(());
+        }
+
+        self.release_lines()?;
+        self.write_inner(s)
+    }
+}
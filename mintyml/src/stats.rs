@@ -0,0 +1,55 @@
+use alloc::collections::BTreeMap;
+
+use crate::document::{Content, Document, ElementType, MultilineKind, SpecialKind};
+
+/// Counts of the elements in a converted document.
+/// See [`convert_with_stats`](crate::convert_with_stats).
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ConvertStats {
+    /// The number of uses of each kind of special element, like `</ ... />` or ```` ``` ````.
+    pub special_tags: BTreeMap<SpecialKind, usize>,
+    /// The total number of elements in the output.
+    pub elements: usize,
+    /// The number of elements whose tag was assigned by inference.
+    pub inferred_tags: usize,
+    /// The number of elements whose tag was not assigned by inference,
+    /// including tags given by special elements.
+    pub explicit_tags: usize,
+}
+
+impl ConvertStats {
+    fn add_special(&mut self, kind: SpecialKind) {
+        *self.special_tags.entry(kind).or_default() += 1;
+    }
+
+    fn add_content(&mut self, content: &Content) {
+        for element in content.nodes.iter().filter_map(|n| n.as_element()) {
+            match element.element_type {
+                ElementType::Special { kind } => self.add_special(kind),
+                ElementType::Multiline {
+                    kind: MultilineKind::Code { .. },
+                } => self.add_special(SpecialKind::CodeBlockContainer),
+                _ => {}
+            }
+
+            for selector in element.selectors.iter().filter(|s| s.tag.name().is_some()) {
+                self.elements += 1;
+                if selector.inferred {
+                    self.inferred_tags += 1;
+                } else {
+                    self.explicit_tags += 1;
+                }
+            }
+
+            self.add_content(&element.content);
+        }
+    }
+}
+
+/// Collects the stats of a fully transformed `document`.
+pub fn collect_stats(document: &Document) -> ConvertStats {
+    let mut stats = ConvertStats::default();
+    stats.add_content(&document.content);
+    stats
+}
@@ -0,0 +1,208 @@
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
+use crate::{error::ConvertError, LangScope, OutputConfig, Src};
+
+/// Holds the output of each top-level block from the last call to
+/// [`convert_with_cache`](crate::convert_with_cache) so blocks that haven't changed since can
+/// be reused rather than converted again.
+///
+/// The cached output depends on the config it was converted with, so a cache should only be
+/// used with one config. Call [`clear`](Self::clear) after changing it.
+#[derive(Debug, Default, Clone)]
+pub struct ConvertCache {
+    blocks: BTreeMap<u64, CachedBlock>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CachedBlock {
+    pub src: String,
+    pub output: String,
+}
+
+impl ConvertCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Discards all cached output.
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+    }
+
+    /// Removes and returns the cached output of `src`, if any.
+    pub(crate) fn take(&mut self, hash: u64, src: &str) -> Option<CachedBlock> {
+        match self.blocks.get(&hash) {
+            Some(block) if block.src == src => self.blocks.remove(&hash),
+            _ => None,
+        }
+    }
+
+    /// Replaces the contents of the cache with `blocks`.
+    pub(crate) fn replace(&mut self, blocks: BTreeMap<u64, CachedBlock>) {
+        self.blocks = blocks;
+    }
+}
+
+/// Hashes `src` with 64-bit FNV-1a.
+pub fn hash(src: &str) -> u64 {
+    src.bytes().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Returns whether a document converted with `config` can be split into independently-converted
+/// top-level blocks without changing the output.
+pub fn can_stream(config: &OutputConfig) -> bool {
+    !config.complete_page.unwrap_or(false)
+        && config.root_element.is_none()
+        && !config.auto_html_wrap.unwrap_or(false)
+        && config.metadata.is_none()
+        && config.max_width.is_none()
+        && config.max_output_bytes.is_none()
+        && config.on_diagnostic.is_none()
+        && config.lang_scope != Some(LangScope::RootOnly)
+        && config.element_visitor.is_none()
+        && config.final_newline.is_none()
+        && !config.check_duplicate_ids.unwrap_or(false)
+        && config.namespaces.is_none()
+        && !config.omit_optional_end_tags.unwrap_or(false)
+        && config.emit_comments != Some(false)
+}
+
+/// Finds the first position after `start` that follows a blank line outside of any braces.
+///
+/// This only approximates the grammar: braces inside verbatim blocks, code, and comments are
+/// still counted. The caller is expected to convert the rest of the document at once when a block
+/// fails to parse on its own.
+pub fn next_boundary(src: &str, start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut escaped = false;
+    let mut line_blank = true;
+    let mut seen_content = false;
+
+    for (i, ch) in src[start..].char_indices() {
+        match ch {
+            '\n' => {
+                if line_blank && depth == 0 && seen_content {
+                    return Some(start + i + 1);
+                }
+                line_blank = true;
+                escaped = false;
+                continue;
+            }
+            _ if ch.is_whitespace() => {}
+            _ => {
+                line_blank = false;
+                seen_content = true;
+            }
+        }
+
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Combines the errors found in two blocks into one, keeping at most `max_errors` errors.
+///
+/// As with converting the whole document, syntax errors take priority over semantic errors.
+pub fn merge_errors<'src>(
+    first: ConvertError<'src>,
+    next: ConvertError<'src>,
+    max_errors: Option<usize>,
+) -> ConvertError<'src> {
+    fn extend<T>(errors: &mut Vec<T>, more: Vec<T>, truncated: &mut bool, max: Option<usize>) {
+        errors.extend(more);
+        if let Some(max) = max.filter(|&max| errors.len() > max) {
+            errors.truncate(max);
+            *truncated = true;
+        }
+    }
+
+    match (first, next) {
+        (
+            ConvertError::Syntax {
+                mut syntax_errors,
+                src,
+                mut truncated,
+            },
+            ConvertError::Syntax {
+                syntax_errors: more,
+                truncated: more_truncated,
+                ..
+            },
+        ) => {
+            truncated |= more_truncated;
+            extend(&mut syntax_errors, more, &mut truncated, max_errors);
+            ConvertError::Syntax {
+                syntax_errors,
+                src,
+                truncated,
+            }
+        }
+        (
+            ConvertError::Semantic {
+                mut semantic_errors,
+                src,
+                mut truncated,
+            },
+            ConvertError::Semantic {
+                semantic_errors: more,
+                truncated: more_truncated,
+                ..
+            },
+        ) => {
+            truncated |= more_truncated;
+            extend(&mut semantic_errors, more, &mut truncated, max_errors);
+            ConvertError::Semantic {
+                semantic_errors,
+                src,
+                truncated,
+            }
+        }
+        (ConvertError::Semantic { .. }, next @ ConvertError::Syntax { .. }) => next,
+        (first, _) => first,
+    }
+}
+
+/// Rebases an error found in the block of `src` starting at `offset` onto the full source.
+pub fn shift_error<'src>(
+    err: ConvertError<'src>,
+    src: &'src str,
+    offset: usize,
+) -> ConvertError<'src> {
+    match err {
+        ConvertError::Syntax {
+            mut syntax_errors,
+            truncated,
+            ..
+        } => {
+            syntax_errors.iter_mut().for_each(|e| e.range += offset);
+            ConvertError::Syntax {
+                syntax_errors,
+                src: Src::Borrowed(src),
+                truncated,
+            }
+        }
+        ConvertError::Semantic {
+            mut semantic_errors,
+            truncated,
+            ..
+        } => {
+            semantic_errors.iter_mut().for_each(|e| e.range += offset);
+            ConvertError::Semantic {
+                semantic_errors,
+                src: Src::Borrowed(src),
+                truncated,
+            }
+        }
+        err => err,
+    }
+}
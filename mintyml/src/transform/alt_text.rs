@@ -0,0 +1,34 @@
+use crate::{
+    document::Content,
+    error::{Errors, Warning, WarningKind},
+};
+
+/// Warns about each `img` element in `content` that has no `alt` attribute.
+/// An empty `alt` attribute is allowed, as it marks the image as decorative.
+pub fn check_alt_text(content: &Content, src: &str, errors: &mut Errors) {
+    for node in &content.nodes {
+        let Some(element) = node.as_element() else {
+            continue;
+        };
+
+        for selector in &element.selectors {
+            let is_img = selector
+                .tag
+                .name()
+                .is_some_and(|tag| tag.as_str(src).eq_ignore_ascii_case("img"));
+
+            if is_img
+                && !selector
+                    .attributes()
+                    .any(|(name, _)| name.as_str(src).eq_ignore_ascii_case("alt"))
+            {
+                errors.warn([Warning {
+                    range: selector.range,
+                    kind: WarningKind::MissingAltText {},
+                }]);
+            }
+        }
+
+        check_alt_text(&element.content, src, errors);
+    }
+}
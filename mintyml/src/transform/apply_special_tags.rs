@@ -1,8 +1,12 @@
-use alloc::borrow::Cow;
+use alloc::{borrow::Cow, format, vec::Vec};
+
+use gramma::parse::LocationRange;
 
 use crate::{
+    config::SpecialTag,
     document::{
-        Content, Document, Element, ElementType, MultilineKind, Node, NodeType, SpecialKind,
+        Content, Document, Element, ElementType, MultilineKind, Node, NodeType, SelectorItem,
+        SpecialKind, Text, TextLike,
     },
     error::{Errors, InternalResult},
     OutputConfig,
@@ -10,64 +14,126 @@ use crate::{
 
 pub fn apply_special_tags<'cfg>(
     mut document: Document<'cfg>,
+    src: &'cfg str,
     config: &OutputConfig<'cfg>,
     errors: &mut Errors,
 ) -> InternalResult<Document<'cfg>> {
-    document.content = TransformContext { config, errors }.transform_content(document.content)?;
+    document.content = TransformContext {
+        src,
+        config,
+        errors,
+    }
+    .transform_content(document.content)?;
     Ok(document)
 }
 
 struct TransformContext<'cx, 'cfg> {
+    src: &'cfg str,
     config: &'cx OutputConfig<'cfg>,
     errors: &'cx mut Errors,
 }
 
+/// A text node copying the source in `range`, like the delimiters of a disabled special element.
+fn literal<'cfg>(range: LocationRange) -> Node<'cfg> {
+    Node {
+        range,
+        node_type: NodeType::TextLike {
+            text_like: TextLike::Text {
+                text: Text {
+                    slice: range.into(),
+                    escape_out: true,
+                    ..Text::default()
+                },
+            },
+        },
+    }
+}
+
 impl<'cfg> TransformContext<'_, 'cfg> {
-    fn tag_name(&self, kind: &SpecialKind) -> InternalResult<Cow<'cfg, str>> {
+    /// The tag for `kind`, or `None` if its syntax is disabled.
+    fn tag_name(&self, kind: &SpecialKind) -> InternalResult<Option<Cow<'cfg, str>>> {
         use SpecialKind::*;
-        let cfg = &self.config.special_tags;
-        Ok(match kind {
-            Emphasis => cfg.emphasis.clone().unwrap_or("em".into()),
-            Strong => cfg.strong.clone().unwrap_or("strong".into()),
-            Underline => cfg.underline.clone().unwrap_or("u".into()),
-            Strike => cfg.strike.clone().unwrap_or("s".into()),
-            Quote => cfg.quote.clone().unwrap_or("q".into()),
-            Code => cfg.code.clone().unwrap_or("code".into()),
-            CodeBlockContainer => cfg.emphasis.clone().unwrap_or("pre".into()),
+        let default = match kind {
+            Emphasis => "em",
+            Strong => "strong",
+            Underline => "u",
+            Strike => "s",
+            Quote => "q",
+            Code => "code",
+            CodeBlockContainer => "pre",
+        };
+        Ok(match self.config.special_tags.get(*kind) {
+            None => Some(default.into()),
+            Some(SpecialTag::Tag(tag)) => Some(tag.clone()),
+            Some(SpecialTag::Disabled) => None,
         })
     }
 
     fn transform_content(&mut self, mut content: Content<'cfg>) -> InternalResult<Content<'cfg>> {
-        content.nodes = content
-            .nodes
-            .into_iter()
-            .map(|n| self.transform_node(n))
-            .collect::<InternalResult<_>>()?;
+        let mut nodes = Vec::with_capacity(content.nodes.len());
+        for node in content.nodes {
+            self.transform_node(node, &mut nodes)?;
+        }
+        content.nodes = nodes;
         Ok(content)
     }
 
-    fn transform_node(&mut self, mut node: Node<'cfg>) -> InternalResult<Node<'cfg>> {
-        node.node_type = match node.node_type {
-            NodeType::Element { element: value } => NodeType::Element {
-                element: self.transform_element(value)?,
+    fn transform_node(&mut self, node: Node<'cfg>, out: &mut Vec<Node<'cfg>>) -> InternalResult {
+        match node.node_type {
+            NodeType::Element { element } => match element.element_type {
+                ElementType::Special { ref kind } if self.tag_name(kind)?.is_none() => {
+                    let (range, inner) = (element.range, element.content.range);
+                    out.push(literal(LocationRange {
+                        start: range.start,
+                        end: inner.start,
+                    }));
+                    out.extend(self.transform_content(element.content)?.nodes);
+                    out.push(literal(LocationRange {
+                        start: inner.end,
+                        end: range.end,
+                    }));
+                }
+                _ => out.push(Node {
+                    node_type: NodeType::Element {
+                        element: self.transform_element(element)?,
+                    },
+                    ..node
+                }),
             },
-            node_type @ NodeType::TextLike { .. } => node_type,
-        };
-        Ok(node)
+            NodeType::TextLike { .. } => out.push(node),
+        }
+        Ok(())
     }
 
     fn transform_element(&mut self, mut element: Element<'cfg>) -> InternalResult<Element<'cfg>> {
         match element.element_type {
+            ElementType::Special {
+                kind: SpecialKind::Code,
+            } if self.config.inline_code_in_pre == Some(true) => {
+                let outer_tag = self.tag_name(&SpecialKind::CodeBlockContainer)?;
+                let inner_tag = self.tag_name(&SpecialKind::Code)?;
+                element.apply_tags(outer_tag.into_iter().chain(inner_tag).map(Into::into));
+            }
             ElementType::Special { ref kind } => {
                 let tag_name = self.tag_name(kind)?;
-                element.apply_tags([tag_name.into()]);
+                element.apply_tags(tag_name.map(Into::into));
             }
             ElementType::Multiline {
-                kind: MultilineKind::Code { .. },
+                kind: MultilineKind::Code { language },
             } => {
                 let outer_tag = self.tag_name(&SpecialKind::CodeBlockContainer)?;
                 let inner_tag = self.tag_name(&SpecialKind::Code)?;
-                element.apply_tags([outer_tag.into(), inner_tag.into()]);
+                let has_inner = inner_tag.is_some();
+                element.apply_tags(outer_tag.into_iter().chain(inner_tag).map(Into::into));
+
+                if let (Some(language), true, Some(code)) =
+                    (language, has_inner, element.selectors.last_mut())
+                {
+                    code.items.push(SelectorItem::Class {
+                        dot: language,
+                        value: format!("language-{}", language.slice(self.src)).into(),
+                    });
+                }
             }
             _ => (),
         }
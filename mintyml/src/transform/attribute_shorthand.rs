@@ -0,0 +1,49 @@
+use crate::document::{Content, Selector, SelectorItem};
+
+/// Reserved attributes that may be used in place of standard ones on any element,
+/// paired with the attribute each is replaced with.
+const SHORTHANDS: &[(&str, &str)] = &[("mty-lang", "lang"), ("mty-aria-label", "aria-label")];
+
+/// Replaces each reserved shorthand attribute in `content`, like `mty-lang`, with the attribute it
+/// stands for. Shorthands are matched case-insensitively, and are dropped if the element already
+/// has the attribute they stand for.
+pub fn apply_attribute_shorthands(content: &mut Content, src: &str) {
+    for node in &mut content.nodes {
+        let Some(element) = node.as_element_mut() else {
+            continue;
+        };
+
+        for &(shorthand, replacement) in SHORTHANDS {
+            let mut is_set = element
+                .selectors
+                .iter()
+                .flat_map(Selector::attributes)
+                .any(|(name, _)| name.as_str(src).eq_ignore_ascii_case(replacement));
+
+            let attribute_lists = element
+                .selectors
+                .iter_mut()
+                .flat_map(|s| &mut s.items)
+                .filter_map(|item| match item {
+                    SelectorItem::Attributes { attributes, .. } => Some(attributes),
+                    _ => None,
+                });
+
+            for attributes in attribute_lists {
+                attributes.retain_mut(|attribute| {
+                    if !attribute.name.as_str(src).eq_ignore_ascii_case(shorthand) {
+                        return true;
+                    }
+                    if is_set {
+                        return false;
+                    }
+                    attribute.name = replacement.into();
+                    is_set = true;
+                    true
+                });
+            }
+        }
+
+        apply_attribute_shorthands(&mut element.content, src);
+    }
+}
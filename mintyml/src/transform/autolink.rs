@@ -0,0 +1,156 @@
+use alloc::{string::String, vec, vec::Vec};
+use core::mem;
+
+use gramma::parse::LocationRange;
+
+use super::synthetic_attribute;
+use crate::document::{
+    Content, Element, ElementType, Node, NodeType, SelectorItem, Text, TextLike, TextSlice,
+};
+
+const SCHEMES: [&str; 2] = ["https://", "http://"];
+
+/// Returns the length of `url` without any trailing punctuation, keeping a `)` that closes a `(`
+/// within the URL.
+fn trim_url(mut url: &str) -> usize {
+    while let Some(last) = url.chars().next_back() {
+        let unbalanced = last == ')' && url.matches(')').count() > url.matches('(').count();
+        if !unbalanced && !matches!(last, '.' | ',' | ';' | ':' | '!' | '?' | '\'') {
+            break;
+        }
+        url = &url[..url.len() - 1];
+    }
+    url.len()
+}
+
+/// Finds the start and end of the first bare `http(s)://` URL in `text`.
+fn find_url(text: &str) -> Option<(usize, usize)> {
+    let mut from = 0;
+    while let Some(start) = text[from..].find("http").map(|i| from + i) {
+        from = start + 1;
+
+        let at_boundary = !text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || matches!(c, '\\' | '/'));
+        let rest = &text[start..];
+        let Some(scheme) = SCHEMES.iter().find(|s| rest.starts_with(**s)) else {
+            continue;
+        };
+        if !at_boundary {
+            continue;
+        }
+
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"'))
+            .unwrap_or(rest.len());
+        let len = trim_url(&rest[..len]);
+        if len > scheme.len() {
+            return Some((start, start + len));
+        }
+    }
+    None
+}
+
+/// Returns whether any tag in the selector chain of `element` is `tag`.
+fn has_tag(element: &Element, src: &str, tag: &str) -> bool {
+    element.selectors.iter().any(|s| {
+        s.tag
+            .name()
+            .is_some_and(|t| t.as_str(src).eq_ignore_ascii_case(tag))
+    })
+}
+
+/// Splits `text` at each bare URL, pushing the plain parts as text nodes and the URLs as `a`
+/// elements linking to themselves.
+fn split_text<'cfg>(range: LocationRange, text: Text<'cfg>, src: &str, out: &mut Vec<Node<'cfg>>) {
+    let value = text.slice.as_str(src);
+
+    let slice = |start: usize, end: usize| -> (LocationRange, TextSlice<'cfg>) {
+        match text.slice {
+            TextSlice::FromSource { range } => {
+                let range = LocationRange {
+                    start: range.start + start,
+                    end: range.start + end,
+                };
+                (range, range.into())
+            }
+            TextSlice::Provided { ref value } => (range, String::from(&value[start..end]).into()),
+        }
+    };
+    let node = |(range, slice): (LocationRange, TextSlice<'cfg>)| Node {
+        range,
+        node_type: NodeType::TextLike {
+            text_like: TextLike::Text {
+                text: Text {
+                    slice,
+                    multiline: text.multiline,
+                    unescape_in: text.unescape_in,
+                    escape_out: text.escape_out,
+                    raw: text.raw,
+                },
+            },
+        },
+    };
+
+    let mut last = 0;
+    while let Some((start, end)) = find_url(&value[last..]) {
+        let (start, end) = (last + start, last + end);
+        if start > last {
+            out.push(node(slice(last, start)));
+        }
+
+        let (url_range, url) = slice(start, end);
+
+        // The href shares the text's slice, so it's unescaped and escaped the same way on output.
+        let mut link = Element::new(url_range, ElementType::Unknown {}).with_tag("a");
+        link.format_inline = true;
+        link.selectors[0].items.push(SelectorItem::Attributes {
+            range: LocationRange::INVALID,
+            attributes: vec![synthetic_attribute("href", url.clone())],
+        });
+        link.content.nodes = vec![node((url_range, url))];
+        out.push(link.into());
+
+        last = end;
+    }
+
+    if last == 0 {
+        out.push(Node {
+            range,
+            node_type: NodeType::TextLike {
+                text_like: TextLike::Text { text },
+            },
+        });
+    } else if last < value.len() {
+        out.push(node(slice(last, value.len())));
+    }
+}
+
+/// Wraps each bare `http(s)://` URL in the text of `content` in an `a` element linking to it.
+///
+/// Text inside raw elements, `a` elements, verbatim segments, and multiline blocks is left alone.
+pub fn autolink(content: &mut Content, src: &str) {
+    let nodes = mem::take(&mut content.nodes);
+    content.nodes.reserve(nodes.len());
+
+    for Node { range, node_type } in nodes {
+        match node_type {
+            NodeType::Element { mut element } => {
+                if !element.is_raw() && !has_tag(&element, src, "a") {
+                    autolink(&mut element.content, src);
+                }
+                content.nodes.push(Node {
+                    range,
+                    node_type: NodeType::Element { element },
+                });
+            }
+            NodeType::TextLike {
+                text_like: TextLike::Text { text },
+            } if text.unescape_in && text.escape_out && !text.raw && !text.multiline => {
+                split_text(range, text, src, &mut content.nodes);
+            }
+            node_type => content.nodes.push(Node { range, node_type }),
+        }
+    }
+}
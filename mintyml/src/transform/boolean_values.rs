@@ -0,0 +1,48 @@
+use crate::document::{Content, SelectorItem};
+
+/// Attributes whose values are conventionally `true` or `false`, besides `aria-*` attributes.
+const BOOLEAN_LIKE: &[&str] = &["contenteditable", "draggable", "spellcheck"];
+
+fn is_boolean_like(name: &str) -> bool {
+    BOOLEAN_LIKE.iter().any(|n| n.eq_ignore_ascii_case(name))
+        || name
+            .get(..5)
+            .is_some_and(|p| p.eq_ignore_ascii_case("aria-"))
+}
+
+/// Lowercases `true` and `false` values of boolean-like attributes in `content`.
+pub fn normalize_boolean_values(content: &mut Content, src: &str) {
+    for node in &mut content.nodes {
+        let Some(element) = node.as_element_mut() else {
+            continue;
+        };
+
+        let attributes = element
+            .selectors
+            .iter_mut()
+            .flat_map(|s| &mut s.items)
+            .flat_map(|item| match item {
+                SelectorItem::Attributes { attributes, .. } => &mut attributes[..],
+                _ => &mut [],
+            });
+
+        for attribute in attributes {
+            if !is_boolean_like(attribute.name.as_str(src)) {
+                continue;
+            }
+
+            let Some(ref mut value) = attribute.value else {
+                continue;
+            };
+
+            let value_str = value.as_str(src);
+            if value_str.eq_ignore_ascii_case("true") && value_str != "true" {
+                *value = "true".into();
+            } else if value_str.eq_ignore_ascii_case("false") && value_str != "false" {
+                *value = "false".into();
+            }
+        }
+
+        normalize_boolean_values(&mut element.content, src);
+    }
+}
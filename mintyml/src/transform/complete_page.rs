@@ -3,9 +3,12 @@ use core::mem;
 
 use gramma::parse::LocationRange;
 
+use super::synthetic_attribute;
 use crate::{
-    document::{Document, Element, ElementType, Node, NodeType, Space},
+    config::CharsetMetaStyle,
+    document::{Document, Element, ElementType, Node, NodeType, SelectorItem, Space},
     error::InternalResult,
+    OutputConfig, Src,
 };
 
 /// Tags that generally belong in a `<head>` element.
@@ -47,8 +50,60 @@ where
     extract_element_with_tag_in(src, node, tags).is_some()
 }
 
+/// Determines whether `node` is a `<meta>` element that declares the character encoding.
+fn is_charset_meta(src: &str, node: &mut Node) -> bool {
+    let Some(e) = extract_element_with_tag_in(src, node, ["meta"]) else {
+        return false;
+    };
+
+    e.selectors
+        .iter()
+        .flat_map(|s| s.attributes())
+        .any(|(name, value)| {
+            let name = name.as_str(src);
+            name.eq_ignore_ascii_case("charset")
+                || name.eq_ignore_ascii_case("http-equiv")
+                    && value.is_some_and(|v| v.as_str(src).eq_ignore_ascii_case("content-type"))
+        })
+}
+
+/// Creates a `<meta>` element declaring the character encoding in the given style.
+fn charset_meta<'cfg>(style: CharsetMetaStyle) -> Node<'cfg> {
+    let attributes = match style {
+        CharsetMetaStyle::Short => vec![synthetic_attribute("charset", "utf-8")],
+        CharsetMetaStyle::HttpEquiv => vec![
+            synthetic_attribute("http-equiv", "Content-Type"),
+            synthetic_attribute("content", "text/html; charset=utf-8"),
+        ],
+    };
+
+    let mut meta = Element::new(LocationRange::INVALID, ElementType::Unknown {}).with_tag("meta");
+    meta.selectors[0].items.push(SelectorItem::Attributes {
+        range: LocationRange::INVALID,
+        attributes,
+    });
+    meta.into()
+}
+
+/// Creates a `<meta>` element with the given Open Graph `property` and `content`.
+fn open_graph_meta<'cfg>(property: Src<'cfg>, content: Src<'cfg>) -> Node<'cfg> {
+    let mut meta = Element::new(LocationRange::INVALID, ElementType::Unknown {}).with_tag("meta");
+    meta.selectors[0].items.push(SelectorItem::Attributes {
+        range: LocationRange::INVALID,
+        attributes: vec![
+            synthetic_attribute("property", property),
+            synthetic_attribute("content", content),
+        ],
+    });
+    meta.into()
+}
+
 /// Transforms `doc` so that its nodes are wrapped in `<html>` tags with a `<head>` and `<body>`
-pub fn complete_page<'cfg>(mut doc: Document<'cfg>, src: &str) -> InternalResult<Document<'cfg>> {
+pub fn complete_page<'cfg>(
+    mut doc: Document<'cfg>,
+    src: &str,
+    config: &OutputConfig<'cfg>,
+) -> InternalResult<Document<'cfg>> {
     if doc
         .content
         .nodes
@@ -93,6 +148,25 @@ pub fn complete_page<'cfg>(mut doc: Document<'cfg>, src: &str) -> InternalResult
             })
             .collect();
 
+        if let Some(style) = config.charset_meta {
+            if !head
+                .content
+                .nodes
+                .iter_mut()
+                .any(|n| is_charset_meta(src, n))
+            {
+                head.content.nodes.insert(0, charset_meta(style));
+            }
+        }
+
+        head.content.nodes.extend(
+            config
+                .open_graph
+                .iter()
+                .flatten()
+                .map(|(property, content)| open_graph_meta(property.clone(), content.clone())),
+        );
+
         root.content.nodes = vec![
             head.into(),
             Node {
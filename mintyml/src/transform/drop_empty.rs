@@ -0,0 +1,83 @@
+use alloc::string::String;
+
+use crate::{
+    document::{Content, Element, Tag, TextSlice},
+    output::is_configured_void,
+    utils::to_lowercase,
+    DropEmptyPolicy, OutputConfig,
+};
+
+/// Tags of elements that mean something even when they're empty, like table cells that keep the
+/// columns of a row lined up.
+const KEEP_EMPTY_TAGS: &[&str] = &[
+    "audio", "canvas", "iframe", "object", "script", "slot", "td", "template", "textarea", "th",
+    "video",
+];
+
+/// Returns whether `element` was added by a transform, like the `html`, `head`, and `body`
+/// elements added by [`OutputConfig::complete_page`], rather than written in the source.
+fn is_synthesized(element: &Element) -> bool {
+    element.selectors.iter().any(|s| {
+        !s.inferred
+            && matches!(
+                s.tag,
+                Tag::Explicit {
+                    value: TextSlice::Provided { .. },
+                    ..
+                }
+            )
+    })
+}
+
+fn is_droppable(
+    element: &Element,
+    src: &str,
+    config: &OutputConfig,
+    policy: DropEmptyPolicy,
+) -> bool {
+    let mut buf = String::new();
+
+    element.content.nodes.iter().all(|n| n.is_space())
+        && element.selectors.iter().all(|s| s.items.is_empty())
+        && (policy == DropEmptyPolicy::Always || element.selectors.iter().all(|s| s.inferred))
+        && !is_synthesized(element)
+        && !element
+            .selectors
+            .iter()
+            .filter_map(|s| s.tag.name())
+            .any(|tag| {
+                let tag = to_lowercase(tag.as_str(src), &mut buf);
+                KEEP_EMPTY_TAGS.contains(&tag) || is_configured_void(tag, config)
+            })
+}
+
+/// Removes each empty element in `content` that `policy` allows to be dropped, along with the
+/// whitespace before it.
+pub fn drop_empty(
+    content: &mut Content,
+    src: &str,
+    config: &OutputConfig,
+    policy: DropEmptyPolicy,
+) {
+    let mut i = 0;
+    while i < content.nodes.len() {
+        let Some(element) = content.nodes[i].as_element_mut() else {
+            i += 1;
+            continue;
+        };
+        drop_empty(&mut element.content, src, config, policy);
+
+        if !is_droppable(element, src, config, policy) {
+            i += 1;
+            continue;
+        }
+
+        content.nodes.remove(i);
+        if i > 0 && content.nodes[i - 1].is_space() {
+            content.nodes.remove(i - 1);
+            i -= 1;
+        } else if content.nodes.get(i).is_some_and(|n| n.is_space()) {
+            content.nodes.remove(i);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use alloc::{collections::BTreeSet, vec::Vec};
+
+use gramma::parse::LocationRange;
+
+use crate::{
+    document::{Content, SelectorItem, TextSlice},
+    error::{Errors, InternalResult, SemanticError, SemanticErrorKind},
+};
+
+fn collect<'a>(
+    content: &'a Content,
+    src: &'a str,
+    seen: &mut BTreeSet<&'a str>,
+    out: &mut Vec<SemanticError>,
+) {
+    for node in &content.nodes {
+        let Some(element) = node.as_element() else {
+            continue;
+        };
+
+        let mut check = |range: LocationRange, value: &'a TextSlice| {
+            let id = value.as_str(src);
+            if !seen.insert(id) {
+                out.push(SemanticError {
+                    range,
+                    kind: SemanticErrorKind::DuplicateId { id: id.into() },
+                });
+            }
+        };
+
+        for item in element.selectors.iter().flat_map(|s| &s.items) {
+            match item {
+                SelectorItem::Id { hash, value } => check(
+                    match value {
+                        TextSlice::FromSource { range } => hash.combine(*range),
+                        TextSlice::Provided { .. } => *hash,
+                    },
+                    value,
+                ),
+                SelectorItem::Attributes { attributes, .. } => {
+                    let ids = attributes
+                        .iter()
+                        .filter(|a| a.name.as_str(src).eq_ignore_ascii_case("id"));
+                    for attribute in ids {
+                        if let Some(value) = &attribute.value {
+                            check(attribute.range, value);
+                        }
+                    }
+                }
+                SelectorItem::Class { .. } => {}
+            }
+        }
+
+        collect(&element.content, src, seen, out);
+    }
+}
+
+/// Reports each `#id` or `id` attribute in `content` that was already given to an earlier element.
+pub fn check_duplicate_ids(content: &Content, src: &str, errors: &mut Errors) -> InternalResult {
+    let mut duplicates = Vec::new();
+    collect(content, src, &mut BTreeSet::new(), &mut duplicates);
+    errors.semantic(duplicates)
+}
@@ -0,0 +1,125 @@
+use alloc::{string::String, vec::Vec};
+
+use gramma::parse::LocationRange;
+
+use super::synthetic_attribute;
+use crate::{
+    config::ElementVisitor,
+    document::{Content, Selector, SelectorItem},
+};
+
+/// A view of an element passed to an [`ElementVisitor`]. See [`OutputConfig::element_visitor`].
+///
+/// Each tag of a chain like `ul>li>` is visited as its own element.
+///
+/// [`OutputConfig::element_visitor`]: crate::OutputConfig::element_visitor
+pub struct ElementRef<'a> {
+    src: &'a str,
+    selector: &'a Selector<'a>,
+    parent_tag: Option<&'a str>,
+    index: usize,
+}
+
+impl<'a> ElementRef<'a> {
+    /// The tag of this element, if known.
+    pub fn tag(&self) -> Option<&'a str> {
+        Some(self.selector.tag.name()?.as_str(self.src))
+    }
+
+    /// The tag of the element containing this one, if any.
+    pub fn parent_tag(&self) -> Option<&'a str> {
+        self.parent_tag
+    }
+
+    /// The position of this element among the elements sharing its parent, starting at `0`.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The ID of this element, if any.
+    pub fn id(&self) -> Option<&'a str> {
+        Some(self.selector.id()?.as_str(self.src))
+    }
+
+    /// Whether this element has the given class.
+    pub fn has_class(&self, class: &str) -> bool {
+        self.selector
+            .class_names()
+            .any(|c| c.as_str(self.src) == class)
+    }
+
+    /// The value of the attribute with the given name, or `Some("")` if it has no value.
+    pub fn attribute(&self, name: &str) -> Option<&'a str> {
+        let (_, value) = self
+            .selector
+            .attributes()
+            .find(|(n, _)| n.as_str(self.src) == name)?;
+        Some(value.map_or("", |v| v.as_str(self.src)))
+    }
+
+    /// Whether an attribute with the given name, matched case-insensitively, would be written for
+    /// this element.
+    fn has_attribute(&self, name: &str) -> bool {
+        (name.eq_ignore_ascii_case("id") && self.selector.id().is_some())
+            || (name.eq_ignore_ascii_case("class") && self.selector.class_names().next().is_some())
+            || self
+                .selector
+                .attributes()
+                .any(|(n, _)| n.as_str(self.src).eq_ignore_ascii_case(name))
+    }
+}
+
+/// Calls `visitor` for each element in `content`, adding the attributes it returns to the element
+/// unless they are already present. If `visitor` returns the same name more than once, only the
+/// first is added.
+pub fn visit_elements(
+    content: &mut Content,
+    src: &str,
+    parent_tag: Option<&str>,
+    visitor: &ElementVisitor,
+) {
+    let mut index = 0;
+    for node in &mut content.nodes {
+        let Some(element) = node.as_element_mut() else {
+            continue;
+        };
+
+        let mut parent_tag = parent_tag.map(String::from);
+        for (i, selector) in element.selectors.iter_mut().enumerate() {
+            let element_ref = ElementRef {
+                src,
+                selector,
+                parent_tag: parent_tag.as_deref(),
+                index: if i == 0 { index } else { 0 },
+            };
+
+            let mut attributes = Vec::new();
+            visitor.call(&element_ref, &mut attributes);
+            let mut added = Vec::<String>::new();
+            attributes.retain(|(name, _)| {
+                let duplicate = element_ref.has_attribute(name)
+                    || added.iter().any(|a| a.eq_ignore_ascii_case(name));
+                if !duplicate {
+                    added.push(name.clone());
+                }
+                !duplicate
+            });
+            let tag = element_ref.tag().map(String::from);
+
+            if !attributes.is_empty() {
+                selector.items.push(SelectorItem::Attributes {
+                    range: LocationRange::INVALID,
+                    attributes: Vec::from_iter(
+                        attributes
+                            .into_iter()
+                            .map(|(name, value)| synthetic_attribute(name, value)),
+                    ),
+                });
+            }
+            parent_tag = tag.or(parent_tag);
+        }
+
+        visit_elements(&mut element.content, src, parent_tag.as_deref(), visitor);
+        index += 1;
+    }
+}
@@ -0,0 +1,30 @@
+use alloc::format;
+use gramma::parse::LocationRange;
+
+use crate::document::{Content, SelectorItem};
+
+/// Adds a class made of `prefix` and the tag to each selector in `content` whose tag was inferred.
+pub fn add_inferred_classes(content: &mut Content, src: &str, prefix: &str) {
+    for node in &mut content.nodes {
+        let Some(element) = node.as_element_mut() else {
+            continue;
+        };
+
+        for selector in element.selectors.iter_mut().filter(|s| s.inferred) {
+            let Some(tag) = selector.tag.name() else {
+                continue;
+            };
+            let class = format!("{prefix}{}", tag.as_str(src));
+
+            selector.items.push(SelectorItem::Class {
+                dot: LocationRange {
+                    start: selector.range.end,
+                    end: selector.range.end,
+                },
+                value: class.into(),
+            });
+        }
+
+        add_inferred_classes(&mut element.content, src, prefix);
+    }
+}
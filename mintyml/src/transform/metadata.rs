@@ -131,15 +131,19 @@ impl<'cfg> AttributeFactory<'cfg> {
     }
 }
 
-struct TransformContext<'cx, 'cfg> {
-    options: &'cx MetadataConfig,
+struct TransformContext<'cfg> {
+    elements: bool,
+    ranges: bool,
+    flags: bool,
     _lt: PhantomData<&'cfg ()>,
 }
 
-impl<'cx, 'cfg> TransformContext<'cx, 'cfg> {
-    fn new(options: &'cx MetadataConfig) -> Self {
+impl<'cfg> TransformContext<'cfg> {
+    fn new(options: &MetadataConfig) -> Self {
         Self {
-            options,
+            elements: options.elements.unwrap_or(false),
+            ranges: options.ranges.unwrap_or(true),
+            flags: options.flags.unwrap_or(true),
             _lt: PhantomData,
         }
     }
@@ -158,7 +162,7 @@ impl<'cx, 'cfg> TransformContext<'cx, 'cfg> {
         root: bool,
     ) -> InternalResult {
         if !element.selectors.iter().any(|s| !s.uninferred()) {
-            if !self.options.elements {
+            if !self.elements {
                 return Ok(());
             }
 
@@ -168,7 +172,7 @@ impl<'cx, 'cfg> TransformContext<'cx, 'cfg> {
         }
 
         // Index of the outermost selector
-        let outer_selector_index = if self.options.elements {
+        let outer_selector_index = if self.elements {
             0
         } else {
             element
@@ -183,7 +187,7 @@ impl<'cx, 'cfg> TransformContext<'cx, 'cfg> {
 
         for (i, selector) in element.selectors.iter_mut().enumerate().rev() {
             if selector.uninferred() {
-                if !self.options.elements {
+                if !self.elements {
                     continue;
                 }
                 selector.tag = Tag::Explicit {
@@ -198,7 +202,9 @@ impl<'cx, 'cfg> TransformContext<'cx, 'cfg> {
                     attrs.add(attr::XMLNS.into(), XMLNS_URI)?;
                 }
                 range = outer_range;
-                attrs.add_bool_except(attr::RAW, element.is_raw, false)?;
+                if self.flags {
+                    attrs.add_bool_except(attr::RAW, element.is_raw, false)?;
+                }
             } else {
                 range = content_range.combine(LocationRange {
                     start: selector.range.start,
@@ -206,11 +212,13 @@ impl<'cx, 'cfg> TransformContext<'cx, 'cfg> {
                 });
             }
 
-            attrs.add_range(attr::START, attr::END, range)?.add_range(
-                attr::CONTENT_START,
-                attr::CONTENT_END,
-                element.content.range,
-            )?;
+            if self.ranges {
+                attrs.add_range(attr::START, attr::END, range)?.add_range(
+                    attr::CONTENT_START,
+                    attr::CONTENT_END,
+                    element.content.range,
+                )?;
+            }
 
             attrs.finish(selector)?;
             content_range = content_range.combine(range);
@@ -218,29 +226,26 @@ impl<'cx, 'cfg> TransformContext<'cx, 'cfg> {
 
         Ok(())
     }
-    fn process_node(
-        &mut self,
-        mut node: Node<'cfg>,
-        options: &MetadataConfig,
-        root: bool,
-    ) -> InternalResult<Node<'cfg>> {
+    fn process_node(&mut self, mut node: Node<'cfg>, root: bool) -> InternalResult<Node<'cfg>> {
         let range = node.range;
         match node.node_type {
             NodeType::Element { ref mut element } => {
                 self.handle_element(range, element, root)?;
                 element.content.nodes = mem::take(&mut element.content.nodes)
                     .into_iter()
-                    .map(|n| self.process_node(n, options, false))
+                    .map(|n| self.process_node(n, false))
                     .collect::<Result<_, _>>()?;
             }
             NodeType::TextLike {
                 text_like: TextLike::Text { ref text },
-            } if options.elements && !text.raw => {
+            } if self.elements && !text.raw => {
                 let mut attrs = self.attrs(range.start);
 
-                attrs
-                    .add_bool_except(attr::VERBATIM, !text.unescape_in, false)?
-                    .add_bool_except(attr::MULTILINE, text.multiline, false)?;
+                if self.flags {
+                    attrs
+                        .add_bool_except(attr::VERBATIM, !text.unescape_in, false)?
+                        .add_bool_except(attr::MULTILINE, text.multiline, false)?;
+                }
 
                 let mut selector = Selector::empty(range.start).with_tag(tag::TEXT);
                 attrs.finish(&mut selector)?;
@@ -257,8 +262,8 @@ impl<'cx, 'cfg> TransformContext<'cx, 'cfg> {
             }
             NodeType::TextLike {
                 text_like: TextLike::Comment { comment },
-            } if options.elements => {
-                let Comment::Tag { slice } = comment;
+            } if self.elements => {
+                let (Comment::Tag { slice } | Comment::Raw { slice }) = comment;
                 let mut element =
                     Element::new(range, ElementType::Unknown {}).with_tag(tag::COMMENT);
 
@@ -296,7 +301,7 @@ pub fn add_metadata<'cfg>(
         .content
         .nodes
         .into_iter()
-        .map(|n| cx.process_node(n, options, true))
+        .map(|n| cx.process_node(n, true))
         .collect::<Result<_, _>>()?;
 
     Ok(target)
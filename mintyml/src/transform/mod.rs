@@ -1,65 +1,303 @@
-use alloc::{borrow::Cow, vec};
+use alloc::{borrow::Cow, format, string::ToString, vec, vec::Vec};
+use core::mem;
 use gramma::parse::LocationRange;
 
 use crate::{
-    document::{Attribute, Document, Element, NodeType},
+    document::{Attribute, Document, Element, ElementType, Node, NodeType, TextSlice},
     error::{Errors, InternalResult},
-    OutputConfig,
+    inference::engine::InferenceOptions,
+    DropEmptyPolicy, LangScope, OutputConfig, Src,
 };
 
 use self::apply_special_tags::apply_special_tags;
 
+pub use self::element_visitor::ElementRef;
+
+mod alt_text;
 mod apply_special_tags;
+mod attribute_shorthand;
+mod autolink;
+mod boolean_values;
 mod complete_page;
+mod drop_empty;
+mod duplicate_ids;
+mod element_visitor;
+mod inferred_class;
 mod metadata;
+mod nesting;
+mod required_values;
+mod responsive_image;
+mod sort_siblings;
+mod table_wrapper;
+mod variables;
+mod void_comments;
+
+/// Assigns the `lang` and `dir` attributes from `config` to each top-level element, or only to
+/// the root element if `config.lang_scope` is [`LangScope::RootOnly`]. An empty document is left
+/// empty.
+fn apply_lang<'src>(document: &mut Document<'src>, config: &OutputConfig<'src>) {
+    let lang = config.lang.as_ref();
+    let dir = config.dir.and_then(|d| d.value(lang.map(|l| &**l)));
+
+    if lang.is_none() && dir.is_none() || document.content.nodes.iter().all(Node::is_space) {
+        return;
+    }
 
-/// If `lang` contains a value, assign it to the `lang` attribute of each top-level element.
-fn apply_lang<'src>(document: &mut Document<'src>, lang: &Option<Cow<'src, str>>) {
-    if let Some(ref lang) = lang {
-        for node in &mut document.content.nodes {
-            if let NodeType::Element {
-                element: Element { selectors, .. },
-            } = &mut node.node_type
-            {
-                if let Some(selector) = selectors.iter_mut().find(|s| !s.uninferred()) {
-                    let range = LocationRange {
-                        start: selector.range.end,
-                        end: selector.range.end,
-                    };
-                    selector
-                        .items
-                        .push(crate::document::SelectorItem::Attributes {
-                            range,
-                            attributes: vec![Attribute {
-                                range,
-                                name: "lang".into(),
-                                value: Some(lang.clone().into()),
-                            }],
-                        })
-                }
+    if config.lang_scope == Some(LangScope::RootOnly)
+        && document
+            .content
+            .nodes
+            .iter()
+            .filter(|n| n.as_element().is_some())
+            .count()
+            != 1
+    {
+        wrap_root(document, &Src::Borrowed("div"));
+    }
+
+    for node in &mut document.content.nodes {
+        if let NodeType::Element {
+            element: Element { selectors, .. },
+        } = &mut node.node_type
+        {
+            if let Some(selector) = selectors.iter_mut().find(|s| !s.uninferred()) {
+                let range = LocationRange {
+                    start: selector.range.end,
+                    end: selector.range.end,
+                };
+                let attribute = |name, value: Cow<'src, str>| Attribute {
+                    range,
+                    ..synthetic_attribute(name, value)
+                };
+
+                selector
+                    .items
+                    .push(crate::document::SelectorItem::Attributes {
+                        range,
+                        attributes: Vec::from_iter(
+                            lang.map(|l| attribute("lang", l.clone()))
+                                .into_iter()
+                                .chain(dir.map(|d| attribute("dir", d.into()))),
+                        ),
+                    })
             }
         }
     }
 }
 
+/// Creates an attribute that doesn't appear in the source, for a transform to add to an element.
+fn synthetic_attribute<'cfg>(
+    name: impl Into<TextSlice<'cfg>>,
+    value: impl Into<TextSlice<'cfg>>,
+) -> Attribute<'cfg> {
+    Attribute {
+        range: LocationRange::INVALID,
+        name: name.into(),
+        value: Some(value.into()),
+    }
+}
+
+/// Moves all top-level nodes of `document` into a single element with the given tag.
+fn wrap_root<'src>(document: &mut Document<'src>, tag: &Src<'src>) {
+    let mut root =
+        Element::new(document.content.range, ElementType::Unknown {}).with_tag(tag.clone());
+    root.content.nodes = mem::take(&mut document.content.nodes);
+    document.content.nodes = vec![root.into()];
+}
+
+/// Returns whether `node` is an element whose tag is `tag`.
+fn is_element(node: &Node, src: &str, tag: &str) -> bool {
+    node.as_element()
+        .and_then(|e| e.selectors.first())
+        .and_then(|s| s.tag.name())
+        .is_some_and(|t| t.as_str(src).eq_ignore_ascii_case(tag))
+}
+
+/// Returns whether the innermost tag of `element` is `tag`.
+fn last_tag_is(element: &Element, src: &str, tag: &str) -> bool {
+    element
+        .selectors
+        .last()
+        .and_then(|s| s.tag.name())
+        .is_some_and(|t| t.as_str(src).eq_ignore_ascii_case(tag))
+}
+
+/// Wraps the top-level nodes of `document` in an `html` element if they include both a `head` and
+/// a `body` element but no `html` element.
+fn wrap_html(document: &mut Document, src: &str) {
+    let nodes = &document.content.nodes;
+    let has = |tag| nodes.iter().any(|n| is_element(n, src, tag));
+
+    if has("head") && has("body") && !has("html") {
+        wrap_root(document, &Src::Borrowed("html"));
+    }
+}
+
+/// Adds the XHTML namespace to each top-level `html` element that has no `xmlns` attribute.
+fn add_xhtml_namespace(document: &mut Document, src: &str) {
+    for node in &mut document.content.nodes {
+        if !is_element(node, src, "html") {
+            continue;
+        }
+        let Some(selector) = node.as_element_mut().and_then(|e| e.selectors.first_mut()) else {
+            continue;
+        };
+        if selector
+            .attributes()
+            .any(|(name, _)| name.as_str(src) == "xmlns")
+        {
+            continue;
+        }
+
+        selector
+            .items
+            .push(crate::document::SelectorItem::Attributes {
+                range: LocationRange::INVALID,
+                attributes: vec![synthetic_attribute("xmlns", "http://www.w3.org/1999/xhtml")],
+            });
+    }
+}
+
+/// Declares each `(prefix, uri)` namespace in `namespaces` on the root element, skipping any it
+/// already declares. An empty prefix declares the default namespace. Does nothing unless the
+/// document has exactly one top-level element.
+fn apply_namespaces<'src>(
+    document: &mut Document<'src>,
+    src: &str,
+    namespaces: &[(Src<'src>, Src<'src>)],
+) {
+    let mut elements = document
+        .content
+        .nodes
+        .iter_mut()
+        .filter_map(|n| n.as_element_mut());
+    let (Some(root), None) = (elements.next(), elements.next()) else {
+        return;
+    };
+    let Some(selector) = root.selectors.first_mut() else {
+        return;
+    };
+
+    let attributes = Vec::from_iter(namespaces.iter().filter_map(|(prefix, uri)| {
+        let name = match &**prefix {
+            "" => "xmlns".to_string(),
+            prefix => format!("xmlns:{prefix}"),
+        };
+        if selector.attributes().any(|(n, _)| n.as_str(src) == name) {
+            return None;
+        }
+        Some(synthetic_attribute(name, uri.clone()))
+    }));
+
+    if !attributes.is_empty() {
+        selector
+            .items
+            .push(crate::document::SelectorItem::Attributes {
+                range: LocationRange::INVALID,
+                attributes,
+            });
+    }
+}
+
 pub fn transform_document<'cfg>(
     mut document: Document<'cfg>,
     src: &'cfg str,
     config: &OutputConfig<'cfg>,
     errors: &mut Errors,
 ) -> InternalResult<Document<'cfg>> {
-    document = apply_special_tags(document, config, errors)?;
+    document = apply_special_tags(document, src, config, errors)?;
+    attribute_shorthand::apply_attribute_shorthands(&mut document.content, src);
+
+    if let Some(ref variables) = config.variables {
+        variables::substitute_variables(&mut document.content, src, variables, errors)?;
+    }
+
+    if config.normalize_boolean_values.unwrap_or(false) {
+        boolean_values::normalize_boolean_values(&mut document.content, src);
+    }
 
     if config.complete_page.unwrap_or(false) {
-        document = complete_page::complete_page(document, src)?;
+        document = complete_page::complete_page(document, src, config)?;
+    } else if let Some(ref tag) = config.root_element {
+        wrap_root(&mut document, tag);
+    } else if config.auto_html_wrap.unwrap_or(false) {
+        wrap_html(&mut document, src);
+    }
+
+    crate::inference::engine::infer(
+        src,
+        &mut document.content,
+        config.inference.as_ref().map(|i| i.get()),
+        InferenceOptions {
+            table_header_row: config.table_header_row.unwrap_or(false),
+            class_placement: config.inferred_class_placement.unwrap_or_default(),
+        },
+    );
+
+    match config.drop_empty.unwrap_or_default() {
+        DropEmptyPolicy::Never => {}
+        policy => drop_empty::drop_empty(&mut document.content, src, config, policy),
+    }
+
+    sort_siblings::sort_siblings_by_id(
+        &mut document.content,
+        src,
+        config.sort_siblings_by_id.unwrap_or(false),
+    );
+
+    if config.autolink.unwrap_or(false) {
+        autolink::autolink(&mut document.content, src);
+    }
+
+    if let Some(ref resolver) = config.responsive_image_resolver {
+        responsive_image::apply_responsive_images(&mut document.content, src, resolver);
     }
 
-    crate::inference::engine::infer(src, &mut document.content);
+    if let Some(ref spec) = config.table_wrapper {
+        table_wrapper::wrap_tables(&mut document.content, src, spec);
+    }
+
+    if config.relocate_void_comments.unwrap_or(false) {
+        void_comments::relocate_void_comments(&mut document.content, src, config);
+    }
+
+    if let Some(ref prefix) = config.inferred_wrapper_class_prefix {
+        inferred_class::add_inferred_classes(&mut document.content, src, prefix);
+    }
+
+    if let Some(ref visitor) = config.element_visitor {
+        element_visitor::visit_elements(&mut document.content, src, None, visitor);
+    }
+
+    if config.check_duplicate_ids.unwrap_or(false) {
+        duplicate_ids::check_duplicate_ids(&document.content, src, errors)?;
+    }
+
+    if config.strict_attributes.unwrap_or(false) {
+        required_values::check_required_values(&document.content, src, config, errors)?;
+    }
+
+    if config.strict_nesting.unwrap_or(false) {
+        nesting::check_nesting(&document.content, src, errors)?;
+    }
+
+    if config.warn_missing_alt.unwrap_or(false) {
+        alt_text::check_alt_text(&document.content, src, errors);
+    }
 
     if let Some(ref metadata) = config.metadata {
         document = metadata::add_metadata(document, metadata)?;
     }
 
-    apply_lang(&mut document, &config.lang);
+    if config.xhtml_strict.unwrap_or(false) {
+        add_xhtml_namespace(&mut document, src);
+    }
+
+    let is_xml = config.xml.unwrap_or(false) || config.xhtml_strict.unwrap_or(false);
+    if let Some(namespaces) = config.namespaces.as_ref().filter(|_| is_xml) {
+        apply_namespaces(&mut document, src, namespaces);
+    }
+
+    apply_lang(&mut document, config);
     Ok(document)
 }
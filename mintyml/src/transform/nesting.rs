@@ -0,0 +1,54 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    document::Content,
+    error::{Errors, InternalResult, SemanticError, SemanticErrorKind},
+    inference::definitions::is_invalid_child,
+    utils::to_lowercase,
+};
+
+fn collect<'a>(
+    content: &'a Content,
+    src: &'a str,
+    parent: Option<&'a str>,
+    out: &mut Vec<SemanticError>,
+) {
+    for node in &content.nodes {
+        let Some(element) = node.as_element() else {
+            continue;
+        };
+
+        // Each tag of an element like `p>span>` is nested inside the one before it.
+        let mut parent = parent;
+        for tag in element.selectors.iter().filter_map(|s| s.tag.name()) {
+            let tag = tag.as_str(src);
+
+            if let Some(parent) = parent {
+                let (mut parent_buf, mut tag_buf) = (String::new(), String::new());
+                if is_invalid_child(
+                    to_lowercase(parent, &mut parent_buf),
+                    to_lowercase(tag, &mut tag_buf),
+                ) {
+                    out.push(SemanticError {
+                        range: element.range,
+                        kind: SemanticErrorKind::InvalidNesting {
+                            parent: parent.into(),
+                            child: tag.into(),
+                        },
+                    });
+                }
+            }
+            parent = Some(tag);
+        }
+
+        collect(&element.content, src, parent, out);
+    }
+}
+
+/// Reports each element in `content` that HTML doesn't allow inside its parent, like a `div`
+/// inside a `p`.
+pub fn check_nesting(content: &Content, src: &str, errors: &mut Errors) -> InternalResult {
+    let mut invalid = Vec::new();
+    collect(content, src, None, &mut invalid);
+    errors.semantic(invalid)
+}
@@ -0,0 +1,71 @@
+use alloc::vec::Vec;
+
+use crate::{
+    document::{Content, SelectorItem},
+    error::{Errors, InternalResult, SemanticError, SemanticErrorKind},
+    OutputConfig,
+};
+
+/// Attributes that are meaningless without a value.
+const VALUE_REQUIRED: &[&str] = &[
+    "action",
+    "cite",
+    "data",
+    "for",
+    "formaction",
+    "href",
+    "poster",
+    "src",
+    "srcset",
+];
+
+fn requires_value(name: &str, config: &OutputConfig) -> bool {
+    VALUE_REQUIRED.iter().any(|n| n.eq_ignore_ascii_case(name))
+        || config
+            .value_required_attributes
+            .iter()
+            .flatten()
+            .any(|n| n.eq_ignore_ascii_case(name))
+}
+
+fn collect(content: &Content, src: &str, config: &OutputConfig, out: &mut Vec<SemanticError>) {
+    for node in &content.nodes {
+        let Some(element) = node.as_element() else {
+            continue;
+        };
+
+        let attributes =
+            element
+                .selectors
+                .iter()
+                .flat_map(|s| &s.items)
+                .flat_map(|item| match item {
+                    SelectorItem::Attributes { attributes, .. } => &attributes[..],
+                    _ => &[],
+                });
+
+        for attribute in attributes {
+            let name = attribute.name.as_str(src);
+            if attribute.value.is_none() && requires_value(name, config) {
+                out.push(SemanticError {
+                    range: attribute.range,
+                    kind: SemanticErrorKind::MissingAttributeValue { name: name.into() },
+                });
+            }
+        }
+
+        collect(&element.content, src, config, out);
+    }
+}
+
+/// Reports each attribute in `content` that requires a value but was given none, like `[href]`.
+pub fn check_required_values(
+    content: &Content,
+    src: &str,
+    config: &OutputConfig,
+    errors: &mut Errors,
+) -> InternalResult {
+    let mut missing = Vec::new();
+    collect(content, src, config, &mut missing);
+    errors.semantic(missing)
+}
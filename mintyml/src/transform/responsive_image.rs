@@ -0,0 +1,89 @@
+use alloc::{string::String, vec::Vec};
+use core::{iter, mem};
+
+use gramma::parse::LocationRange;
+
+use super::{last_tag_is, synthetic_attribute};
+use crate::{
+    config::ResponsiveImageResolver,
+    document::{Content, Element, ElementType, Node, SelectorItem},
+};
+
+fn source<'cfg>(media: String, srcset: String) -> Node<'cfg> {
+    let mut attributes = Vec::with_capacity(2);
+    if !media.is_empty() {
+        attributes.push(synthetic_attribute("media", media));
+    }
+    attributes.push(synthetic_attribute("srcset", srcset));
+
+    let mut source =
+        Element::new(LocationRange::INVALID, ElementType::Unknown {}).with_tag("source");
+    source.selectors[0].items.push(SelectorItem::Attributes {
+        range: LocationRange::INVALID,
+        attributes,
+    });
+    source.into()
+}
+
+/// Wraps each `img` element in `content` whose `src` is known to `resolver` in a `picture` element,
+/// with a `source` element for each `(media, srcset)` pair followed by the original `img`.
+///
+/// Images already inside a `picture` element are left alone.
+pub fn apply_responsive_images(
+    content: &mut Content,
+    src: &str,
+    resolver: &ResponsiveImageResolver,
+) {
+    for node in &mut content.nodes {
+        let Some(element) = node.as_element_mut() else {
+            continue;
+        };
+
+        if last_tag_is(element, src, "picture") {
+            continue;
+        }
+
+        if !last_tag_is(element, src, "img") {
+            apply_responsive_images(&mut element.content, src, resolver);
+            continue;
+        }
+
+        if element.selectors.len() > 1 {
+            // Handle the `img` in `a>img>` separately from the elements containing it.
+            element.split_at(element.selectors.len() - 1);
+            if !last_tag_is(element, src, "picture") {
+                apply_responsive_images(&mut element.content, src, resolver);
+            }
+            continue;
+        }
+
+        let Some(img_src) = element.selectors[0]
+            .attributes()
+            .find(|(name, _)| name.as_str(src).eq_ignore_ascii_case("src"))
+            .and_then(|(_, value)| value)
+        else {
+            continue;
+        };
+
+        let sources = match resolver.resolve(img_src.as_str(src)) {
+            Some(sources) if !sources.is_empty() => sources,
+            _ => continue,
+        };
+
+        let element_type = element.element_type.clone();
+        let format_inline = element.format_inline;
+        let range = node.range;
+
+        let mut picture = Element::new(range, element_type).with_tag("picture");
+        picture.format_inline = format_inline;
+
+        let img = mem::replace(node, Element::new(range, ElementType::Unknown {}).into());
+        picture.content.nodes = Vec::from_iter(
+            sources
+                .into_iter()
+                .map(|(media, srcset)| source(media, srcset))
+                .chain(iter::once(img)),
+        );
+        *node = picture.into();
+    }
+}
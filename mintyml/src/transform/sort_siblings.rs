@@ -0,0 +1,66 @@
+use alloc::vec::Vec;
+
+use crate::document::{Content, Element, Node, SelectorItem};
+
+/// Reserved attribute marking an element whose children may be sorted by ID.
+const SORT_MARKER: &str = "mty-sort";
+
+/// The ID of the outermost element of `node`, whether given as `#id` or `[id=...]`.
+fn id<'a>(node: &'a Node, src: &'a str) -> Option<&'a str> {
+    let selector = node.as_element()?.selectors.first()?;
+    selector
+        .id()
+        .or_else(|| {
+            selector
+                .attributes()
+                .find(|(name, _)| name.as_str(src) == "id")
+                .and_then(|(_, value)| value)
+        })
+        .map(|id| id.as_str(src))
+}
+
+/// Removes any `mty-sort` attribute from `element`, returning whether one was found.
+fn take_sort_marker(element: &mut Element, src: &str) -> bool {
+    let mut found = false;
+    for item in element.selectors.iter_mut().flat_map(|s| &mut s.items) {
+        if let SelectorItem::Attributes { attributes, .. } = item {
+            attributes.retain(|a| {
+                let is_marker = a.name.as_str(src) == SORT_MARKER;
+                found |= is_marker;
+                !is_marker
+            });
+        }
+    }
+    found
+}
+
+/// Reorders the children with IDs of `content` by ID, leaving all other nodes in place.
+fn sort_by_id(content: &mut Content, src: &str) {
+    let positions =
+        Vec::from_iter((0..content.nodes.len()).filter(|&i| id(&content.nodes[i], src).is_some()));
+    let mut order = positions.clone();
+    order.sort_by(|&a, &b| id(&content.nodes[a], src).cmp(&id(&content.nodes[b], src)));
+
+    let mut nodes = Vec::from_iter(content.nodes.drain(..).map(Some));
+    let mut sorted = Vec::from_iter(order.iter().map(|&i| nodes[i].take()));
+    for (&i, node) in positions.iter().zip(&mut sorted) {
+        nodes[i] = node.take();
+    }
+    content.nodes = nodes.into_iter().flatten().collect();
+}
+
+/// Sorts the children of each element in `content` marked with `mty-sort` by ID, and removes the
+/// marker. If `enabled` is `false`, the markers are removed without sorting.
+pub fn sort_siblings_by_id(content: &mut Content, src: &str, enabled: bool) {
+    for node in &mut content.nodes {
+        let Some(element) = node.as_element_mut() else {
+            continue;
+        };
+
+        if take_sort_marker(element, src) && enabled {
+            sort_by_id(&mut element.content, src);
+        }
+
+        sort_siblings_by_id(&mut element.content, src, enabled);
+    }
+}
@@ -0,0 +1,82 @@
+use alloc::{string::String, vec};
+use core::mem;
+
+use gramma::parse::LocationRange;
+
+use super::last_tag_is;
+use crate::document::{Content, Element, ElementType, SelectorItem};
+
+/// Builds an empty element from a selector like `div.table-wrap` or `.scroll#results`.
+///
+/// Only the tag, classes, and ID are read. The tag defaults to `div`.
+fn wrapper<'cfg>(spec: &str, range: LocationRange) -> Element<'cfg> {
+    let markers = ['.', '#'];
+    let end = spec.find(markers).unwrap_or(spec.len());
+    let tag = match spec[..end].trim() {
+        "" => "div",
+        tag => tag,
+    };
+
+    let mut element = Element::new(range, ElementType::Unknown {}).with_tag(String::from(tag));
+    let selector = &mut element.selectors[0];
+    let mut rest = &spec[end..];
+
+    while let Some(marker) = rest.chars().next() {
+        let item = &rest[1..];
+        let len = item.find(markers).unwrap_or(item.len());
+        let value = String::from(&item[..len]);
+        rest = &item[len..];
+
+        if value.is_empty() {
+            continue;
+        }
+
+        selector.items.push(match marker {
+            '#' => SelectorItem::Id {
+                hash: LocationRange::INVALID,
+                value: value.into(),
+            },
+            _ => SelectorItem::Class {
+                dot: LocationRange::INVALID,
+                value: value.into(),
+            },
+        });
+    }
+
+    element
+}
+
+/// Wraps each `table` element in `content`, including nested tables, in an element described by
+/// the selector `spec`.
+pub fn wrap_tables(content: &mut Content, src: &str, spec: &str) {
+    for node in &mut content.nodes {
+        let Some(element) = node.as_element_mut() else {
+            continue;
+        };
+
+        if !last_tag_is(element, src, "table") {
+            wrap_tables(&mut element.content, src, spec);
+            continue;
+        }
+
+        if element.selectors.len() > 1 {
+            // Handle the `table` in `section>table>` separately from the elements containing it.
+            element.split_at(element.selectors.len() - 1);
+            wrap_tables(&mut element.content, src, spec);
+            continue;
+        }
+
+        wrap_tables(&mut element.content, src, spec);
+
+        let format_inline = element.format_inline;
+        let range = node.range;
+
+        let mut wrapper = wrapper(spec, range);
+        wrapper.format_inline = format_inline;
+        wrapper.content.nodes = vec![mem::replace(
+            node,
+            Element::new(range, ElementType::Unknown {}).into(),
+        )];
+        *node = wrapper.into();
+    }
+}
@@ -0,0 +1,114 @@
+use alloc::{borrow::Cow, collections::BTreeMap, string::String, vec::Vec};
+
+use gramma::parse::{Location, LocationRange};
+
+use crate::{
+    document::{Content, SelectorItem, TextSlice},
+    error::{Errors, InternalResult, SemanticError, SemanticErrorKind},
+};
+
+/// Returns the length of the variable name at the start of `s`.
+fn name_len(s: &str) -> usize {
+    s.find(|c: char| !c.is_alphanumeric() && c != '_')
+        .unwrap_or(s.len())
+}
+
+/// Replaces each `$name` in `value`, which starts at `start` in the source, with the value of the
+/// variable `name`. Returns `None` if `value` has no references.
+fn substitute(
+    value: &str,
+    start: Location,
+    variables: &BTreeMap<Cow<str>, Cow<str>>,
+    out: &mut Vec<SemanticError>,
+) -> Option<String> {
+    let mut substituted = None::<String>;
+    let mut last = 0;
+    let mut chars = value.char_indices();
+
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '\\' => {
+                chars.next();
+            }
+            '$' => {
+                let name = &value[i + 1..i + 1 + name_len(&value[i + 1..])];
+                if name.is_empty() {
+                    continue;
+                }
+
+                let buf = substituted.get_or_insert_with(String::new);
+                buf.push_str(&value[last..i]);
+                last = i + 1 + name.len();
+
+                match variables.get(name) {
+                    // The value is unescaped on output, so escape it to keep it as written.
+                    Some(v) => buf.push_str(&v.replace('\\', "\\\\")),
+                    None => out.push(SemanticError {
+                        range: LocationRange {
+                            start: start + i,
+                            end: start + last,
+                        },
+                        kind: SemanticErrorKind::UndefinedVariable { name: name.into() },
+                    }),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut substituted = substituted?;
+    substituted.push_str(&value[last..]);
+    Some(substituted)
+}
+
+fn substitute_content<'cfg>(
+    content: &mut Content<'cfg>,
+    src: &str,
+    variables: &BTreeMap<Cow<str>, Cow<str>>,
+    out: &mut Vec<SemanticError>,
+) {
+    for node in &mut content.nodes {
+        let Some(element) = node.as_element_mut() else {
+            continue;
+        };
+
+        let attributes = element
+            .selectors
+            .iter_mut()
+            .flat_map(|s| &mut s.items)
+            .flat_map(|item| match item {
+                SelectorItem::Attributes { attributes, .. } => &mut attributes[..],
+                _ => &mut [],
+            });
+
+        for attribute in attributes {
+            let Some(TextSlice::FromSource { range }) = attribute.value else {
+                continue;
+            };
+            // Quoted values are left as written.
+            let start = range.start.position;
+            if start > 0 && matches!(src.as_bytes()[start - 1], b'"' | b'\'') {
+                continue;
+            }
+
+            if let Some(value) = substitute(range.slice(src), range.start, variables, out) {
+                attribute.value = Some(value.into());
+            }
+        }
+
+        substitute_content(&mut element.content, src, variables, out);
+    }
+}
+
+/// Replaces each `$name` in the unquoted attribute values of `content` with the value of the
+/// variable `name`, reporting any variable that isn't defined.
+pub fn substitute_variables(
+    content: &mut Content,
+    src: &str,
+    variables: &BTreeMap<Cow<str>, Cow<str>>,
+    errors: &mut Errors,
+) -> InternalResult {
+    let mut undefined = Vec::new();
+    substitute_content(content, src, variables, &mut undefined);
+    errors.semantic(undefined)
+}
@@ -0,0 +1,63 @@
+use alloc::{string::String, vec::Vec};
+use core::mem;
+
+use gramma::parse::LocationRange;
+
+use crate::{
+    document::{Content, Node, NodeType, Space, TextLike},
+    output::is_configured_void,
+    utils::to_lowercase,
+    OutputConfig,
+};
+
+/// Moves comments out of void elements in `content` to directly after the element,
+/// since void elements can't contain anything. Each moved comment is preceded by a line end, so
+/// it's separated from the element like any other sibling.
+pub fn relocate_void_comments(content: &mut Content, src: &str, config: &OutputConfig) {
+    let mut string_buf = String::new();
+    let nodes = mem::take(&mut content.nodes);
+    content.nodes.reserve(nodes.len());
+
+    for mut node in nodes {
+        let mut relocated = Vec::new();
+
+        if let Some(element) = node.as_element_mut() {
+            relocate_void_comments(&mut element.content, src, config);
+
+            let is_void = element
+                .selectors
+                .iter()
+                .rev()
+                .find_map(|s| s.tag.name())
+                .is_some_and(|tag| {
+                    is_configured_void(to_lowercase(tag.as_str(src), &mut string_buf), config)
+                });
+
+            if is_void {
+                let (comments, rest) =
+                    mem::take(&mut element.content.nodes)
+                        .into_iter()
+                        .partition(|n| {
+                            matches!(
+                                n.node_type,
+                                NodeType::TextLike {
+                                    text_like: TextLike::Comment { .. }
+                                }
+                            )
+                        });
+                element.content.nodes = rest;
+                relocated = comments;
+            }
+        }
+
+        let end = node.range.end;
+        content.nodes.push(node);
+        for comment in relocated {
+            content.nodes.push(Node {
+                range: LocationRange { start: end, end },
+                node_type: Space::LineEnd {}.into(),
+            });
+            content.nodes.push(comment);
+        }
+    }
+}
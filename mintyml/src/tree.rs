@@ -0,0 +1,734 @@
+//! A source-mapped view of a parsed MinTyML document, for tools that need the structure
+//! of a document rather than its HTML output.
+//!
+//! With the `serde` feature enabled, every type in this module implements `Serialize`.
+
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{borrow::Borrow, ops};
+
+use derive_more::Display;
+use gramma::parse::LocationRange;
+
+use crate::{
+    document::{self, ElementDelimiter, ElementType, MultilineKind, NodeType, SpecialKind},
+    error::Errors,
+    ConvertError, OutputConfig,
+};
+
+/// Parses `src` into a [`Document`] without applying any inference or transformations.
+///
+/// # Example
+///
+/// ```
+/// use mintyml::tree::Node;
+///
+/// let document = mintyml::tree::parse("div> Hello").unwrap();
+/// let Node::Element { range, selectors, .. } = &document.nodes[0] else {
+///     panic!()
+/// };
+///
+/// assert_eq!((range.start, range.end), (0, 10));
+/// assert_eq!(selectors[0].tag.as_deref(), Some("div"));
+/// ```
+pub fn parse(src: &str) -> Result<Document<'_>, ConvertError<'_>> {
+    let config = OutputConfig::new();
+    let mut errors = Errors::new(&config);
+
+    let document = document::Document::parse(src, &config, &mut errors).ok();
+    errors.to_convert_error(src)?;
+    let document = document.ok_or(ConvertError::Unknown)?;
+
+    Ok(Document {
+        range: document.range.into(),
+        nodes: nodes(src, &document.content),
+    })
+}
+
+/// Parses and transforms `src` as [`convert`](crate::convert) would, and returns the resulting
+/// [`Document`], with the tags chosen by inference filled in.
+///
+/// # Example
+///
+/// ```
+/// use mintyml::OutputConfig;
+///
+/// let document = mintyml::tree::transform("ul {\n  > One\n}", OutputConfig::new()).unwrap();
+/// let list = &document.nodes[0];
+///
+/// assert_eq!(list.resolved_tag(), Some("ul"));
+/// assert_eq!(list.content()[0].resolved_tag(), Some("li"));
+/// ```
+pub fn transform<'src>(
+    src: &'src str,
+    config: impl Borrow<OutputConfig<'src>>,
+) -> Result<Document<'src>, ConvertError<'src>> {
+    let config = config.borrow();
+    let mut errors = Errors::new(config);
+
+    let document = crate::parse_and_transform(src, config, &mut errors).ok();
+    errors.to_convert_error(src)?;
+    let document = document.ok_or(ConvertError::Unknown)?;
+
+    Ok(Document {
+        range: document.range.into(),
+        nodes: nodes(src, &document.content),
+    })
+}
+
+/// Parses and transforms `src` as [`convert`](crate::convert) would, and renders only the element
+/// at `range` and its content, as if it were the only node in the document.
+///
+/// `range` is the [`Node::range`] of an element in the [`Document`] returned by [`transform`] for
+/// the same `src` and `config`. Returns `Ok(None)` if there is no element at `range`.
+///
+/// # Example
+///
+/// ```
+/// use mintyml::OutputConfig;
+///
+/// let src = "h1> Title\n\nsection {\n  p> Hello, <#world#>!\n}";
+/// let config = OutputConfig::new();
+///
+/// let document = mintyml::tree::transform(src, &config).unwrap();
+/// let section = document.nodes.iter().find(|n| n.resolved_tag() == Some("section")).unwrap();
+///
+/// let html = mintyml::tree::render_element(src, &config, section.range()).unwrap();
+/// assert_eq!(
+///     html.as_deref(),
+///     Some("<section><p>Hello, <strong>world</strong>!</p></section>"),
+/// );
+/// ```
+pub fn render_element<'src>(
+    src: &'src str,
+    config: impl Borrow<OutputConfig<'src>>,
+    range: Range,
+) -> Result<Option<String>, ConvertError<'src>> {
+    fn find<'a, 'src>(
+        content: &'a document::Content<'src>,
+        range: Range,
+    ) -> Option<&'a document::Element<'src>> {
+        content.nodes.iter().find_map(|n| {
+            let element = n.as_element()?;
+            match Range::from(n.range) == range {
+                true => Some(element),
+                false => find(&element.content, range),
+            }
+        })
+    }
+
+    let config = config.borrow();
+    let mut errors = Errors::new(config);
+
+    let document = crate::parse_and_transform(src, config, &mut errors).ok();
+    errors.to_convert_error(src)?;
+    let document = document.ok_or(ConvertError::Unknown)?;
+
+    find(&document.content, range)
+        .map(|element| crate::output::render_element(src, element, config))
+        .transpose()
+        .map_err(Into::into)
+}
+
+/// Parses and transforms `src` as [`convert`](crate::convert) would, and returns the tag inference
+/// chose for each element written without one, in source order.
+///
+/// # Example
+///
+/// ```
+/// use mintyml::OutputConfig;
+///
+/// let tags = mintyml::tree::inferred_tags("ul {\n  > One\n}\nTwo", OutputConfig::new()).unwrap();
+/// let tags = Vec::from_iter(tags.iter().map(|t| (t.range.start, t.range.end, &*t.tag)));
+///
+/// assert_eq!(tags, [(7, 12, "li"), (15, 18, "p")]);
+/// ```
+pub fn inferred_tags<'src>(
+    src: &'src str,
+    config: impl Borrow<OutputConfig<'src>>,
+) -> Result<Vec<InferredTag<'src>>, ConvertError<'src>> {
+    let config = config.borrow();
+    let mut errors = Errors::new(config);
+
+    let document = crate::parse_and_transform(src, config, &mut errors).ok();
+    errors.to_convert_error(src)?;
+    let document = document.ok_or(ConvertError::Unknown)?;
+
+    let mut out = Vec::new();
+    inferred_tags_in(src, &document.content, &mut out);
+    Ok(out)
+}
+
+/// A tag assigned to an element by inference.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct InferredTag<'src> {
+    /// The range of the element the tag was inferred for.
+    pub range: Range,
+    pub tag: Cow<'src, str>,
+}
+
+fn inferred_tags_in<'src>(
+    src: &'src str,
+    content: &document::Content<'src>,
+    out: &mut Vec<InferredTag<'src>>,
+) {
+    for element in content.nodes.iter().filter_map(|n| n.as_element()) {
+        for selector in element.selectors.iter().filter(|s| s.inferred) {
+            if let document::Tag::Explicit { value } = &selector.tag {
+                out.push(InferredTag {
+                    range: element.range.into(),
+                    tag: text(src, value),
+                });
+            }
+        }
+        inferred_tags_in(src, &element.content, out);
+    }
+}
+
+/// A span of the source, given as byte offsets.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Range {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl From<LocationRange> for Range {
+    fn from(range: LocationRange) -> Self {
+        Self {
+            start: range.start.position,
+            end: range.end.position,
+        }
+    }
+}
+
+/// The root of a parsed document.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Document<'src> {
+    pub range: Range,
+    pub nodes: Vec<Node<'src>>,
+}
+
+/// Returned by [`Document::apply_edit`] when an edit can't be applied without parsing the
+/// document again.
+#[non_exhaustive]
+#[derive(Debug, Display, Clone, PartialEq, Eq)]
+#[display(fmt = "the edit may change the structure of the document")]
+#[cfg_attr(feature = "error-trait", derive(derive_more::Error))]
+pub struct ReparseNeeded {}
+
+/// Characters that could change the structure of a document if added to or removed from text.
+fn is_structural(ch: char) -> bool {
+    matches!(
+        ch,
+        '<' | '>' | '{' | '}' | '[' | ']' | '"' | '\'' | '`' | '\\' | '\n' | '\r'
+    )
+}
+
+/// An edit that replaced the bytes in `removed` with `inserted_len` new bytes.
+struct Edit {
+    removed: ops::Range<usize>,
+    inserted_len: usize,
+    /// Whether positions at the end of `removed` stay in place instead of moving past the new
+    /// bytes, as when text is inserted at the very start of a text node.
+    keep_boundary: bool,
+}
+
+impl Range {
+    /// Moves this range to account for `edit`.
+    fn shift(&mut self, edit: &Edit) {
+        let Edit {
+            ref removed,
+            inserted_len,
+            keep_boundary,
+        } = *edit;
+        let shift = |pos: &mut usize| {
+            if *pos > removed.end || *pos == removed.end && !keep_boundary {
+                *pos = *pos - removed.end + removed.start + inserted_len;
+            }
+        };
+        shift(&mut self.start);
+        shift(&mut self.end);
+    }
+}
+
+impl<'src> Document<'src> {
+    /// Updates the document for an edit that replaced the bytes in `range` with `new_text`,
+    /// without parsing it again. `src_after` is the full source after the edit.
+    ///
+    /// This only succeeds if the edit is contained within a single text node and can't have
+    /// changed the structure of the document. Otherwise, this returns [`ReparseNeeded`] and
+    /// leaves the document unchanged, and the caller should call [`parse`] on `src_after`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let before = "div> Hello there";
+    /// let after = "div> Hello over there";
+    /// let mut document = mintyml::tree::parse(before).unwrap();
+    ///
+    /// document.apply_edit(11..11, "over ", after).unwrap();
+    /// assert!(document.apply_edit(0..0, "{", "{div> Hello over there").is_err());
+    /// ```
+    pub fn apply_edit(
+        &mut self,
+        range: ops::Range<usize>,
+        new_text: &str,
+        src_after: &str,
+    ) -> Result<(), ReparseNeeded> {
+        let src_len = self.range.end;
+        let valid = range.start <= range.end
+            && range.end <= src_len
+            && src_len - range.len() + new_text.len() == src_after.len()
+            && src_after.get(range.start..range.start + new_text.len()) == Some(new_text);
+
+        if !valid || new_text.contains(is_structural) {
+            return Err(ReparseNeeded {});
+        }
+
+        let Some(Node::Text {
+            range: text_range,
+            text,
+            ..
+        }) = find_text(&mut self.nodes, &range)
+        else {
+            return Err(ReparseNeeded {});
+        };
+
+        let removed = text.get(range.start - text_range.start..range.end - text_range.start);
+        match removed {
+            Some(removed) if !removed.contains(is_structural) => {}
+            _ => return Err(ReparseNeeded {}),
+        }
+
+        let edit = Edit {
+            keep_boundary: range.is_empty() && range.start == text_range.start,
+            removed: range,
+            inserted_len: new_text.len(),
+        };
+        let new_range = {
+            let mut r = *text_range;
+            r.shift(&edit);
+            r
+        };
+        let Some(new_value) = src_after.get(new_range.start..new_range.end) else {
+            return Err(ReparseNeeded {});
+        };
+
+        if new_value.is_empty()
+            || new_value.starts_with(char::is_whitespace)
+            || new_value.ends_with(char::is_whitespace)
+        {
+            return Err(ReparseNeeded {});
+        }
+
+        *text = new_value.to_string().into();
+
+        self.range.shift(&edit);
+        for node in &mut self.nodes {
+            node.shift(&edit);
+        }
+
+        Ok(())
+    }
+}
+
+/// Finds the text node that contains all of `range`, if any.
+fn find_text<'a, 'src>(
+    nodes: &'a mut [Node<'src>],
+    range: &ops::Range<usize>,
+) -> Option<&'a mut Node<'src>> {
+    nodes
+        .iter_mut()
+        .filter(|n| {
+            let r = n.range();
+            r.start <= range.start && range.end <= r.end
+        })
+        .find_map(|node| match node {
+            Node::Element { content, .. } => find_text(content, range),
+            Node::Text { .. } => Some(node),
+            Node::Comment { .. } | Node::Space { .. } => None,
+        })
+}
+
+impl<'src> Node<'src> {
+    /// The range of the source this node was parsed from.
+    pub fn range(&self) -> Range {
+        match *self {
+            Node::Element { range, .. }
+            | Node::Text { range, .. }
+            | Node::Comment { range, .. }
+            | Node::Space { range, .. } => range,
+        }
+    }
+
+    /// The tag of this element's first selector, or `None` if this isn't an element or the
+    /// element has no tag, as is the case for dissolved elements. For a document returned by
+    /// [`transform`], this is the tag the element is output with.
+    pub fn resolved_tag(&self) -> Option<&str> {
+        match self {
+            Node::Element { selectors, .. } => {
+                selectors.first()?.tag.as_deref().filter(|&t| t != "*")
+            }
+            _ => None,
+        }
+    }
+
+    /// The child nodes of this element, or an empty slice if this isn't an element.
+    pub fn content(&self) -> &[Node<'src>] {
+        match self {
+            Node::Element { content, .. } => content,
+            _ => &[],
+        }
+    }
+
+    fn shift(&mut self, edit: &Edit) {
+        match self {
+            Node::Element {
+                range,
+                content_range,
+                kind,
+                selectors,
+                content,
+            } => {
+                range.shift(edit);
+                content_range.shift(edit);
+
+                if let ElementKind::Standard { delimiter }
+                | ElementKind::Inline {
+                    delimiter: Some(delimiter),
+                } = kind
+                {
+                    match delimiter {
+                        Delimiter::Line { combinator } => combinator.shift(edit),
+                        Delimiter::Block { block } => block.shift(edit),
+                        Delimiter::LineBlock { combinator, block } => {
+                            combinator.shift(edit);
+                            block.shift(edit);
+                        }
+                    }
+                }
+
+                for selector in selectors {
+                    selector.range.shift(edit);
+
+                    for item in &mut selector.items {
+                        match item {
+                            SelectorItem::Id { range, .. } | SelectorItem::Class { range, .. } => {
+                                range.shift(edit)
+                            }
+                            SelectorItem::Attributes { range, attributes } => {
+                                range.shift(edit);
+                                for attribute in attributes {
+                                    attribute.range.shift(edit);
+                                }
+                            }
+                        }
+                    }
+                }
+
+                for node in content {
+                    node.shift(edit);
+                }
+            }
+            Node::Text { range, .. } | Node::Comment { range, .. } | Node::Space { range, .. } => {
+                range.shift(edit)
+            }
+        }
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "type", rename_all = "camelCase")
+)]
+pub enum Node<'src> {
+    #[non_exhaustive]
+    Element {
+        range: Range,
+        /// The range of the element's content, excluding its selectors and delimiters. This is
+        /// empty if the element has no content, as with void elements.
+        content_range: Range,
+        kind: ElementKind,
+        selectors: Vec<Selector<'src>>,
+        content: Vec<Node<'src>>,
+    },
+    /// Text, as written in the source with any escape sequences intact.
+    #[non_exhaustive]
+    Text {
+        range: Range,
+        text: Cow<'src, str>,
+        multiline: bool,
+        raw: bool,
+    },
+    /// A comment. If `raw`, it was written with `<!! ... !!>` and is output without escaping.
+    #[non_exhaustive]
+    Comment {
+        range: Range,
+        text: Cow<'src, str>,
+        raw: bool,
+    },
+    #[non_exhaustive]
+    Space { range: Range, space: SpaceKind },
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "type", rename_all = "camelCase")
+)]
+pub enum ElementKind {
+    /// A paragraph formed from lines of text.
+    Paragraph,
+    /// An element such as `div> ...` or `div { ... }`.
+    #[non_exhaustive]
+    Standard {
+        delimiter: Delimiter,
+    },
+    /// An inline element such as `<(a> ...)>`.
+    #[non_exhaustive]
+    Inline {
+        delimiter: Option<Delimiter>,
+    },
+    /// A special inline element such as `<# ... #>`.
+    #[non_exhaustive]
+    Special {
+        name: &'static str,
+    },
+    /// A multiline text block such as `""" ... """`.
+    #[non_exhaustive]
+    Multiline {
+        name: &'static str,
+    },
+    Unknown,
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "type", rename_all = "camelCase")
+)]
+pub enum Delimiter {
+    /// `>`
+    #[non_exhaustive]
+    Line { combinator: Range },
+    /// `{ ... }`
+    #[non_exhaustive]
+    Block { block: Range },
+    /// `> { ... }`
+    #[non_exhaustive]
+    LineBlock { combinator: Range, block: Range },
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum SpaceKind {
+    /// Whitespace between lines of a paragraph.
+    LineEnd,
+    /// Whitespace at the end of a paragraph.
+    ParagraphEnd,
+    /// Whitespace within a line.
+    Inline,
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Selector<'src> {
+    pub range: Range,
+    /// The element name, or `*` for a wildcard. `None` if no element name was given.
+    pub tag: Option<Cow<'src, str>>,
+    pub items: Vec<SelectorItem<'src>>,
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "type", rename_all = "camelCase")
+)]
+pub enum SelectorItem<'src> {
+    /// `#value`
+    #[non_exhaustive]
+    Id { range: Range, value: Cow<'src, str> },
+    /// `.value`
+    #[non_exhaustive]
+    Class { range: Range, value: Cow<'src, str> },
+    /// `[name=value ...]`
+    #[non_exhaustive]
+    Attributes {
+        range: Range,
+        attributes: Vec<Attribute<'src>>,
+    },
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Attribute<'src> {
+    pub range: Range,
+    pub name: Cow<'src, str>,
+    pub value: Option<Cow<'src, str>>,
+}
+
+fn text<'src>(src: &'src str, slice: &document::TextSlice<'src>) -> Cow<'src, str> {
+    match slice {
+        document::TextSlice::FromSource { range } => range.slice(src).into(),
+        document::TextSlice::Provided { value } => value.clone(),
+    }
+}
+
+fn slice_range(slice: &document::TextSlice, fallback: LocationRange) -> Range {
+    match slice {
+        document::TextSlice::FromSource { range } => (*range).into(),
+        document::TextSlice::Provided { .. } => fallback.into(),
+    }
+}
+
+fn nodes<'src>(src: &'src str, content: &document::Content<'src>) -> Vec<Node<'src>> {
+    Vec::from_iter(content.nodes.iter().map(|n| node(src, n)))
+}
+
+fn node<'src>(src: &'src str, node: &document::Node<'src>) -> Node<'src> {
+    let range = node.range.into();
+
+    match &node.node_type {
+        NodeType::Element { element } => Node::Element {
+            range,
+            content_range: element.content_span().into(),
+            kind: element_kind(&element.element_type),
+            selectors: Vec::from_iter(element.selectors.iter().map(|s| selector(src, s))),
+            content: nodes(src, &element.content),
+        },
+        NodeType::TextLike { text_like } => match text_like {
+            document::TextLike::Text { text: t } => Node::Text {
+                range,
+                text: text(src, &t.slice),
+                multiline: t.multiline,
+                raw: t.raw,
+            },
+            document::TextLike::Comment { comment } => match comment {
+                document::Comment::Tag { slice } => Node::Comment {
+                    range,
+                    text: text(src, slice),
+                    raw: false,
+                },
+                document::Comment::Raw { slice } => Node::Comment {
+                    range,
+                    text: text(src, slice),
+                    raw: true,
+                },
+            },
+            document::TextLike::Space { space } => Node::Space {
+                range,
+                space: match space {
+                    document::Space::LineEnd {} => SpaceKind::LineEnd,
+                    document::Space::ParagraphEnd {} => SpaceKind::ParagraphEnd,
+                    document::Space::Inline { .. } => SpaceKind::Inline,
+                },
+            },
+        },
+    }
+}
+
+fn delimiter(delimiter: &ElementDelimiter) -> Delimiter {
+    match *delimiter {
+        ElementDelimiter::Line { combinator } => Delimiter::Line {
+            combinator: combinator.into(),
+        },
+        ElementDelimiter::Block { block } => Delimiter::Block {
+            block: block.into(),
+        },
+        ElementDelimiter::LineBlock { combinator, block } => Delimiter::LineBlock {
+            combinator: combinator.into(),
+            block: block.into(),
+        },
+    }
+}
+
+fn element_kind(element_type: &ElementType) -> ElementKind {
+    match element_type {
+        ElementType::Paragraph {} => ElementKind::Paragraph,
+        ElementType::Standard { delimiter: d } => ElementKind::Standard {
+            delimiter: delimiter(d),
+        },
+        ElementType::Inline { delimiter: d } => ElementKind::Inline {
+            delimiter: d.as_ref().map(delimiter),
+        },
+        ElementType::Special { kind } => ElementKind::Special {
+            name: match kind {
+                SpecialKind::Emphasis => "emphasis",
+                SpecialKind::Strong => "strong",
+                SpecialKind::Underline => "underline",
+                SpecialKind::Strike => "strike",
+                SpecialKind::Quote => "quote",
+                SpecialKind::Code => "code",
+                SpecialKind::CodeBlockContainer => "codeBlockContainer",
+            },
+        },
+        ElementType::Multiline { kind } => ElementKind::Multiline {
+            name: match kind {
+                MultilineKind::Escaped {} => "escaped",
+                MultilineKind::Unescaped {} => "unescaped",
+                MultilineKind::Code { .. } => "code",
+            },
+        },
+        ElementType::Unknown {} => ElementKind::Unknown,
+    }
+}
+
+fn selector<'src>(src: &'src str, selector: &document::Selector<'src>) -> Selector<'src> {
+    Selector {
+        range: selector.range.into(),
+        tag: match &selector.tag {
+            document::Tag::Explicit { value } => Some(text(src, value)),
+            document::Tag::Wildcard { .. } => Some("*".into()),
+            document::Tag::Implicit {} => None,
+        },
+        items: Vec::from_iter(selector.items.iter().map(|item| match item {
+            document::SelectorItem::Id { hash, value } => SelectorItem::Id {
+                range: Range {
+                    start: hash.start.position,
+                    end: slice_range(value, *hash).end,
+                },
+                value: text(src, value),
+            },
+            document::SelectorItem::Class { dot, value } => SelectorItem::Class {
+                range: Range {
+                    start: dot.start.position,
+                    end: slice_range(value, *dot).end,
+                },
+                value: text(src, value),
+            },
+            document::SelectorItem::Attributes { range, attributes } => SelectorItem::Attributes {
+                range: (*range).into(),
+                attributes: Vec::from_iter(attributes.iter().map(|a| Attribute {
+                    range: a.range.into(),
+                    name: text(src, &a.name),
+                    value: a.value.as_ref().map(|v| text(src, v)),
+                })),
+            },
+        })),
+    }
+}
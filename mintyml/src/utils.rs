@@ -43,33 +43,6 @@ impl<'src> StrCursor<'src> {
         slice
     }
 
-    pub fn advance_by(&mut self, num: usize) -> Result<&'src str, &'src str> {
-        match self
-            .src
-            .get(self.position..)
-            .unwrap_or_default()
-            .char_indices()
-            .take(num)
-            .enumerate()
-            .last()
-        {
-            Some((n, (i, ch))) => {
-                let len = i + ch.len_utf8();
-                let slice = self
-                    .src
-                    .get(self.position..self.position + len)
-                    .unwrap_or("");
-                self.position += len;
-                if n == num - 1 {
-                    Ok(slice)
-                } else {
-                    Err(slice)
-                }
-            }
-            None => Ok(""),
-        }
-    }
-
     pub fn advance_to_char(&mut self, p: char) -> Result<&'src str, &'src str> {
         let post = self.post();
         match post.find(p) {
@@ -199,3 +172,14 @@ pub fn to_lowercase<'lt>(src: &'lt str, buf: &'lt mut String) -> &'lt str {
     buf.make_ascii_lowercase();
     buf
 }
+
+/// Adapts an [`io::Write`](std::io::Write) sink so it can be used as a [`fmt::Write`].
+#[cfg(feature = "std")]
+pub struct IoWriter<W>(pub W);
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> fmt::Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write_all(s.as_bytes()).map_err(|_| fmt::Error)
+    }
+}
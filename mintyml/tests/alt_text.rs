@@ -0,0 +1,48 @@
+use mintyml::{
+    error::{Warning, WarningKind},
+    OutputConfig,
+};
+
+mod utils;
+
+fn convert_warnings(src: &str) -> Vec<Warning> {
+    let (out, warnings) =
+        mintyml::convert_forgiving_with_warnings(src, OutputConfig::new().warn_missing_alt(true));
+    out.unwrap();
+    warnings
+}
+
+#[test]
+fn img_without_alt_warns() {
+    let warnings = convert_warnings(
+        r#"
+        section {
+            img[src="a.png"]>
+        }
+        "#,
+    );
+
+    match_set!(
+        &warnings,
+        [
+            |Warning {
+                 kind: WarningKind::MissingAltText { .. },
+                 ..
+             }| true
+        ],
+    );
+}
+
+#[test]
+fn img_with_alt_does_not_warn() {
+    let warnings = convert_warnings(r#"img[src="a.png" alt="x"]>"#);
+
+    assert_eq!(warnings, []);
+}
+
+#[test]
+fn img_with_empty_alt_does_not_warn() {
+    let warnings = convert_warnings(r#"img[src="a.png" alt=""]>"#);
+
+    assert_eq!(warnings, []);
+}
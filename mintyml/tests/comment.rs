@@ -126,4 +126,85 @@ fn comment_separates_closing_dash() {
     let out = convert_unwrap(src, None);
 
     assert_eq!(out, "<p>A <!-- B C - --> F</p>")
-}
\ No newline at end of file
+}
+
+#[test]
+fn relocate_void_comments() {
+    let src = r#"
+        section {
+            img { <! note !> }
+        }
+    "#;
+
+    let out = convert_unwrap(
+        src,
+        OutputConfig::new()
+            .indent("  ")
+            .relocate_void_comments(true),
+    );
+
+    assert_eq!(out, "<section>\n  <img>\n  <!-- note -->\n</section>\n");
+
+    let out = convert_unwrap(src, OutputConfig::new().relocate_void_comments(true));
+
+    assert_eq!(out, "<section><img> <!-- note --></section>");
+
+    // Without relocation, the comment is written as though it were the image's content.
+    let out = convert_unwrap(src, OutputConfig::new().indent("  "));
+
+    assert_eq!(out, "<section>\n  <img>\n    <!-- note -->\n</section>\n");
+}
+
+#[test]
+fn strip_comments() {
+    let config = || OutputConfig::new().emit_comments(false);
+
+    let src = r#"
+    foo
+
+    <!bar!>
+
+    baz
+    "#;
+    assert_eq!(convert_unwrap(src, config()), "<p>foo</p> <p>baz</p>");
+
+    let src = r#"
+    foo
+    <!bar!>
+    baz
+    "#;
+    assert_eq!(convert_unwrap(src, config()), "<p>foo baz</p>");
+
+    let src = "div {\n    <!only!>\n}\nul {\n    > a\n    <!x!>\n    > b\n}";
+    assert_eq!(
+        convert_unwrap(src, config().indent("  ")),
+        "<div></div>\n<ul>\n  <li>a</li>\n  <li>b</li>\n</ul>\n",
+    );
+}
+
+#[test]
+fn raw_comment() {
+    let src = r#"
+    <!![if IE]><p>Please upgrade your browser.</p><![endif]!!>
+    p> Hello <!!-- raw -- !!> world
+    "#;
+    let out = convert_unwrap(src, None);
+
+    assert_eq!(
+        out,
+        concat!(
+            "<!--[if IE]><p>Please upgrade your browser.</p><![endif]-->",
+            "<p>Hello <!---- raw -- --> world</p>",
+        )
+    );
+
+    // Ordinary comments are still sanitized.
+    assert_eq!(
+        convert_unwrap("<!-- a -- b !>", None),
+        "<!--- - a - - b -->"
+    );
+    assert_eq!(
+        convert_unwrap("<!![if IE]>!!>", OutputConfig::new().emit_comments(false)),
+        "",
+    );
+}
@@ -1,5 +1,8 @@
 mod utils;
-use mintyml::OutputConfig;
+use mintyml::{
+    ConvertError, Direction, DropEmptyPolicy, LineEnding, MetadataConfig, OutputConfig,
+    SpacesPolicy, SpecialKind, TagCase,
+};
 
 use crate::utils::convert_unwrap;
 
@@ -79,6 +82,34 @@ fn special_tags() {
     )
 }
 
+#[test]
+fn disabled_special_tags() {
+    let src = "section {\n    Abc </def <#ghi#>/> <`jkl`>\n\n    ```\n    mno\n    ```\n}";
+
+    let out = convert_unwrap(
+        src,
+        OutputConfig::new()
+            .disable_special(SpecialKind::Emphasis)
+            .disable_special(SpecialKind::CodeBlockContainer),
+    );
+    assert_eq!(
+        out,
+        concat!(
+            "<section><p>Abc &lt;/def <strong>ghi</strong>/&gt; <code>jkl</code></p>",
+            " <code>mno</code></section>",
+        ),
+    );
+
+    let out = convert_unwrap(src, OutputConfig::new().emphasis_tag("i"));
+    assert_eq!(
+        out,
+        concat!(
+            "<section><p>Abc <i>def <strong>ghi</strong></i> <code>jkl</code></p>",
+            " <pre><code>mno</code></pre></section>",
+        ),
+    );
+}
+
 #[test]
 fn paragraph_infer_inline() {
     let src = r#"
@@ -446,3 +477,871 @@ fn small_document_with_list() {
         )
     )
 }
+
+#[test]
+fn inline_lang_shorthand() {
+    let src = r#"
+        section {
+            She said <([mty-lang=fr]> bonjour )>.
+        }
+    "#;
+
+    let out = convert_unwrap(src, None);
+
+    assert_eq!(
+        out,
+        concat!(
+            r#"<section>"#,
+            r#"<p>She said <span lang="fr">bonjour</span>.</p>"#,
+            r#"</section>"#,
+        )
+    );
+
+    let out = convert_unwrap("p[MTY-Lang=fr]> bonjour", None);
+    assert_eq!(out, r#"<p lang="fr">bonjour</p>"#);
+
+    let out = convert_unwrap("p[lang=en][mty-lang=fr][mty-lang=de]> hello", None);
+    assert_eq!(out, r#"<p lang="en">hello</p>"#);
+
+    let out = convert_unwrap("p[mty-lang=fr][mty-lang=de]> bonjour", None);
+    assert_eq!(out, r#"<p lang="fr">bonjour</p>"#);
+}
+
+#[test]
+fn aria_label_shorthand() {
+    let src = r#"
+        nav[mty-aria-label="Tom & Jerry's \"links\""] {
+            a[href=/]> Home
+        }
+    "#;
+
+    let out = convert_unwrap(src, None);
+
+    assert_eq!(
+        out,
+        r#"<nav aria-label="Tom &amp; Jerry's &quot;links&quot;"><a href="/">Home</a></nav>"#
+    );
+
+    let out = convert_unwrap("nav[Mty-Aria-Label=Links] { }", None);
+    assert_eq!(out, r#"<nav aria-label="Links"></nav>"#);
+
+    let out = convert_unwrap("nav[ARIA-LABEL=Main][mty-aria-label=Links] { }", None);
+    assert_eq!(out, r#"<nav ARIA-LABEL="Main"></nav>"#);
+}
+
+#[test]
+fn explicit_rtl_dir() {
+    let out = convert_unwrap("section> Hello", OutputConfig::new().dir(Direction::Rtl));
+
+    assert_eq!(out, r#"<section dir="rtl">Hello</section>"#)
+}
+
+#[test]
+fn auto_dir_from_lang() {
+    let src = "section> مرحبا";
+
+    let out = convert_unwrap(src, OutputConfig::new().lang("ar").dir(Direction::Auto));
+    assert_eq!(out, r#"<section lang="ar" dir="rtl">مرحبا</section>"#);
+
+    let out = convert_unwrap(src, OutputConfig::new().lang("en-US").dir(Direction::Auto));
+    assert_eq!(out, r#"<section lang="en-US">مرحبا</section>"#);
+}
+
+#[test]
+fn custom_void_element() {
+    let src = r#"
+        section {
+            My-Icon[name=star]>
+            p> Favorite
+        }
+    "#;
+
+    let out = convert_unwrap(src, OutputConfig::new().void_elements(["my-icon"]));
+
+    assert_eq!(
+        out,
+        r#"<section><My-Icon name="star"> <p>Favorite</p></section>"#
+    );
+}
+
+#[test]
+fn self_close_void_html() {
+    let src = r#"
+        section {
+            img[src=x]>
+            div>
+        }
+    "#;
+
+    let out = convert_unwrap(src, OutputConfig::new().self_close_void(true));
+    assert_eq!(out, r#"<section><img src="x"/> <div></div></section>"#);
+
+    let out = convert_unwrap(src, OutputConfig::new().self_close_void(true).xml(true));
+    assert_eq!(out, r#"<section><img src="x"/> <div/></section>"#);
+}
+
+#[test]
+fn tag_case_policy() {
+    let out = convert_unwrap("DIV> hi", OutputConfig::new().tag_case(TagCase::Lower));
+    assert_eq!(out, "<div>hi</div>");
+
+    let out = convert_unwrap("DIV> hi", OutputConfig::new().tag_case(TagCase::Preserve));
+    assert_eq!(out, "<DIV>hi</DIV>");
+}
+
+#[test]
+fn xml_canonical_attrs() {
+    let src = r#"a[xlink:title=t z=1 xmlns:xlink="http://www.w3.org/1999/xlink" b xml:lang=en xlink:href=h].c#i> x"#;
+
+    let out = convert_unwrap(src, OutputConfig::new().xml(true).xml_canonical_attrs(true));
+    assert_eq!(
+        out,
+        concat!(
+            r#"<a xmlns:xlink="http://www.w3.org/1999/xlink" b="" class="c" id="i" z="1" "#,
+            r#"xlink:href="h" xlink:title="t" xml:lang="en">x</a>"#,
+        )
+    );
+
+    let out = convert_unwrap(src, OutputConfig::new().xml_canonical_attrs(true));
+    assert!(out.starts_with(r#"<a id="i" class="c" xlink:title="t""#));
+}
+
+#[test]
+fn responsive_image_resolver() {
+    let src = r#"
+        section {
+            img[src=hero.jpg alt=Hero]>
+            img[src=other.jpg]>
+            picture {
+                img[src=hero.jpg]>
+            }
+        }
+    "#;
+
+    let config = OutputConfig::new().responsive_image_resolver(|src| {
+        (src == "hero.jpg").then(|| {
+            vec![
+                ("(min-width: 800px)".into(), "hero-large.jpg".into()),
+                ("".into(), "hero-small.jpg".into()),
+            ]
+        })
+    });
+
+    let out = convert_unwrap(src, config);
+    assert_eq!(
+        out,
+        concat!(
+            r#"<section><picture>"#,
+            r#"<source media="(min-width: 800px)" srcset="hero-large.jpg">"#,
+            r#"<source srcset="hero-small.jpg">"#,
+            r#"<img src="hero.jpg" alt="Hero">"#,
+            r#"</picture> <img src="other.jpg"> "#,
+            r#"<picture><img src="hero.jpg"></picture></section>"#,
+        )
+    );
+}
+
+#[test]
+fn omit_optional_end_tags_li() {
+    let src = r#"
+        ul {
+            > one
+            > two
+            > three
+        }
+        ol {
+            > <(a> four)>
+        }
+    "#;
+
+    let config = OutputConfig::new().omit_optional_end_tags(true);
+
+    assert_eq!(
+        convert_unwrap(src, config.clone()),
+        r#"<ul><li>one <li>two <li>three</ul> <ol><li><a>four</a></ol>"#,
+    );
+
+    assert_eq!(
+        convert_unwrap(src, config.xml(true)),
+        concat!(
+            r#"<ul><li>one</li> <li>two</li> <li>three</li></ul> "#,
+            r#"<ol><li><a>four</a></li></ol>"#,
+        ),
+    );
+}
+
+#[test]
+fn omit_optional_end_tags_p() {
+    let src = r#"
+        section {
+            First
+
+            Second
+        }
+
+        a {
+            Third
+        }
+
+        div {
+            Fourth
+
+            <! note !>
+        }
+
+        Fifth
+    "#;
+
+    let out = convert_unwrap(src, OutputConfig::new().omit_optional_end_tags(true));
+
+    assert_eq!(
+        out,
+        concat!(
+            r#"<section><p>First <p>Second</section> "#,
+            r#"<a><p>Third</p></a> "#,
+            r#"<div><p>Fourth</p> <!-- note --></div> "#,
+            r#"<p>Fifth</p>"#,
+        ),
+    );
+}
+
+#[test]
+fn omit_optional_end_tags_rewritten() {
+    let config = OutputConfig::new()
+        .omit_optional_end_tags(true)
+        .tag_rewrite(|tag| match tag {
+            "div" => Some("x-box".into()),
+            "section" => Some("a".into()),
+            _ => None,
+        });
+
+    // The following sibling and the parent are checked by the tags they're written with.
+    assert_eq!(
+        convert_unwrap("p> A\n\ndiv> B\n\nsection {\n    p> C\n}", config),
+        "<p>A</p> <x-box>B</x-box> <a><p>C</p></a>",
+    );
+}
+
+#[test]
+fn collapse_text_whitespace() {
+    let src = "section {\n    Hello  \t  there,   <#big#>    world!\n\n    pre> a    b\n}";
+
+    let out = convert_unwrap(src, OutputConfig::new().collapse_text_whitespace(true));
+    assert_eq!(
+        out,
+        "<section><p>Hello there, <strong>big</strong> world!</p> <pre>a    b</pre></section>"
+    );
+
+    let out = convert_unwrap(src, OutputConfig::new());
+    assert!(out.contains("there,   <strong>"), "{out}");
+}
+
+#[test]
+fn normalize_boolean_values() {
+    let src = r#"div[contenteditable=TRUE spellcheck="False" aria-hidden=True title=TRUE]> Hi"#;
+
+    let out = convert_unwrap(src, OutputConfig::new().normalize_boolean_values(true));
+    assert_eq!(
+        out,
+        r#"<div contenteditable="true" spellcheck="false" aria-hidden="true" title="TRUE">Hi</div>"#
+    );
+
+    let out = convert_unwrap(src, OutputConfig::new());
+    assert!(out.contains(r#"contenteditable="TRUE""#));
+}
+
+#[test]
+fn convert_with_stats() {
+    let src = r#"
+    section {
+        Some </emphasized/> and <#strong#> text,
+        and </more emphasis/>.
+
+        p> Explicit
+    }
+    "#;
+
+    let (out, stats) = mintyml::convert_with_stats(src, OutputConfig::new()).unwrap();
+
+    assert_eq!(
+        out,
+        concat!(
+            "<section><p>Some <em>emphasized</em> and <strong>strong</strong> text, ",
+            "and <em>more emphasis</em>.</p> <p>Explicit</p></section>",
+        ),
+    );
+    assert_eq!(stats.special_tags.get(&SpecialKind::Emphasis), Some(&2));
+    assert_eq!(stats.special_tags.get(&SpecialKind::Strong), Some(&1));
+    assert_eq!(stats.special_tags.get(&SpecialKind::Code), None);
+    assert_eq!(stats.elements, 6);
+    assert_eq!(stats.inferred_tags, 1);
+    assert_eq!(stats.explicit_tags, 5);
+}
+
+#[test]
+fn table_wrapper() {
+    let src = "section {\n    p> Before\n\n    table {\n        > > Cell\n    }\n}";
+
+    let out = convert_unwrap(src, OutputConfig::new().table_wrapper("div.table-wrap"));
+    assert_eq!(
+        out,
+        concat!(
+            r#"<section><p>Before</p> <div class="table-wrap">"#,
+            "<table><tr><td>Cell</td></tr></table></div></section>",
+        ),
+    );
+
+    let out = convert_unwrap(
+        "section>table> > > table> > > Inner",
+        OutputConfig::new().table_wrapper(".scroll"),
+    );
+    assert_eq!(
+        out.matches(r#"<div class="scroll"><table>"#).count(),
+        2,
+        "{out}"
+    );
+}
+
+#[test]
+fn adjacent_inline_special_space() {
+    const JOINED: &str = "<p><strong>a</strong><em>b</em></p>";
+    const SPACED: &str = "<p><strong>a</strong> <em>b</em></p>";
+
+    let cases = [
+        (SpacesPolicy::Preserve, JOINED, SPACED),
+        (SpacesPolicy::Always, SPACED, SPACED),
+        (SpacesPolicy::Never, JOINED, JOINED),
+    ];
+
+    for (policy, adjacent, spaced) in cases {
+        let config = OutputConfig::new().adjacent_inline_special_space(policy);
+        let out = convert_unwrap("<#a#></b/>", config.clone());
+        assert_eq!(out, adjacent, "{policy:?}");
+        let out = convert_unwrap("<#a#> </b/>", config);
+        assert_eq!(out, spaced, "{policy:?}");
+    }
+
+    let cases = [
+        (SpacesPolicy::Preserve, "<p><b>a</b><i>b</i></p>"),
+        (SpacesPolicy::Always, "<p><b>a</b> <i>b</i></p>"),
+        (SpacesPolicy::Never, "<p><b>a</b><i>b</i></p>"),
+    ];
+    for (policy, expected) in cases {
+        let config = OutputConfig::new().adjacent_inline_special_space(policy);
+        assert_eq!(
+            convert_unwrap("<(b>a)><(i>b)>", config),
+            expected,
+            "{policy:?}"
+        );
+    }
+    let out = convert_unwrap(
+        "<(b>a)> <(i>b)>",
+        OutputConfig::new().adjacent_inline_special_space(SpacesPolicy::Never),
+    );
+    assert_eq!(out, "<p><b>a</b><i>b</i></p>");
+
+    let out = convert_unwrap(
+        "x <#a#> y </b/>",
+        OutputConfig::new().adjacent_inline_special_space(SpacesPolicy::Never),
+    );
+    assert_eq!(out, "<p>x <strong>a</strong> y <em>b</em></p>");
+}
+
+#[test]
+fn convert_normalized() {
+    let a = r#"
+section[title="Intro" id=main] {
+    Hello,    <#world#>!
+
+    a[rel=next href="/b"]>   Next
+}
+"#;
+    let b = "section#main[title=Intro] { > Hello, <#world#>!\n a[href=/b rel=next]> Next }";
+
+    let a = mintyml::convert_normalized(a, OutputConfig::new()).unwrap();
+    let b = mintyml::convert_normalized(b, OutputConfig::new().indent("\t")).unwrap();
+
+    assert_eq!(a, b);
+    assert_eq!(
+        a,
+        concat!(
+            "<section id=\"main\" title=\"Intro\">\n",
+            "  <p>Hello, <strong>world</strong>!</p>\n",
+            "  <a href=\"/b\" rel=\"next\">Next</a>\n",
+            "</section>\n",
+        ),
+    );
+}
+
+#[test]
+fn sort_siblings_by_id() {
+    let src = r#"
+        nav[mty-sort] {
+            a#b[href="/b"]> B
+            a[href="/none"]> None
+            a#a[href="/a"]> A
+        }
+    "#;
+
+    let out = convert_unwrap(src, OutputConfig::new().sort_siblings_by_id(true));
+    assert_eq!(
+        out,
+        concat!(
+            r#"<nav><a id="a" href="/a">A</a> <a href="/none">None</a>"#,
+            r#" <a id="b" href="/b">B</a></nav>"#,
+        ),
+    );
+
+    let out = convert_unwrap(src, None);
+    assert!(out.starts_with(r#"<nav><a id="b""#), "{out}");
+}
+
+#[test]
+fn element_visitor() {
+    let src = r#"
+        ul {
+            > First
+            > Second
+            li[data-index=custom]> Third
+        }
+    "#;
+
+    let config = OutputConfig::new().element_visitor(|element, attributes| {
+        if element.tag() == Some("li") && element.parent_tag() == Some("ul") {
+            attributes.push(("data-index".into(), element.index().to_string()));
+        }
+    });
+
+    assert_eq!(
+        convert_unwrap(src, config),
+        concat!(
+            r#"<ul><li data-index="0">First</li>"#,
+            r#" <li data-index="1">Second</li>"#,
+            r#" <li data-index="custom">Third</li></ul>"#,
+        ),
+    );
+
+    let config = OutputConfig::new().element_visitor(|_, attributes| {
+        attributes.push(("data-a".into(), "1".into()));
+        attributes.push(("DATA-A".into(), "2".into()));
+        attributes.push(("data-b".into(), "3".into()));
+        attributes.push(("ID".into(), "other".into()));
+    });
+
+    assert_eq!(
+        convert_unwrap("p#main[Data-B=x]> Hi", config),
+        r#"<p id="main" Data-B="x" data-a="1">Hi</p>"#,
+    );
+}
+
+#[test]
+fn autolink() {
+    let config = || OutputConfig::new().autolink(true);
+
+    assert_eq!(
+        convert_unwrap(
+            "p> Docs are at https://example.com/a?b=1&c=2, or ask.",
+            config()
+        ),
+        concat!(
+            r#"<p>Docs are at <a href="https://example.com/a?b=1&amp;c=2">"#,
+            r#"https://example.com/a?b=1&amp;c=2</a>, or ask.</p>"#,
+        ),
+    );
+
+    assert_eq!(
+        convert_unwrap(
+            r#"p> a[href="http://example.com"]> http://example.com"#,
+            config(),
+        ),
+        r#"<p><a href="http://example.com">http://example.com</a></p>"#,
+    );
+
+    assert_eq!(
+        convert_unwrap("p> No link: <`https://example.com`>", config()),
+        "<p>No link: <code>https://example.com</code></p>",
+    );
+}
+
+#[test]
+fn tag_rewrite() {
+    let config = OutputConfig::new().tag_rewrite(|tag| match tag {
+        "span" => Some("x-span".into()),
+        "line-break" => Some("br".into()),
+        _ => None,
+    });
+
+    assert_eq!(
+        convert_unwrap("p> <(span> hi )> <(line-break>)> <(b> there )>", config),
+        "<p><x-span>hi</x-span> <br> <b>there</b></p>",
+    );
+}
+
+#[test]
+fn tag_rewrite_called_once_per_tag() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    let config = OutputConfig::new().tag_rewrite(|_| {
+        CALLS.fetch_add(1, Ordering::Relaxed);
+        None
+    });
+
+    let src = "ul {\n    li> One\n\n    li> Two\n}\n\nsection>p> Three";
+    mintyml::convert(src, config).unwrap();
+    assert_eq!(CALLS.load(Ordering::Relaxed), 5);
+}
+
+#[test]
+fn convert_all() {
+    let config = OutputConfig::new();
+    let results = mintyml::convert_all([
+        ("p> One", &config),
+        ("p> Two {", &config),
+        ("p> Three", &config),
+    ]);
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_deref().unwrap(), "<p>One</p>");
+    assert!(matches!(results[1], Err(ConvertError::Syntax { .. })));
+    assert_eq!(results[2].as_deref().unwrap(), "<p>Three</p>");
+}
+
+#[test]
+fn try_update() {
+    let validate = |config: &mut OutputConfig| match config.indent {
+        Some(_) if config.max_width == Some(0) => Err("max_width must be positive"),
+        _ => Ok(()),
+    };
+
+    let err = OutputConfig::new()
+        .indent("  ")
+        .max_width(0)
+        .try_update(validate)
+        .unwrap_err();
+    assert_eq!(err, "max_width must be positive");
+
+    let config = OutputConfig::new()
+        .indent("  ")
+        .try_update(validate)
+        .unwrap();
+    assert_eq!(config.indent.as_deref(), Some("  "));
+}
+
+#[test]
+fn final_newline() {
+    let src = "section {\n    Hello\n}";
+    let compact = "<section><p>Hello</p></section>";
+    let indented = "<section>\n  <p>Hello</p>\n</section>";
+
+    let cases = [
+        (None, None, compact.to_string()),
+        (None, Some(true), format!("{compact}\n")),
+        (None, Some(false), compact.to_string()),
+        (Some("  "), None, format!("{indented}\n")),
+        (Some("  "), Some(true), format!("{indented}\n")),
+        (Some("  "), Some(false), indented.to_string()),
+    ];
+
+    for (indent, final_newline, expected) in cases {
+        let out = convert_unwrap(
+            src,
+            OutputConfig::new().update(|c| {
+                c.indent = indent.map(Into::into);
+                c.final_newline = final_newline;
+            }),
+        );
+        assert_eq!(out, expected, "{indent:?}, {final_newline:?}");
+    }
+
+    let out = convert_unwrap(
+        src,
+        OutputConfig::new()
+            .indent("  ")
+            .line_ending(LineEnding::CrLf)
+            .final_newline(false),
+    );
+    assert_eq!(out, indented.replace('\n', "\r\n"));
+}
+
+#[test]
+fn attribute_variables() {
+    let config = || OutputConfig::new().variables([("base", "/docs"), ("v", r"a\b")]);
+
+    let out = convert_unwrap(
+        r#"a[href=$base/page.html?v=$v]> Page
+img[src="$base/x.png" alt=\$base]>"#,
+        config(),
+    );
+    assert_eq!(
+        out,
+        concat!(
+            r#"<a href="/docs/page.html?v=a\b">Page</a> "#,
+            r#"<img src="$base/x.png" alt="$base">"#,
+        )
+    );
+
+    let out = convert_unwrap("a[href=$base]> Page", None);
+    assert_eq!(out, r#"<a href="$base">Page</a>"#);
+}
+
+#[test]
+fn merge_config() {
+    let base = OutputConfig::new()
+        .indent("  ")
+        .xml(false)
+        .emphasis_tag("i")
+        .strong_tag("b");
+    let overrides = OutputConfig::new().xml(true).strong_tag("mark");
+
+    let config = base.merge(overrides);
+    assert_eq!(config.indent.as_deref(), Some("  "));
+    assert_eq!(config.xml, Some(true));
+
+    let out = convert_unwrap("p> </a/> <#b#>", config);
+    assert_eq!(out, "<p><i>a</i> <mark>b</mark></p>\n");
+
+    let config = OutputConfig::new().indent("\t").merge(OutputConfig::new());
+    assert_eq!(config.indent.as_deref(), Some("\t"));
+
+    let base = OutputConfig::new().metadata(MetadataConfig::new().elements(true));
+    let overrides = OutputConfig::new().metadata(MetadataConfig::new().ranges(false));
+    let metadata = base.merge(overrides).metadata.unwrap();
+    assert_eq!(metadata.elements, Some(true));
+    assert_eq!(metadata.ranges, Some(false));
+    assert_eq!(metadata.flags, None);
+}
+
+#[test]
+fn fragment_list() {
+    let src = r#"
+section {
+    h2> One
+}
+
+<! Between !>
+
+section#two {
+    Two
+}
+
+section> ul {
+    > Three
+}
+"#;
+
+    let fragments = mintyml::convert_fragment_list(src, OutputConfig::new()).unwrap();
+    assert_eq!(
+        fragments,
+        [
+            "<section><h2>One</h2></section>",
+            r#"<section id="two"><p>Two</p></section>"#,
+            "<section><ul><li>Three</li></ul></section>",
+        ]
+    );
+
+    let fragments = mintyml::convert_fragment_list(src, OutputConfig::new().indent("  ")).unwrap();
+    assert_eq!(fragments[0], "<section>\n  <h2>One</h2>\n</section>\n");
+
+    let e = mintyml::convert_fragment_list("section> <# a", OutputConfig::new()).unwrap_err();
+    assert!(matches!(e, ConvertError::Syntax { .. }), "{e:?}");
+}
+
+#[test]
+fn minimize_boolean_attributes() {
+    let src =
+        r#"input[disabled=disabled checked="" READONLY=readonly required=no value=disabled]>"#;
+
+    let out = convert_unwrap(src, OutputConfig::new().minimize_boolean_attributes(true));
+    assert_eq!(
+        out,
+        r#"<input disabled checked READONLY required="no" value="disabled">"#
+    );
+
+    let out = convert_unwrap(src, OutputConfig::new());
+    assert!(out.contains(r#"disabled="disabled""#), "{out}");
+
+    let out = convert_unwrap(
+        src,
+        OutputConfig::new()
+            .minimize_boolean_attributes(true)
+            .xml(true),
+    );
+    assert!(out.contains(r#"disabled="disabled""#), "{out}");
+}
+
+#[test]
+fn indent_spaces_and_tabs() {
+    let src = "section {\n    ul {\n        item\n    }\n}";
+
+    let out = convert_unwrap(src, OutputConfig::new().indent_spaces(2));
+    assert_eq!(
+        out,
+        "<section>\n  <ul>\n    <li>item</li>\n  </ul>\n</section>\n"
+    );
+    assert_eq!(out, convert_unwrap(src, OutputConfig::new().indent("  ")));
+
+    let out = convert_unwrap(src, OutputConfig::new().indent_tabs());
+    assert_eq!(
+        out,
+        "<section>\n\t<ul>\n\t\t<li>item</li>\n\t</ul>\n</section>\n"
+    );
+}
+
+#[test]
+fn preserve_inline_space() {
+    let src = "nav> <(span> One)> <(span> Two)>  <#Three#> and <(span> Four)>";
+
+    let out = convert_unwrap(src, OutputConfig::new());
+    assert_eq!(
+        out,
+        "<nav><span>One</span> <span>Two</span>  <strong>Three</strong> and <span>Four</span></nav>"
+    );
+
+    let out = convert_unwrap(src, OutputConfig::new().preserve_inline_space(false));
+    assert_eq!(
+        out,
+        "<nav><span>One</span><span>Two</span><strong>Three</strong> and <span>Four</span></nav>"
+    );
+}
+
+#[test]
+fn dedup_classes() {
+    let out = convert_unwrap("div.a.b.a> Hi", OutputConfig::new());
+    assert_eq!(out, r#"<div class="a b">Hi</div>"#);
+
+    let out = convert_unwrap("div.a.b.a> Hi", OutputConfig::new().dedup_classes(false));
+    assert_eq!(out, r#"<div class="a b a">Hi</div>"#);
+
+    let out = convert_unwrap("div.b.a.b> Hi", OutputConfig::new().sort_attributes(true));
+    assert_eq!(out, r#"<div class="b a">Hi</div>"#);
+}
+
+#[test]
+fn inline_code_in_pre() {
+    let src = "p> Call <`run()`> first.\n\n```\nrun()\n```";
+
+    let out = convert_unwrap(src, OutputConfig::new());
+    assert_eq!(
+        out,
+        "<p>Call <code>run()</code> first.</p> <pre><code>run()</code></pre>"
+    );
+
+    let out = convert_unwrap(src, OutputConfig::new().inline_code_in_pre(true));
+    assert_eq!(
+        out,
+        "<p>Call <pre><code>run()</code></pre> first.</p> <pre><code>run()</code></pre>"
+    );
+}
+
+#[test]
+fn sort_attributes() {
+    let src = "div[z=1][a=2 m=3][a=4]> Hi";
+
+    let out = convert_unwrap(src, OutputConfig::new());
+    assert_eq!(out, r#"<div z="1" a="2" m="3" a="4">Hi</div>"#);
+
+    let out = convert_unwrap(src, OutputConfig::new().sort_attributes(true));
+    assert_eq!(out, r#"<div a="2" a="4" m="3" z="1">Hi</div>"#);
+
+    let out = convert_unwrap(
+        "div#id.class[z][a]> Hi",
+        OutputConfig::new().sort_attributes(true),
+    );
+    assert_eq!(out, r#"<div id="id" class="class" a z>Hi</div>"#);
+}
+
+#[test]
+fn drop_empty() {
+    let src = "section {\n    >\n\n    div>\n}\n\nbr>\n\n#keep>\n\n> Text";
+    let config = |policy| OutputConfig::new().drop_empty(policy);
+
+    let out = convert_unwrap(src, config(DropEmptyPolicy::Never));
+    assert_eq!(
+        out,
+        r#"<section><p></p> <div></div></section> <br> <p id="keep"></p> <p>Text</p>"#
+    );
+
+    let out = convert_unwrap(src, config(DropEmptyPolicy::Inferred));
+    assert_eq!(
+        out,
+        r#"<section><div></div></section> <br> <p id="keep"></p> <p>Text</p>"#
+    );
+
+    let out = convert_unwrap(src, config(DropEmptyPolicy::Always));
+    assert_eq!(out, r#"<br> <p id="keep"></p> <p>Text</p>"#);
+
+    let out = convert_unwrap(
+        "my-void>\n\ndiv>",
+        config(DropEmptyPolicy::Always).void_elements(["my-void"]),
+    );
+    assert_eq!(out, "<my-void>");
+
+    let out = convert_unwrap("", config(DropEmptyPolicy::Always).complete_page(true));
+    assert_eq!(
+        out,
+        "<!DOCTYPE html>\n<html><head></head> <body></body></html>"
+    );
+
+    let out = convert_unwrap(
+        "table {\n    tr {\n        td>\n        td> A\n    }\n}",
+        config(DropEmptyPolicy::Always),
+    );
+    assert_eq!(out, "<table><tr><td></td> <td>A</td></tr></table>");
+
+    let out = convert_unwrap(
+        "textarea>\n\nscript>\n\niframe>",
+        config(DropEmptyPolicy::Always),
+    );
+    assert_eq!(
+        out,
+        "<textarea></textarea> <script></script> <iframe></iframe>"
+    );
+
+    assert_eq!(
+        convert_unwrap(src, None),
+        convert_unwrap(src, config(DropEmptyPolicy::Never))
+    );
+}
+
+#[test]
+fn source_map() {
+    let src = "section {\n    h1> Title\n\n    p> Some <#bold#> text\n}";
+
+    let check = |config: OutputConfig| {
+        let (html, source_map) = mintyml::convert_with_source_map(src, config).unwrap();
+        Vec::from_iter(source_map.into_iter().map(|(output, input)| {
+            (
+                html[output].to_owned(),
+                &src[input.start.position..input.end.position],
+            )
+        }))
+    };
+
+    let entries = check(OutputConfig::new());
+    assert_eq!(
+        entries,
+        [
+            (
+                "<section><h1>Title</h1> <p>Some <strong>bold</strong> text</p></section>".into(),
+                src
+            ),
+            ("<h1>Title</h1>".into(), "h1> Title"),
+            (
+                "<p>Some <strong>bold</strong> text</p>".into(),
+                "p> Some <#bold#> text"
+            ),
+            ("<strong>bold</strong>".into(), "<#bold#>"),
+        ]
+    );
+
+    let entries = check(OutputConfig::new().indent("  ").max_width(12));
+    assert!(entries[2].0.starts_with("<p>Some\n"), "{entries:?}");
+    assert_eq!(entries[2].1, "p> Some <#bold#> text");
+    assert_eq!(entries[3], ("<strong>bold</strong>".into(), "<#bold#>"));
+
+    let entries = check(OutputConfig::new().complete_page(true));
+    assert_eq!(entries.len(), 4, "{entries:?}");
+    assert_eq!(entries[0].1, src);
+    assert_eq!(entries[3], ("<strong>bold</strong>".into(), "<#bold#>"));
+}
@@ -1,7 +1,15 @@
 mod utils;
 
-use mintyml::{error::UnclosedDelimiterKind, ConvertError, SyntaxError, SyntaxErrorKind};
-use utils::convert_fail;
+use std::sync::{Arc, Mutex};
+
+use mintyml::{
+    error::{
+        Diagnostic, DisplayWithSrcOptions, ErrorRef, LineIndex, SemanticError, SemanticErrorKind,
+        UnclosedDelimiterKind, Warning, WarningKind,
+    },
+    ConvertError, OutputConfig, SyntaxError, SyntaxErrorKind,
+};
+use utils::{convert_fail, convert_unwrap};
 
 /// Test that an unclosed block is detected and a best-effort parsing is used.
 #[test]
@@ -128,15 +136,17 @@ fn double_unclosed_special() {
                  range,
                  ..
              }| range.start.position == 63 && range.end.position == 65,
+            // `<"` is closed by `~>`, so the error points at the mismatched closer.
             |SyntaxError {
                  kind:
-                     SyntaxErrorKind::Unclosed {
-                         delimiter: UnclosedDelimiterKind::SpecialInline { .. },
+                     SyntaxErrorKind::MismatchedDelimiter {
+                         expected: UnclosedDelimiterKind::SpecialInline { .. },
+                         found: UnclosedDelimiterKind::SpecialInline { .. },
                          ..
                      },
                  range,
                  ..
-             }| range.start.position == 92 && range.end.position == 94,
+             }| range.start.position == 101 && range.end.position == 103,
         ],
     );
 
@@ -222,3 +232,539 @@ fn unclosed_comment() {
 
     assert_eq!(out.unwrap(), "<p>foo <!--bar\n    baz\n    --></p>")
 }
+
+#[test]
+fn warnings_on_success() {
+    let src = r#"
+        input[type=text name=foo TYPE=checkbox]>
+    "#;
+
+    let (out, warnings) = mintyml::convert_forgiving_with_warnings(src, OutputConfig::new());
+
+    assert_eq!(
+        out.unwrap(),
+        r#"<input type="text" name="foo" TYPE="checkbox">"#
+    );
+
+    match_set!(
+        &warnings,
+        [
+            |Warning {
+                 kind: WarningKind::DuplicateAttribute { .. },
+                 range,
+                 ..
+             }| range.start.position == 34 && range.end.position == 38
+        ],
+    );
+
+    assert_eq!(
+        warnings[0]
+            .display_with_src(src, &Default::default())
+            .to_string(),
+        r#"Duplicate attribute "TYPE""#
+    );
+}
+
+#[test]
+fn multiple_ids() {
+    let src = "div#a#b.c#d> Hi";
+
+    let (partial, e) = convert_fail(src, None);
+    let ConvertError::Semantic {
+        semantic_errors, ..
+    } = e
+    else {
+        panic!("{e:?}")
+    };
+    let ranges: Vec<_> = semantic_errors
+        .iter()
+        .map(|e| {
+            assert!(matches!(e.kind, SemanticErrorKind::MultipleIds { .. }));
+            &src[e.range.start.position..e.range.end.position]
+        })
+        .collect();
+    assert_eq!(ranges, ["#b", "#d"]);
+    assert_eq!(partial.unwrap(), r#"<div id="a" class="c">Hi</div>"#);
+
+    convert_unwrap("div#a.b.c> Hi", None);
+}
+
+#[test]
+fn syntax_error_line_column() {
+    let src = "section {\n  foo\n  bar <(baz\n}\n";
+
+    let (_, e) = convert_fail(src, None);
+
+    let ConvertError::Syntax {
+        syntax_errors, src, ..
+    } = e
+    else {
+        panic!()
+    };
+
+    let mut options = DisplayWithSrcOptions::default();
+    options.show_line_column = true;
+
+    assert_eq!(
+        syntax_errors[0]
+            .display_with_src(&src, &options)
+            .to_string(),
+        r#"Unclosed delimiter "<(" at line 3, column 7"#
+    );
+
+    options.show_location = true;
+    options.line_index = Some(LineIndex::new(&src));
+
+    assert_eq!(
+        syntax_errors[0]
+            .display_with_src(&src, &options)
+            .to_string(),
+        r#"Unclosed delimiter "<(" at line 3, column 7 (character 22..<24)"#
+    );
+}
+
+#[test]
+fn output_too_large() {
+    let src = r#"
+        ul {
+            > one
+            > two
+            > three
+        }
+    "#;
+
+    let (out, e) = convert_fail(src, OutputConfig::new().max_output_bytes(16));
+
+    assert!(matches!(e, ConvertError::OutputTooLarge { limit: 16 }));
+    assert!(out.unwrap().len() <= 16);
+
+    convert_unwrap(src, OutputConfig::new().max_output_bytes(1024));
+}
+
+#[test]
+fn on_diagnostic_per_error() {
+    let src = r#"
+        section {
+            <(a> one
+            <(b> two
+        }
+        p> <(c> three
+    "#;
+
+    let found = Arc::new(Mutex::new(Vec::new()));
+    let config = OutputConfig::new().on_diagnostic({
+        let found = found.clone();
+        move |d| match d {
+            Diagnostic::Syntax { error, .. } => found.lock().unwrap().push(error.range),
+            _ => panic!(),
+        }
+    });
+
+    let Err(ConvertError::Syntax { syntax_errors, .. }) = mintyml::convert(src, config) else {
+        panic!()
+    };
+
+    let found = found.lock().unwrap();
+    assert_eq!(found.len(), 3);
+    assert_eq!(
+        *found,
+        Vec::from_iter(syntax_errors.iter().map(|e| e.range))
+    );
+}
+
+#[test]
+fn annotate_errors_in_output() {
+    let src = r#"
+        table {
+          {
+            > Cell A
+            > Cell B
+          {
+            > Cell C
+            > Cell D
+          <(a> unclosed
+          }
+        }
+    "#;
+
+    let (partial, e) = convert_fail(src, OutputConfig::new().annotate_errors_in_output(true));
+
+    let ConvertError::Syntax { syntax_errors, .. } = e else {
+        panic!()
+    };
+    assert_eq!(syntax_errors.len(), 2);
+
+    let partial = partial.unwrap();
+    assert!(partial.starts_with("<table><tr><td>Cell A</td>"));
+    assert!(partial.ends_with("</table> <!-- mintyml: 2 errors, output may be incomplete -->"));
+
+    let (partial, _) = convert_fail(src, None);
+    assert!(!partial.unwrap().contains("<!--"));
+}
+
+#[test]
+fn duplicate_id() {
+    let src = "section#foo {\n    p#bar> One\n    p#foo> Two\n}";
+    let config = || OutputConfig::new().check_duplicate_ids(true);
+
+    assert!(mintyml::convert(src, OutputConfig::new()).is_ok());
+
+    let (partial, e) = convert_fail(src, config());
+
+    let ConvertError::Semantic {
+        semantic_errors, ..
+    } = e
+    else {
+        panic!("{e:?}")
+    };
+    let [SemanticError {
+        range,
+        kind: SemanticErrorKind::DuplicateId { id, .. },
+        ..
+    }] = &semantic_errors[..]
+    else {
+        panic!("{semantic_errors:?}")
+    };
+    assert_eq!(id, "foo");
+    assert_eq!(&src[range.start.position..range.end.position], "#foo");
+    assert_eq!(range.start.position, src.rfind("#foo").unwrap());
+
+    assert_eq!(
+        partial.unwrap(),
+        r#"<section id="foo"><p id="bar">One</p> <p id="foo">Two</p></section>"#
+    );
+
+    let (partial, _) = convert_fail(src, config().fail_fast(true));
+    assert!(partial.is_none());
+
+    let src = "p#foo> One\n\np[ID=foo]> Two\n\np[id=bar]> Three";
+    let (_, e) = convert_fail(src, config());
+    let ConvertError::Semantic {
+        semantic_errors, ..
+    } = e
+    else {
+        panic!("{e:?}")
+    };
+    let [SemanticError {
+        range,
+        kind: SemanticErrorKind::DuplicateId { id, .. },
+        ..
+    }] = &semantic_errors[..]
+    else {
+        panic!("{semantic_errors:?}")
+    };
+    assert_eq!(id, "foo");
+    assert_eq!(&src[range.start.position..range.end.position], "ID=foo");
+}
+
+#[test]
+fn strict_attributes() {
+    let config = || OutputConfig::new().strict_attributes(true);
+
+    let (_, e) = convert_fail("p> <(a[href]> Home)>", config());
+    let ConvertError::Semantic {
+        semantic_errors, ..
+    } = e
+    else {
+        panic!("{e:?}")
+    };
+    let [SemanticError {
+        kind: SemanticErrorKind::MissingAttributeValue { name, .. },
+        ..
+    }] = &semantic_errors[..]
+    else {
+        panic!("{semantic_errors:?}")
+    };
+    assert_eq!(name, "href");
+
+    let out = convert_unwrap("input[disabled]>", config());
+    assert_eq!(out, "<input disabled>");
+
+    convert_unwrap("a[href]> Home", None);
+}
+
+#[test]
+fn inconsistent_indentation() {
+    let src = "section {\n    p> One\n\tp> Two\n    div {\n\t\tp> Three\n\t  p> Four\n    }\n}";
+    let config = || OutputConfig::new().check_indentation(true);
+
+    let (partial, e) = convert_fail(src, config());
+    let ConvertError::Semantic {
+        semantic_errors, ..
+    } = e
+    else {
+        panic!("{e:?}")
+    };
+    let ranges: Vec<_> = semantic_errors
+        .iter()
+        .map(|e| {
+            assert!(matches!(
+                e.kind,
+                SemanticErrorKind::InconsistentIndentation { .. }
+            ));
+            &src[e.range.start.position..e.range.end.position]
+        })
+        .collect();
+    assert_eq!(ranges, ["\t", "\t  "]);
+    assert!(partial.unwrap().contains("<p>Four</p>"));
+
+    convert_unwrap(src, None);
+    convert_unwrap(
+        "div {\n\tp> One\n\tp> Two\n}\ndiv { p> Three\n  p> Four\n}",
+        config(),
+    );
+}
+
+#[test]
+fn invalid_nesting() {
+    let src = "p {\n    section> Inside\n\n    <(span> Fine)>\n}\n\nsection {\n    p> Fine\n}";
+    let config = || OutputConfig::new().strict_nesting(true);
+
+    let (partial, e) = convert_fail(src, config());
+    let ConvertError::Semantic {
+        semantic_errors, ..
+    } = e
+    else {
+        panic!("{e:?}")
+    };
+    let [SemanticError {
+        range,
+        kind: SemanticErrorKind::InvalidNesting { parent, child, .. },
+        ..
+    }] = &semantic_errors[..]
+    else {
+        panic!("{semantic_errors:?}")
+    };
+    assert_eq!((&parent[..], &child[..]), ("p", "section"));
+    assert_eq!(
+        &src[range.start.position..range.end.position],
+        "section> Inside"
+    );
+    assert!(partial.unwrap().starts_with("<p><section>Inside</section>"));
+
+    convert_unwrap(src, None);
+    convert_unwrap("details {\n    summary> h2> Title\n}", config());
+
+    // These allow some flow content, unlike most elements that contain phrasing content.
+    for src in [
+        "table {\n    caption> <(div> x)>\n}",
+        "dl {\n    dt> <(div> x)>\n}",
+        "fieldset {\n    legend> <(h2> x)>\n}",
+    ] {
+        convert_unwrap(src, config());
+    }
+}
+
+/// Test that a closing delimiter that doesn't match the innermost opening delimiter is reported
+/// at the closer, whether or not the rest of the document parses.
+#[test]
+fn mismatched_special_delimiter() {
+    use mintyml::SpecialKind;
+
+    for src in ["<# a </ b #>", "<# a </ b #> c />"] {
+        let (_, e) = convert_fail(src, None);
+        let ConvertError::Syntax { syntax_errors, .. } = e else {
+            panic!("{e:?}")
+        };
+
+        match_set!(
+            &syntax_errors,
+            [
+                |SyntaxError {
+                     kind:
+                         SyntaxErrorKind::MismatchedDelimiter {
+                             expected:
+                                 UnclosedDelimiterKind::SpecialInline {
+                                     kind: SpecialKind::Emphasis,
+                                     ..
+                                 },
+                             found:
+                                 UnclosedDelimiterKind::SpecialInline {
+                                     kind: SpecialKind::Strong,
+                                     ..
+                                 },
+                             ..
+                         },
+                     range,
+                     ..
+                 }| range.start.position == 10 && range.end.position == 12,
+            ],
+        );
+    }
+
+    let (_, e) = convert_fail("<( a <_ b )>", None);
+    let ConvertError::Syntax { syntax_errors, .. } = e else {
+        panic!("{e:?}")
+    };
+    assert!(matches!(
+        syntax_errors[..],
+        [SyntaxError {
+            kind: SyntaxErrorKind::MismatchedDelimiter {
+                found: UnclosedDelimiterKind::Inline { .. },
+                ..
+            },
+            ..
+        }]
+    ));
+
+    // Correctly nested delimiters are fine.
+    assert_eq!(
+        convert_unwrap("<# a </ b /> c #>", None),
+        "<p><strong>a <em>b</em> c</strong></p>"
+    );
+}
+
+#[test]
+fn convert_error_iter() {
+    let src = "p> a <# b\n\np> c </ d";
+    let (_, e) = convert_fail(src, None);
+
+    let errors = Vec::from_iter(e.errors());
+    assert_eq!(errors.len(), 2);
+    assert!(errors.iter().all(|e| matches!(e, ErrorRef::Syntax { .. })));
+    assert_eq!(
+        Vec::from_iter(errors.iter().map(|e| e.range().start.position)),
+        [5, 16],
+    );
+    assert_eq!(
+        errors[0].to_string(),
+        "Unclosed strong tag delimiter at character 5..<7",
+    );
+    assert_eq!(
+        errors[1]
+            .display_with_src(src, &DisplayWithSrcOptions::default())
+            .to_string(),
+        e.to_string().split("; ").nth(1).unwrap(),
+    );
+
+    assert_eq!(ConvertError::Unknown.errors().count(), 0);
+}
+
+#[test]
+fn undefined_variable() {
+    let src = "a[href=$base/$page]> Page";
+    let (_, e) = convert_fail(src, OutputConfig::new().variables([("base", "/docs")]));
+    let ConvertError::Semantic {
+        semantic_errors, ..
+    } = e
+    else {
+        panic!("{e:?}")
+    };
+    let [SemanticError {
+        kind: SemanticErrorKind::UndefinedVariable { name, .. },
+        range,
+        ..
+    }] = &semantic_errors[..]
+    else {
+        panic!("{semantic_errors:?}")
+    };
+    assert_eq!(name, "page");
+    assert_eq!(range.slice(src), "$page");
+}
+
+#[test]
+fn strict_vs_fail_fast() {
+    let src = "p> a <# b\n\np> c </ d";
+    let syntax_error_count = |e: &ConvertError| match e {
+        ConvertError::Syntax { syntax_errors, .. } => syntax_errors.len(),
+        e => panic!("{e:?}"),
+    };
+
+    let (partial, e) = convert_fail(src, None);
+    assert!(partial.is_some());
+    assert_eq!(syntax_error_count(&e), 2);
+
+    let (partial, e) = convert_fail(src, OutputConfig::new().strict(true));
+    assert!(partial.is_none());
+    assert_eq!(syntax_error_count(&e), 2);
+
+    let (partial, e) = convert_fail(src, OutputConfig::new().fail_fast(true));
+    assert!(partial.is_none());
+    assert_eq!(syntax_error_count(&e), 1);
+
+    // Other syntax errors still allow partial output.
+    let (partial, _) = convert_fail(r"p> a \q b", OutputConfig::new().strict(true));
+    assert!(partial.is_some());
+}
+
+#[test]
+fn max_errors() {
+    let src = "p> <`\\q`>\n".repeat(50);
+
+    let (_, e) = convert_fail(
+        &src,
+        OutputConfig::new().strict_escapes(true).max_errors(10),
+    );
+    let ConvertError::Syntax {
+        ref syntax_errors,
+        truncated,
+        ..
+    } = e
+    else {
+        panic!("{e:?}")
+    };
+    assert_eq!(syntax_errors.len(), 10);
+    assert!(truncated);
+    assert!(
+        e.to_string().ends_with("; too many errors, stopping"),
+        "{e}"
+    );
+
+    let (_, e) = convert_fail(&src, OutputConfig::new().strict_escapes(true));
+    let ConvertError::Syntax {
+        syntax_errors,
+        truncated,
+        ..
+    } = e
+    else {
+        panic!("{e:?}")
+    };
+    assert_eq!(syntax_errors.len(), 50);
+    assert!(!truncated);
+
+    let e = mintyml::convert(
+        "p> <`\\q`>",
+        OutputConfig::new().strict_escapes(true).max_errors(0),
+    )
+    .unwrap_err();
+    let ConvertError::Syntax {
+        syntax_errors,
+        truncated,
+        ..
+    } = e
+    else {
+        panic!("{e:?}")
+    };
+    assert_eq!(syntax_errors.len(), 1);
+    assert!(!truncated);
+}
+
+#[test]
+fn unclosed_raw_comment() {
+    let src = "p> a <!![if IE]> b\n\np> c\n";
+    let (_, e) = convert_fail(src, None);
+
+    let ConvertError::Syntax { syntax_errors, .. } = e else {
+        panic!()
+    };
+    match_set!(
+        &syntax_errors,
+        [
+            |SyntaxError {
+                 kind:
+                     SyntaxErrorKind::Unclosed {
+                         delimiter: UnclosedDelimiterKind::RawComment { .. },
+                         ..
+                     },
+                 range,
+                 ..
+             }| range.start.position == 5 && range.end.position == 8,
+        ],
+    );
+    assert_eq!(
+        syntax_errors[0]
+            .display_with_src(src, &Default::default())
+            .to_string(),
+        r#"Unclosed delimiter "<!!""#
+    );
+}
@@ -1,5 +1,8 @@
-
-use utils::convert_unwrap;
+use mintyml::{
+    error::{SyntaxError, SyntaxErrorKind},
+    ConvertError, OutputConfig,
+};
+use utils::{convert_fail, convert_unwrap};
 
 mod utils;
 
@@ -42,3 +45,147 @@ fn u_letters_multiline() {
 
     assert_eq!(out, r"<p>AB</p>");
 }
+
+#[test]
+fn escape_mixed_content() {
+    let out = convert_unwrap(
+        "Plain text & \\<tags\\>, caf\u{e9} \u{85}next \u{a0}\u{7f} \"quoted\" \u{1f600}\\t",
+        None,
+    );
+
+    assert_eq!(
+        out,
+        "<p>Plain text &amp; &lt;tags&gt;, caf\u{e9} &#133;next \u{a0}&#127; \"quoted\" \u{1f600}&Tab;</p>"
+    );
+}
+
+#[test]
+fn escape_mixed_content_attribute() {
+    let out = convert_unwrap(r#"div[title="a & b < \"c\" caf\u{e9}\u{9f}"]>"#, None);
+
+    assert_eq!(
+        out,
+        r#"<div title="a &amp; b &lt; &quot;c&quot; café&#159;"></div>"#
+    );
+}
+
+#[test]
+fn unknown_escape_lenient() {
+    let out = convert_unwrap(
+        r#"
+        '''
+        a\qb
+        '''
+    "#,
+        None,
+    );
+
+    assert_eq!(out, r"<p>a\qb</p>");
+}
+
+#[test]
+fn unknown_escape_strict() {
+    let src = r#"
+        '''
+        a\qb
+        '''
+    "#;
+
+    let (_, e) = convert_fail(src, OutputConfig::new().strict_escapes(true));
+
+    let ConvertError::Syntax { syntax_errors, .. } = e else {
+        panic!()
+    };
+
+    assert_eq!(syntax_errors.len(), 1);
+    assert!(matches!(
+        syntax_errors[0].kind,
+        SyntaxErrorKind::InvalidEscape { .. }
+    ));
+    assert_eq!(syntax_errors[0].range.slice(src), r"\q");
+}
+
+#[test]
+fn unknown_escape_output() {
+    let src = r#"
+        p> a\qb
+
+        '''
+        c\qd
+        '''
+    "#;
+
+    for config in [OutputConfig::new(), OutputConfig::new().strict_escapes(true)] {
+        let (out, _) = convert_fail(src, config);
+
+        assert_eq!(out.as_deref(), Some(r"<p>a\qb</p> <p>c\qd</p>"));
+    }
+}
+
+#[test]
+fn invalid_escape_range() {
+    let cases = [
+        (r"p> a \q b", r"\q"),
+        (r"p> a \x7 b", r"\x7"),
+        (r"p> a \xZZ b", r"\x"),
+        (r"p> a \x80 b", r"\x80"),
+        (r"p> a \u{110000} b", r"\u{110000}"),
+        (r"p> a \u{1234567} b", r"\u{1234567}"),
+        (r"a[href=x\qy]> b", r"\q"),
+        (r#"a[title="a \u{zz}"]> b"#, r"\u{"),
+    ];
+
+    for (src, expected) in cases {
+        let (_, e) = convert_fail(src, None);
+        let ConvertError::Syntax { syntax_errors, .. } = e else {
+            panic!("{e:?}")
+        };
+
+        let [SyntaxError {
+            kind: SyntaxErrorKind::InvalidEscape { .. },
+            range,
+            ..
+        }] = &syntax_errors[..]
+        else {
+            panic!("{syntax_errors:?}")
+        };
+        assert_eq!(range.slice(src), expected, "{src:?}");
+    }
+}
+
+#[test]
+fn ascii_only() {
+    let src = "p[title=\"Crème brûlée\"]> Café 🎉 naïve";
+
+    let out = convert_unwrap(src, OutputConfig::new().ascii_only(true));
+    assert_eq!(
+        out,
+        r#"<p title="Cr&#232;me br&#251;l&#233;e">Caf&#233; &#127881; na&#239;ve</p>"#
+    );
+
+    let out = convert_unwrap(src, OutputConfig::new().ascii_only(true).xml(true));
+    assert_eq!(
+        out,
+        r#"<p title="Cr&#232;me br&#251;l&#233;e">Caf&#233; &#127881; na&#239;ve</p>"#
+    );
+
+    let out = convert_unwrap(src, OutputConfig::new());
+    assert_eq!(out, r#"<p title="Crème brûlée">Café 🎉 naïve</p>"#);
+}
+
+#[test]
+fn extra_escapes() {
+    let src = "p[title=\"It's\"]> Don't & café";
+
+    let out = convert_unwrap(src, OutputConfig::new().extra_escapes([('\'', "&apos;")]));
+    assert_eq!(out, r#"<p title="It&apos;s">Don&apos;t &amp; café</p>"#);
+
+    let out = convert_unwrap(
+        src,
+        OutputConfig::new().extra_escapes([('&', "&#38;"), ('é', "&eacute;")]),
+    );
+    assert_eq!(out, r#"<p title="It's">Don't &#38; caf&eacute;</p>"#);
+
+    let out = convert_unwrap(src, OutputConfig::new());
+    assert_eq!(out, r#"<p title="It's">Don't &amp; café</p>"#);
+}
@@ -1,4 +1,9 @@
-use mintyml::OutputConfig;
+use mintyml::{
+    inference::{
+        define_methods, define_tags, when::*, Infer, MethodDefinition, StandardInfer, TagDefinition,
+    },
+    ClassPlacement, OutputConfig,
+};
 use utils::convert_unwrap;
 
 mod utils;
@@ -52,3 +57,325 @@ fn table_nested_in_div() {
         )
     )
 }
+
+#[test]
+fn inferred_wrapper_class() {
+    let src = r#"
+    section {
+        Hello
+
+        div.foo> World
+    }
+    "#;
+
+    let out = convert_unwrap(
+        src,
+        OutputConfig::new().inferred_wrapper_class_prefix("mty-inferred-"),
+    );
+
+    assert_eq!(
+        out,
+        concat!(
+            r#"<section>"#,
+            r#"<p class="mty-inferred-p">Hello</p> "#,
+            r#"<div class="foo">World</div>"#,
+            r#"</section>"#,
+        )
+    )
+}
+
+#[test]
+fn forced_tag_attribute() {
+    let src = r#"
+    figure {
+        img[src=a.png]>
+
+        [mty:as=figcaption].note> A picture
+    }
+
+    [MTY:AS=ol] {
+        > one
+        > two
+    }
+    "#;
+
+    let out = convert_unwrap(src, None);
+
+    assert_eq!(
+        out,
+        concat!(
+            r#"<figure><img src="a.png"> <figcaption class="note">A picture</figcaption></figure> "#,
+            r#"<ol><li>one</li> <li>two</li></ol>"#,
+        )
+    )
+}
+
+#[derive(Debug)]
+struct CardInfer;
+
+impl<'cfg> Infer<'cfg> for CardInfer {
+    fn define_tags(&self) -> impl TagDefinition<'cfg> {
+        define_tags()
+            .when(!after(element()), "header")
+            .default("section")
+    }
+}
+
+#[derive(Debug)]
+struct CardsInfer;
+
+impl<'cfg> Infer<'cfg> for CardsInfer {
+    fn define_tags(&self) -> impl TagDefinition<'cfg> {
+        StandardInfer::INSTANCE.define_tags()
+    }
+
+    fn define_methods(&self) -> impl MethodDefinition<'cfg> {
+        define_methods()
+            .when(tag("card"), CardInfer)
+            .apply_from(StandardInfer::INSTANCE)
+    }
+}
+
+#[test]
+fn custom_root_inference() {
+    let src = r#"
+    card {
+        > Title
+        > First
+
+        Second
+    }
+
+    ul {
+        > item
+    }
+    "#;
+
+    let out = convert_unwrap(src, OutputConfig::new().inference(CardsInfer));
+
+    assert_eq!(
+        out,
+        concat!(
+            r#"<card><header>Title</header> <section>First</section> <section>Second</section></card> "#,
+            r#"<ul><li>item</li></ul>"#,
+        ),
+    );
+
+    let out = convert_unwrap(src, None);
+    assert!(out.starts_with("<card><p>Title</p>"));
+}
+
+#[test]
+fn table_header_cells() {
+    let src = r#"
+    table {
+        thead {
+            {
+                > Name
+                > Age
+            }
+        }
+        tbody {
+            {
+                > Ann
+                > 30
+            }
+        }
+    }
+
+    table {
+        {
+            > Name
+            > Age
+        }
+        {
+            > Bob
+            > 40
+        }
+    }
+    "#;
+
+    let out = convert_unwrap(src, OutputConfig::new().table_header_row(true));
+
+    assert_eq!(
+        out,
+        concat!(
+            r#"<table><thead><tr><th>Name</th> <th>Age</th></tr></thead>"#,
+            r#" <tbody><tr><td>Ann</td> <td>30</td></tr></tbody></table> "#,
+            r#"<table><tr><th>Name</th> <th>Age</th></tr>"#,
+            r#" <tr><td>Bob</td> <td>40</td></tr></table>"#,
+        ),
+    );
+
+    let out = convert_unwrap(src, None);
+
+    assert_eq!(
+        out,
+        concat!(
+            r#"<table><thead><tr><th>Name</th> <th>Age</th></tr></thead>"#,
+            r#" <tbody><tr><td>Ann</td> <td>30</td></tr></tbody></table> "#,
+            r#"<table><tr><td>Name</td> <td>Age</td></tr>"#,
+            r#" <tr><td>Bob</td> <td>40</td></tr></table>"#,
+        ),
+    );
+}
+
+#[derive(Debug)]
+struct ListItemInfer;
+
+impl<'cfg> Infer<'cfg> for ListItemInfer {
+    fn define_tags(&self) -> impl TagDefinition<'cfg> {
+        define_tags().default("li")
+    }
+}
+
+#[derive(Debug)]
+struct RoleInfer;
+
+impl<'cfg> Infer<'cfg> for RoleInfer {
+    fn define_tags(&self) -> impl TagDefinition<'cfg> {
+        define_tags()
+            .when(attribute("data-note"), "aside")
+            .apply_from(StandardInfer::INSTANCE)
+    }
+
+    fn define_methods(&self) -> impl MethodDefinition<'cfg> {
+        define_methods()
+            .when(attribute_eq("role", "list"), ListItemInfer)
+            .apply_from(StandardInfer::INSTANCE)
+    }
+}
+
+#[test]
+fn attribute_predicates() {
+    let src = r#"
+    div[role=list] {
+        > one
+        > two
+    }
+
+    div[role=main] {
+        > three
+    }
+
+    [data-note] { Note }
+    "#;
+
+    let out = convert_unwrap(src, OutputConfig::new().inference(RoleInfer));
+
+    assert_eq!(
+        out,
+        concat!(
+            r#"<div role="list"><li>one</li> <li>two</li></div> "#,
+            r#"<div role="main"><p>three</p></div> "#,
+            r#"<aside data-note><p>Note</p></aside>"#,
+        ),
+    );
+}
+
+#[derive(Debug)]
+struct PositionInfer;
+
+impl<'cfg> Infer<'cfg> for PositionInfer {
+    fn define_tags(&self) -> impl TagDefinition<'cfg> {
+        define_tags()
+            .when(first_child(), "header")
+            .when(last_child() & just_after(any()), "footer")
+            .default("p")
+    }
+}
+
+#[derive(Debug)]
+struct PositionsInfer;
+
+impl<'cfg> Infer<'cfg> for PositionsInfer {
+    fn define_tags(&self) -> impl TagDefinition<'cfg> {
+        StandardInfer::INSTANCE.define_tags()
+    }
+
+    fn define_methods(&self) -> impl MethodDefinition<'cfg> {
+        define_methods()
+            .when(tag("card"), PositionInfer)
+            .apply_from(StandardInfer::INSTANCE)
+    }
+}
+
+#[test]
+fn first_and_last_child() {
+    let src = r#"
+    card {
+        <! note !>
+        > Title
+        > Body
+        > End
+
+        <! note !>
+    }
+
+    card {
+        > Only
+    }
+    "#;
+
+    let out = convert_unwrap(src, OutputConfig::new().inference(PositionsInfer));
+
+    assert_eq!(
+        out,
+        concat!(
+            r#"<card><!-- note --><header>Title</header> <p>Body</p> <footer>End</footer> <!-- note --></card> "#,
+            r#"<card><header>Only</header></card>"#,
+        ),
+    );
+}
+
+#[derive(Debug)]
+struct LinkItemInfer;
+
+impl<'cfg> Infer<'cfg> for LinkItemInfer {
+    fn define_tags(&self) -> impl TagDefinition<'cfg> {
+        define_tags().default(["li", "a"])
+    }
+}
+
+#[derive(Debug)]
+struct NavInfer;
+
+impl<'cfg> Infer<'cfg> for NavInfer {
+    fn define_tags(&self) -> impl TagDefinition<'cfg> {
+        StandardInfer::INSTANCE.define_tags()
+    }
+
+    fn define_methods(&self) -> impl MethodDefinition<'cfg> {
+        define_methods()
+            .when(tag("ul"), LinkItemInfer)
+            .apply_from(StandardInfer::INSTANCE)
+    }
+}
+
+#[test]
+fn inferred_class_placement() {
+    let src = "ul {\n    .active#home> Home\n    > About\n}\n\n.intro> Hello";
+
+    let out = convert_unwrap(src, OutputConfig::new().inference(NavInfer));
+    assert_eq!(
+        out,
+        concat!(
+            r#"<ul><li id="home" class="active"><a>Home</a></li> <li><a>About</a></li></ul> "#,
+            r#"<p class="intro">Hello</p>"#,
+        ),
+    );
+
+    let out = convert_unwrap(
+        src,
+        OutputConfig::new()
+            .inference(NavInfer)
+            .inferred_class_placement(ClassPlacement::Innermost),
+    );
+    assert_eq!(
+        out,
+        concat!(
+            r#"<ul><li><a id="home" class="active">Home</a></li> <li><a>About</a></li></ul> "#,
+            r#"<p class="intro">Hello</p>"#,
+        ),
+    );
+}
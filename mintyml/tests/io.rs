@@ -0,0 +1,57 @@
+#![cfg(feature = "std")]
+
+use std::io;
+
+use mintyml::{ConvertError, OutputConfig};
+
+mod utils;
+
+const SRC: &str = r#"
+section {
+    Hello, <#world#>!
+}
+"#;
+
+#[test]
+fn convert_to_io_vec() {
+    let mut out = Vec::<u8>::new();
+    mintyml::convert_to_io(SRC, OutputConfig::new(), &mut out).unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        utils::convert_unwrap(SRC, None)
+    );
+}
+
+#[test]
+fn convert_to_io_with_errors() {
+    let src = "p> <(b> a\n";
+    let mut out = Vec::<u8>::new();
+    let e = mintyml::convert_to_io(src, OutputConfig::new(), &mut out).unwrap_err();
+
+    let mut expected = String::new();
+    mintyml::convert_to(src, OutputConfig::new(), &mut expected).unwrap_err();
+
+    assert!(matches!(e, ConvertError::Syntax { .. }));
+    assert_eq!(String::from_utf8(out).unwrap(), expected);
+    assert_eq!(expected, "<p><b>a</b></p>");
+}
+
+#[test]
+fn convert_to_io_write_error() {
+    struct FailingWriter;
+
+    impl io::Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let e = mintyml::convert_to_io(SRC, OutputConfig::new(), &mut FailingWriter).unwrap_err();
+
+    assert!(matches!(e, ConvertError::Unknown));
+}
@@ -0,0 +1,39 @@
+mod utils;
+
+use mintyml::{MetadataConfig, OutputConfig};
+use utils::convert_unwrap;
+
+const SRC: &str = r#"
+div {
+    '''
+    x
+    '''
+}
+"#;
+
+#[test]
+fn metadata_flags_disabled() {
+    let metadata = MetadataConfig::new().elements(true);
+
+    let out = convert_unwrap(SRC, OutputConfig::new().metadata(metadata.clone()));
+    assert!(out.contains("mty:verbatim"));
+    assert!(out.contains("mty:multiline"));
+
+    let out = convert_unwrap(SRC, OutputConfig::new().metadata(metadata.flags(false)));
+    assert!(!out.contains("mty:verbatim"));
+    assert!(!out.contains("mty:multiline"));
+    assert!(!out.contains("mty:raw"));
+    assert!(out.contains(r#"mty:start="1""#));
+}
+
+#[test]
+fn metadata_ranges_disabled() {
+    let out = convert_unwrap(
+        SRC,
+        OutputConfig::new().metadata(MetadataConfig::new().elements(true).ranges(false)),
+    );
+
+    assert!(!out.contains("mty:start"));
+    assert!(!out.contains("mty:content-end"));
+    assert!(out.contains("mty:verbatim"));
+}
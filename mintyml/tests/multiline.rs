@@ -141,3 +141,94 @@ fn multiline_double_indentation_pretty() {
 "#
     );
 }
+
+#[test]
+fn multiline_code_crlf_line_ending() {
+    let src = r#"
+    section {
+        ```
+        Hello
+            there,
+        world!
+        ```
+    }
+
+    "#;
+
+    let out = utils::convert_unwrap(
+        src,
+        OutputConfig::new()
+            .indent("  ")
+            .line_ending(mintyml::LineEnding::CrLf),
+    );
+
+    assert_eq!(
+        out,
+        concat!(
+            "<section>\r\n",
+            "  <pre><code>Hello&NewLine;    there,&NewLine;world!</code></pre>\r\n",
+            "</section>\r\n",
+        )
+    );
+}
+
+#[test]
+fn multiline_code_language() {
+    let src = r#"
+    section {
+        ```rust
+        fn main() {}
+        ```
+    }
+    "#;
+
+    let out = utils::convert_unwrap(src, None);
+
+    assert_eq!(
+        out,
+        r#"<section><pre><code class="language-rust">fn main() {}</code></pre></section>"#
+    );
+}
+
+#[test]
+fn multiline_code_container_tag() {
+    let src = "section {\n    ```\n    fn main() {}\n    ```\n}";
+
+    let out = utils::convert_unwrap(src, OutputConfig::new().emphasis_tag("i"));
+    assert_eq!(
+        out,
+        r#"<section><pre><code>fn main() {}</code></pre></section>"#
+    );
+
+    let out = utils::convert_unwrap(src, OutputConfig::new().code_block_container_tag("div"));
+    assert_eq!(
+        out,
+        r#"<section><div><code>fn main() {}</code></div></section>"#
+    );
+}
+
+#[test]
+fn multiline_code_no_language() {
+    let src = "section {\n    ```   \n    fn main() {}\n    ```\n}";
+
+    let out = utils::convert_unwrap(src, None);
+
+    assert_eq!(
+        out,
+        r#"<section><pre><code>fn main() {}</code></pre></section>"#
+    );
+}
+
+#[test]
+fn multiline_untrimmed() {
+    let src = "pre {\n    '''\n      /\\_/\\\n     ( o.o ) &\n    '''\n}";
+
+    let out = utils::convert_unwrap(src, None);
+    assert_eq!(out, r"<pre>  /\_/\&NewLine; ( o.o ) &amp;</pre>");
+
+    let out = utils::convert_unwrap(src, OutputConfig::new().trim_multiline(false));
+    assert_eq!(
+        out,
+        r"<pre>&NewLine;      /\_/\&NewLine;     ( o.o ) &amp;&NewLine;    </pre>"
+    );
+}
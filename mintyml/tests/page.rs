@@ -0,0 +1,400 @@
+use mintyml::{CharsetMetaStyle, LangScope, OutputConfig};
+use utils::convert_unwrap;
+
+mod utils;
+
+const SRC: &str = r#"
+title> Foo
+div> Hello
+"#;
+
+#[test]
+fn complete_page_xhtml_transitional_doctype() {
+    let doctype = concat!(
+        r#"<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Transitional//EN" "#,
+        r#""http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd">"#,
+    );
+
+    let out = convert_unwrap(
+        SRC,
+        OutputConfig::new()
+            .complete_page(true)
+            .xml(true)
+            .doctype(doctype),
+    );
+
+    assert_eq!(
+        out,
+        [
+            doctype,
+            "\n",
+            r#"<html><head><title>Foo</title></head> "#,
+            r#"<body><div>Hello</div></body></html>"#,
+        ]
+        .concat()
+    );
+}
+
+#[test]
+fn complete_page_xml_default_doctype() {
+    let out = convert_unwrap(SRC, OutputConfig::new().complete_page(true).xml(true));
+
+    assert_eq!(
+        out,
+        concat!(
+            r#"<html><head><title>Foo</title></head> "#,
+            r#"<body><div>Hello</div></body></html>"#,
+        )
+    );
+}
+
+#[test]
+fn complete_page_suppressed_doctype() {
+    let out = convert_unwrap(SRC, OutputConfig::new().complete_page(true).doctype(""));
+
+    assert_eq!(
+        out,
+        concat!(
+            r#"<html><head><title>Foo</title></head> "#,
+            r#"<body><div>Hello</div></body></html>"#,
+        )
+    );
+}
+
+#[test]
+fn complete_page_charset_meta_short() {
+    let out = convert_unwrap(
+        SRC,
+        OutputConfig::new()
+            .complete_page(true)
+            .charset_meta(CharsetMetaStyle::Short),
+    );
+
+    assert_eq!(
+        out,
+        concat!(
+            "<!DOCTYPE html>\n",
+            r#"<html><head><meta charset="utf-8"><title>Foo</title></head> "#,
+            r#"<body><div>Hello</div></body></html>"#,
+        )
+    );
+}
+
+#[test]
+fn complete_page_charset_meta_http_equiv() {
+    let out = convert_unwrap(
+        SRC,
+        OutputConfig::new()
+            .complete_page(true)
+            .charset_meta(CharsetMetaStyle::HttpEquiv),
+    );
+
+    assert_eq!(
+        out,
+        concat!(
+            "<!DOCTYPE html>\n",
+            r#"<html><head><meta http-equiv="Content-Type" content="text/html; charset=utf-8">"#,
+            r#"<title>Foo</title></head> "#,
+            r#"<body><div>Hello</div></body></html>"#,
+        )
+    );
+}
+
+#[test]
+fn complete_page_charset_meta_existing() {
+    let src = r#"
+meta[charset=latin1]>
+div> Hello
+"#;
+
+    let out = convert_unwrap(
+        src,
+        OutputConfig::new()
+            .complete_page(true)
+            .charset_meta(CharsetMetaStyle::Short),
+    );
+
+    assert_eq!(
+        out,
+        concat!(
+            "<!DOCTYPE html>\n",
+            r#"<html><head><meta charset="latin1"></head> "#,
+            r#"<body><div>Hello</div></body></html>"#,
+        )
+    );
+}
+
+#[test]
+fn complete_page_indent_html_children() {
+    let out = convert_unwrap(SRC, OutputConfig::new().complete_page(true).indent("  "));
+
+    assert_eq!(
+        out,
+        concat!(
+            "<!DOCTYPE html>\n",
+            "<html>\n",
+            "<head>\n  <title>Foo</title>\n</head>\n",
+            "<body>\n  <div>Hello</div>\n</body>\n",
+            "</html>\n",
+        )
+    );
+
+    let out = convert_unwrap(
+        SRC,
+        OutputConfig::new()
+            .complete_page(true)
+            .indent("  ")
+            .indent_html_children(true),
+    );
+
+    assert_eq!(
+        out,
+        concat!(
+            "<!DOCTYPE html>\n",
+            "<html>\n",
+            "  <head>\n    <title>Foo</title>\n  </head>\n",
+            "  <body>\n    <div>Hello</div>\n  </body>\n",
+            "</html>\n",
+        )
+    );
+}
+
+#[test]
+fn root_element_wrapper() {
+    let out = convert_unwrap(SRC, OutputConfig::new().root_element("main"));
+
+    assert_eq!(out, "<main><title>Foo</title> <div>Hello</div></main>");
+
+    let out = convert_unwrap(
+        SRC,
+        OutputConfig::new().root_element("main").complete_page(true),
+    );
+
+    assert!(!out.contains("<main>"));
+}
+
+#[test]
+fn auto_html_wrap() {
+    let src = "head {\n    title> Foo\n}\nbody {\n    Hello\n}";
+
+    let out = convert_unwrap(src, OutputConfig::new().auto_html_wrap(true));
+    assert_eq!(
+        out,
+        "<html><head><title>Foo</title></head> <body><p>Hello</p></body></html>"
+    );
+
+    let out = convert_unwrap(src, OutputConfig::new());
+    assert!(!out.contains("<html>"));
+
+    let out = convert_unwrap("body { Hello }", OutputConfig::new().auto_html_wrap(true));
+    assert!(!out.contains("<html>"));
+}
+
+#[test]
+fn complete_page_lang_root_only() {
+    let out = convert_unwrap(
+        SRC,
+        OutputConfig::new()
+            .complete_page(true)
+            .lang("en")
+            .lang_scope(LangScope::RootOnly),
+    );
+
+    assert_eq!(out.matches("lang=").count(), 1, "{out}");
+    assert!(out.contains(r#"<html lang="en">"#), "{out}");
+
+    let out = convert_unwrap(
+        "section> One\n\nsection> Two",
+        OutputConfig::new()
+            .lang("en")
+            .lang_scope(LangScope::RootOnly),
+    );
+    assert_eq!(
+        out,
+        r#"<div lang="en"><section>One</section> <section>Two</section></div>"#
+    );
+
+    for src in ["", "\n  \n"] {
+        let out = convert_unwrap(
+            src,
+            OutputConfig::new()
+                .lang("en")
+                .lang_scope(LangScope::RootOnly),
+        );
+        assert_eq!(out, "", "{src:?}");
+    }
+}
+
+#[test]
+fn complete_page_open_graph() {
+    let out = convert_unwrap(
+        SRC,
+        OutputConfig::new().complete_page(true).open_graph([
+            ("og:title", "Foo & \"Bar\""),
+            ("og:image", "https://example.com/a.png"),
+        ]),
+    );
+
+    assert_eq!(
+        out,
+        concat!(
+            "<!DOCTYPE html>\n",
+            r#"<html><head><title>Foo</title>"#,
+            r#"<meta property="og:title" content="Foo &amp; &quot;Bar&quot;">"#,
+            r#"<meta property="og:image" content="https://example.com/a.png"></head> "#,
+            r#"<body><div>Hello</div></body></html>"#,
+        )
+    );
+}
+
+/// A lightweight well-formedness check: tags are balanced, attribute values are quoted, and
+/// `<` and `&` only appear as markup or entities outside of CDATA sections.
+fn assert_well_formed_xml(xml: &str) {
+    let mut stack = Vec::new();
+    let mut rest = xml;
+
+    while let Some(i) = rest.find(['<', '&']) {
+        let text = &rest[i..];
+        if let Some(entity) = text.strip_prefix('&') {
+            let end = entity.find(';').expect("unterminated entity");
+            assert!(
+                entity[..end]
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '#'),
+                "bare `&` in {text:.40?}",
+            );
+            rest = entity;
+            continue;
+        }
+
+        let (close, is_tag) = match () {
+            _ if text.starts_with("<!--") => ("-->", false),
+            _ if text.starts_with("<![CDATA[") => ("]]>", false),
+            _ if text.starts_with("<!") || text.starts_with("<?") => (">", false),
+            _ => (">", true),
+        };
+        let len = text.find(close).expect("unterminated markup") + close.len();
+        let tag = &text[..len];
+        rest = &text[len..];
+
+        if !is_tag {
+            continue;
+        }
+
+        if let Some(name) = tag.strip_prefix("</") {
+            let name = name.trim_end_matches('>').trim();
+            assert_eq!(stack.pop(), Some(name), "mismatched closing tag {tag:?}");
+            continue;
+        }
+
+        let self_closing = tag.ends_with("/>");
+        let inner = tag[1..].trim_end_matches('>').trim_end_matches('/');
+        let (name, mut attrs) = inner.split_once(' ').unwrap_or((inner, ""));
+        while let Some(eq) = attrs.find('=') {
+            let value = &attrs[eq + 1..];
+            let quote = value.chars().next().expect("missing attribute value");
+            assert!(
+                quote == '"' || quote == '\'',
+                "unquoted attribute in {tag:?}"
+            );
+            let value_end = value[1..]
+                .find(quote)
+                .expect("unterminated attribute value");
+            attrs = &value[value_end + 2..];
+        }
+        assert!(
+            attrs.trim().is_empty(),
+            "attribute without a value in {tag:?}",
+        );
+
+        if !self_closing {
+            stack.push(name);
+        }
+    }
+
+    assert!(stack.is_empty(), "unclosed tags: {stack:?}");
+}
+
+#[test]
+fn complete_page_xhtml_strict() {
+    let src = include_str!("../../web-demo/public/examples/sample-page.mty");
+    let out = convert_unwrap(
+        src,
+        OutputConfig::new()
+            .complete_page(true)
+            .xhtml_strict(true)
+            .indent("  ")
+            .lang("en"),
+    );
+
+    assert!(out.starts_with(r#"<html xmlns="http://www.w3.org/1999/xhtml" lang="en">"#));
+    assert_well_formed_xml(&out);
+
+    assert_well_formed_xml(&convert_unwrap(
+        "script>'''\nif (a < b && c) {}\n'''\ninput[disabled]>\np> Tom & Jerry",
+        OutputConfig::new().xhtml_strict(true),
+    ));
+}
+
+#[test]
+fn complete_page_namespaces() {
+    let config = || {
+        OutputConfig::new()
+            .complete_page(true)
+            .namespaces([("svg", "http://www.w3.org/2000/svg"), ("", "urn:x")])
+    };
+
+    let out = convert_unwrap("svg:svg> svg:circle[r=4]>", config().xml(true));
+    assert_eq!(
+        out,
+        concat!(
+            r#"<html xmlns:svg="http://www.w3.org/2000/svg" xmlns="urn:x"><head/> "#,
+            r#"<body><svg:svg><svg:circle r="4"/></svg:svg></body></html>"#,
+        )
+    );
+    assert_well_formed_xml(&out);
+
+    let out = convert_unwrap("html[xmlns=urn:y]> body> Hi", config().xhtml_strict(true));
+    assert_eq!(
+        out,
+        r#"<html xmlns="urn:y" xmlns:svg="http://www.w3.org/2000/svg"><body>Hi</body></html>"#
+    );
+
+    let out = convert_unwrap(SRC, config());
+    assert!(!out.contains("xmlns"), "{out}");
+
+    let config = OutputConfig::new()
+        .xml(true)
+        .namespaces([("svg", "http://www.w3.org/2000/svg")]);
+
+    let out = convert_unwrap("svg:svg>\n\np> Hi", config.clone());
+    assert_eq!(out, "<svg:svg/> <p>Hi</p>");
+
+    let out = convert_unwrap("svg:svg>\n\np> Hi", config.root_element("main"));
+    assert_eq!(
+        out,
+        r#"<main xmlns:svg="http://www.w3.org/2000/svg"><svg:svg/> <p>Hi</p></main>"#
+    );
+}
+
+#[test]
+fn xhtml_preset() {
+    let src = "title> Foo\ninput[type=checkbox disabled]>\np> Tom & Jerry";
+
+    let out = convert_unwrap(src, OutputConfig::xhtml());
+    assert_eq!(
+        out,
+        concat!(
+            r#"<title>Foo</title> <input type="checkbox" disabled=""/> "#,
+            r#"<p>Tom &amp; Jerry</p>"#,
+        )
+    );
+    assert_well_formed_xml(&out);
+
+    let out = convert_unwrap(src, OutputConfig::xhtml().complete_page(true));
+    assert!(out.starts_with(concat!(
+        "<!DOCTYPE html>\n",
+        r#"<html xmlns="http://www.w3.org/1999/xhtml"><head><title>Foo</title></head> "#,
+    )));
+    assert_well_formed_xml(&out);
+}
@@ -54,3 +54,53 @@ head {
 "#
     );
 }
+
+#[test]
+fn xml_script_cdata() {
+    let src = r#"
+head {
+  script>'''
+  if (a < b && c) {
+    x = y[z[0]]>1;
+  }
+  '''
+  style>'''
+  p > a { color: red; }
+  '''
+}
+"#;
+
+    let actual = convert_unwrap(src, OutputConfig::new().xml(true));
+
+    assert_eq!(
+        actual,
+        concat!(
+            "<head><script>/* <![CDATA[ */if (a < b && c) {\n",
+            "  x = y[z[0]]]]><![CDATA[>1;\n}/* ]]> */</script> ",
+            "<style>/* <![CDATA[ */p > a { color: red; }/* ]]> */</style></head>",
+        )
+    );
+
+    let actual = convert_unwrap(src, OutputConfig::new());
+    assert!(!actual.contains("CDATA"), "{actual}");
+}
+
+#[test]
+fn explicit_raw() {
+    let src = "xmp!>'''\nif (a < b && c) {}\n'''";
+    let actual = convert_unwrap(src, OutputConfig::new());
+    assert_eq!(actual, "<xmp>if (a < b && c) {}</xmp>");
+
+    let actual = convert_unwrap(src.replace('!', ""), OutputConfig::new());
+    assert_eq!(actual, "<xmp>if (a &lt; b &amp;&amp; c) {}</xmp>");
+
+    let src = "section> template.x!>'''\n{{ a < b }}\n'''\n\nul!{\n  '''\n  a & b\n  '''\n}";
+    let actual = convert_unwrap(src, OutputConfig::new());
+    assert_eq!(
+        actual,
+        concat!(
+            r#"<section><template class="x">{{ a < b }}</template></section> "#,
+            "<ul><li>a & b</li></ul>",
+        ),
+    );
+}
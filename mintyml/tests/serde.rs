@@ -0,0 +1,44 @@
+#![cfg(feature = "serde")]
+
+use mintyml::{LineEnding, MetadataConfig, OutputConfig, SpecialKind};
+use utils::convert_unwrap;
+
+mod utils;
+
+#[test]
+fn config_round_trip() {
+    let config = OutputConfig::new()
+        .indent("  ")
+        .xml(true)
+        .strong_tag("b")
+        .disable_special(SpecialKind::Strike)
+        .line_ending(LineEnding::CrLf)
+        .metadata(MetadataConfig::new().ranges(false))
+        .variables([("base", "/docs")])
+        .on_diagnostic(|_| {});
+
+    let json = serde_json::to_string(&config).unwrap();
+    let parsed: OutputConfig = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    assert!(parsed.on_diagnostic.is_none());
+
+    let src = "section {\n  a[href=$base]> <#Docs#> <~old~>\n}\n";
+    assert_eq!(
+        convert_unwrap(src, parsed),
+        convert_unwrap(src, config.on_diagnostic(|_| {}))
+    );
+}
+
+#[test]
+fn config_from_partial_json() {
+    let config: OutputConfig = serde_json::from_str(
+        r#"{ "completePage": true, "specialTags": { "emphasis": { "tag": "i" } } }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        convert_unwrap("p> </Hi/>", config),
+        "<!DOCTYPE html>\n<html><head></head> <body><p><i>Hi</i></p></body></html>"
+    );
+}
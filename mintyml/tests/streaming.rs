@@ -0,0 +1,187 @@
+use mintyml::{ConvertCache, ConvertError, OutputConfig};
+
+const SRC: &str = r#"
+h1> Title
+
+section {
+    Hello, <#world#>!
+
+    > Line one
+    > Line two
+}
+
+'''
+A multiline string
+
+with a blank line
+'''
+
+img[src=pic.png]>
+
+Goodbye.
+"#;
+
+fn convert_streaming<'src>(
+    src: &'src str,
+    config: &OutputConfig<'src>,
+) -> Result<String, ConvertError<'src>> {
+    let mut out = String::new();
+    mintyml::convert_streaming(src, config, &mut out)?;
+    Ok(out)
+}
+
+#[test]
+fn streaming_matches_convert() {
+    let config = OutputConfig::new();
+    assert_eq!(
+        convert_streaming(SRC, &config).unwrap(),
+        mintyml::convert(SRC, &config).unwrap(),
+    );
+}
+
+#[test]
+fn streaming_matches_convert_pretty() {
+    let config = OutputConfig::new().indent("  ");
+    assert_eq!(
+        convert_streaming(SRC, &config).unwrap(),
+        mintyml::convert(SRC, &config).unwrap(),
+    );
+}
+
+#[test]
+fn streaming_error_location() {
+    let src = "p> One\n\np> Two\n\ndiv {\n    p> Three\n";
+
+    let Err(ConvertError::Syntax { syntax_errors, .. }) =
+        convert_streaming(src, &OutputConfig::new())
+    else {
+        panic!("expected a syntax error");
+    };
+    let Err(ConvertError::Syntax {
+        syntax_errors: expected,
+        ..
+    }) = mintyml::convert(src, OutputConfig::new())
+    else {
+        panic!("expected a syntax error");
+    };
+
+    assert_eq!(syntax_errors, expected);
+}
+
+#[test]
+fn cache_matches_convert() {
+    let edited = SRC.replace("Hello", "Goodbye");
+
+    for config in [OutputConfig::new(), OutputConfig::new().complete_page(true)] {
+        let mut cache = ConvertCache::new();
+        for src in [SRC, &edited, &edited, SRC] {
+            assert_eq!(
+                mintyml::convert_with_cache(src, &config, &mut cache).unwrap(),
+                mintyml::convert(src, &config).unwrap(),
+            );
+        }
+    }
+}
+
+#[test]
+fn cache_error_location() {
+    let mut cache = ConvertCache::new();
+    let config = OutputConfig::new();
+    mintyml::convert_with_cache("p> One\n\np> Two", &config, &mut cache).unwrap();
+
+    let src = "p> One\n\np> Two\n\ndiv {\n    p> Three\n";
+    let Err(ConvertError::Syntax { syntax_errors, .. }) =
+        mintyml::convert_with_cache(src, &config, &mut cache)
+    else {
+        panic!("expected a syntax error");
+    };
+    let Err(ConvertError::Syntax {
+        syntax_errors: expected,
+        ..
+    }) = mintyml::convert(src, &config)
+    else {
+        panic!("expected a syntax error");
+    };
+
+    assert_eq!(syntax_errors, expected);
+}
+
+#[test]
+fn duplicate_ids_across_blocks() {
+    let src = "p#foo> One\n\np#foo> Two";
+    let config = OutputConfig::new().check_duplicate_ids(true);
+
+    assert!(matches!(
+        mintyml::convert(src, &config),
+        Err(ConvertError::Semantic { .. })
+    ));
+    assert!(matches!(
+        convert_streaming(src, &config),
+        Err(ConvertError::Semantic { .. })
+    ));
+    assert!(matches!(
+        mintyml::convert_with_cache(src, &config, &mut ConvertCache::new()),
+        Err(ConvertError::Semantic { .. })
+    ));
+}
+
+const BLOCKS_SRC: &str = "p> A\n\n<! comment !>\n\np> B\n\nul {\n    li> One\n}\n\np> C";
+
+/// Configs whose output changes if the document is converted one block at a time.
+fn whole_document_configs() -> [OutputConfig<'static>; 3] {
+    [
+        OutputConfig::new()
+            .xml(true)
+            .namespaces([("svg", "http://www.w3.org/2000/svg")]),
+        OutputConfig::new().omit_optional_end_tags(true),
+        OutputConfig::new().emit_comments(false),
+    ]
+}
+
+#[test]
+fn streaming_matches_convert_whole_document_options() {
+    for config in whole_document_configs() {
+        assert_eq!(
+            convert_streaming(BLOCKS_SRC, &config).unwrap(),
+            mintyml::convert(BLOCKS_SRC, &config).unwrap(),
+        );
+    }
+}
+
+#[test]
+fn streaming_reports_errors_from_every_block() {
+    let src = "a[href=$one]> One\n\np> Two\n\na[href=$three]> Three";
+    let config = OutputConfig::new().variables([("two", "2")]);
+
+    let Err(ConvertError::Semantic {
+        semantic_errors: expected,
+        ..
+    }) = mintyml::convert(src, &config)
+    else {
+        panic!("expected a semantic error");
+    };
+    assert_eq!(expected.len(), 2);
+
+    let Err(ConvertError::Semantic {
+        semantic_errors, ..
+    }) = convert_streaming(src, &config)
+    else {
+        panic!("expected a semantic error");
+    };
+    assert_eq!(semantic_errors, expected);
+}
+
+#[test]
+fn cache_matches_convert_whole_document_options() {
+    let edited = BLOCKS_SRC.replace("p> B", "p> b");
+
+    for config in whole_document_configs() {
+        let mut cache = ConvertCache::new();
+        for src in [BLOCKS_SRC, &edited, &edited, BLOCKS_SRC] {
+            assert_eq!(
+                mintyml::convert_with_cache(src, &config, &mut cache).unwrap(),
+                mintyml::convert(src, &config).unwrap(),
+            );
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use mintyml::OutputConfig;
+
 use crate::utils::convert_unwrap;
 
 mod utils;
@@ -89,3 +91,91 @@ fn interpolation_curly_double_follows_single_word() {
         )
     )
 }
+
+#[test]
+fn convert_with_text_demo() {
+    let src = r#"
+    {
+        Hello there,
+        world!
+        img[src="./pic.png"]>
+
+        > Click <(a.example-link[href=www.example.com]> here )>
+        for more.
+        .empty>
+        .foo#bar.baz> Goodbye \<3
+    }
+    "#;
+
+    let (html, text) = mintyml::convert_with_text(src, OutputConfig::new()).unwrap();
+
+    assert_eq!(html, convert_unwrap(src, None));
+    assert!(text.contains("Hello there, world!"));
+    assert_eq!(
+        text,
+        "Hello there, world!\nClick here for more.\nGoodbye <3"
+    );
+}
+
+#[test]
+fn opaque_spans() {
+    let src = r#"
+        section {
+            p> Hello {{ a > b }} and ${ </c/> & d } and @@ e < f @@
+        }
+    "#;
+
+    let out = convert_unwrap(
+        src,
+        OutputConfig::new().opaque_spans([("${", "}"), ("@@", "@@")]),
+    );
+
+    assert_eq!(
+        out,
+        concat!(
+            r#"<section>"#,
+            r#"<p>Hello {{ a > b }} and ${ </c/> & d } and @@ e < f @@</p>"#,
+            r#"</section>"#,
+        )
+    )
+}
+
+#[test]
+fn short_opaque_spans() {
+    let config = || OutputConfig::new().opaque_spans([("$", "$"), ("@", "@")]);
+
+    assert_eq!(
+        convert_unwrap("p> a $<$ b @{@ c $$ & d", config()),
+        "<p>a $<$ b @{@ c $$ &amp; d</p>",
+    );
+    assert_eq!(convert_unwrap("$>$", config()), "<p>$>$</p>");
+}
+
+#[test]
+fn unclosed_opaque_span() {
+    let config = OutputConfig::new().opaque_spans([("~~", "~~"), ("@@", "@@")]);
+
+    assert_eq!(
+        convert_unwrap("p> a ~~ b and @@ c < d @@", config),
+        "<p>a ~~ b and @@ c < d @@</p>",
+    );
+}
+
+#[test]
+fn paragraph_break_marker() {
+    let src = "section {\n    One\n    \\\n    Two\n    p> Three\n\\\n    Four\n}";
+
+    let out = convert_unwrap(src, None);
+
+    assert_eq!(
+        out,
+        concat!(
+            r#"<section>"#,
+            r#"<p>One</p> <p>Two</p> <p>Three</p> <p>Four</p>"#,
+            r#"</section>"#,
+        )
+    );
+
+    let out = convert_unwrap("pre>'''\nOne\n\\\nTwo\n'''", None);
+    assert_eq!(out, "<pre>One&NewLine;\\&NewLine;Two</pre>");
+}
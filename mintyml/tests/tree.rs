@@ -0,0 +1,124 @@
+use mintyml::{
+    tree::{self, Node},
+    OutputConfig,
+};
+
+const SRC: &str = "section {\n    p> Hello world\n    div> Bye\n}\n";
+
+fn text_nodes<'a>(nodes: &'a [Node<'a>], out: &mut Vec<(usize, usize, &'a str)>) {
+    for node in nodes {
+        match node {
+            Node::Element { content, .. } => text_nodes(content, out),
+            Node::Text { range, text, .. } => out.push((range.start, range.end, text)),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn apply_edit_within_text() {
+    let after = SRC.replace("Hello world", "Hello there world");
+    let mut document = tree::parse(SRC).unwrap();
+
+    document.apply_edit(22..22, " there", &after).unwrap();
+
+    let expected = tree::parse(&after).unwrap();
+    assert_eq!(format!("{document:?}"), format!("{expected:?}"));
+
+    let mut texts = Vec::new();
+    text_nodes(&document.nodes, &mut texts);
+    assert_eq!(texts, [(17, 34, "Hello there world"), (44, 47, "Bye")]);
+}
+
+#[test]
+fn apply_edit_at_text_boundaries() {
+    for (src, at) in [
+        ("p> Hello", 3),
+        ("p> Hello", 8),
+        ("Hello", 0),
+        ("p> Hello <(b> world)>", 3),
+        ("p> <(b> Hello)> world", 8),
+        ("p> <(b> Hello)> world", 13),
+        ("p> <(b> Hello)> world", 16),
+    ] {
+        let after = format!("{}X{}", &src[..at], &src[at..]);
+        let mut document = tree::parse(src).unwrap();
+
+        document.apply_edit(at..at, "X", &after).expect(&after);
+
+        let expected = tree::parse(&after).unwrap();
+        assert_eq!(
+            format!("{document:?}"),
+            format!("{expected:?}"),
+            "{after:?}"
+        );
+    }
+}
+
+#[test]
+fn apply_edit_brace_needs_reparse() {
+    let after = SRC.replace("Hello world", "Hello { world");
+    let mut document = tree::parse(SRC).unwrap();
+    let before = format!("{document:?}");
+
+    assert!(document.apply_edit(22..22, " {", &after).is_err());
+    assert_eq!(format!("{document:?}"), before);
+}
+
+#[test]
+fn content_range() {
+    let src = "div { hello }\nimg>";
+    let document = tree::parse(src).unwrap();
+    let ranges = Vec::from_iter(document.nodes.iter().filter_map(|node| match node {
+        Node::Element {
+            range,
+            content_range,
+            ..
+        } => Some((
+            &src[range.start..range.end],
+            &src[content_range.start..content_range.end],
+        )),
+        _ => None,
+    }));
+
+    assert_eq!(ranges, [("div { hello }", "hello "), ("img>", "")]);
+}
+
+fn resolved_tags<'a>(nodes: &'a [Node<'a>], out: &mut Vec<Option<&'a str>>) {
+    for node in nodes.iter().filter(|n| matches!(n, Node::Element { .. })) {
+        out.push(node.resolved_tag());
+        resolved_tags(node.content(), out);
+    }
+}
+
+#[test]
+fn resolved_tag() {
+    let src = "ul {\n    foo\n}\n\nsection {\n    {\n        Hello\n    }\n}\n";
+    let document = tree::transform(src, OutputConfig::new()).unwrap();
+
+    let mut tags = Vec::new();
+    resolved_tags(&document.nodes, &mut tags);
+    assert_eq!(tags, ["ul", "li", "section", "div", "p"].map(Some));
+
+    let document = tree::parse("{ foo }").unwrap();
+    assert_eq!(document.nodes[0].resolved_tag(), None);
+}
+
+#[test]
+fn inferred_tags() {
+    let src = "ul {\n    > One\n    li> Two\n}\n\nsection {\n    {\n        Hello\n    }\n}\n";
+    let tags = tree::inferred_tags(src, OutputConfig::new()).unwrap();
+
+    let tags = Vec::from_iter(
+        tags.iter()
+            .map(|t| (&src[t.range.start..t.range.end], &*t.tag)),
+    );
+    assert_eq!(
+        tags,
+        [
+            ("> One", "li"),
+            ("{\n        Hello\n    }", "div"),
+            ("Hello", "p"),
+        ]
+    );
+}
@@ -0,0 +1,108 @@
+use mintyml::OutputConfig;
+use utils::convert_unwrap;
+
+mod utils;
+
+fn assert_max_width(out: &str, width: usize) {
+    for line in out.lines() {
+        assert!(
+            line.chars().count() <= width || !line.trim().contains(' '),
+            "line exceeds {width} columns: {line:?}",
+        );
+    }
+}
+
+#[test]
+fn wrap_long_paragraph() {
+    let src = r#"
+section {
+    Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor
+    incididunt ut labore et dolore magna aliqua. <(a[href=www.example.com]> Ut enim )> ad minim
+    veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat.
+}
+"#;
+
+    let out = convert_unwrap(src, OutputConfig::new().indent("  ").max_width(40));
+
+    assert_max_width(&out, 40);
+    assert_eq!(
+        out,
+        concat!(
+            "<section>\n",
+            "  <p>Lorem ipsum dolor sit amet,\n",
+            "    consectetur adipiscing elit, sed do\n",
+            "    eiusmod tempor incididunt ut labore\n",
+            "    et dolore magna aliqua. <a\n",
+            "    href=\"www.example.com\">Ut enim</a>\n",
+            "    ad minim veniam, quis nostrud\n",
+            "    exercitation ullamco laboris nisi ut\n",
+            "    aliquip ex ea commodo consequat.</p>\n",
+            "</section>\n",
+        )
+    );
+}
+
+#[test]
+fn wrap_long_word() {
+    let src = "p> a supercalifragilisticexpialidocious word";
+
+    let out = convert_unwrap(src, OutputConfig::new().indent("  ").max_width(10));
+
+    assert_max_width(&out, 10);
+    assert_eq!(
+        out,
+        "<p>a\n  supercalifragilisticexpialidocious\n  word</p>\n"
+    );
+}
+
+#[test]
+fn wrap_requires_indent() {
+    let src = "p> Lorem ipsum dolor sit amet, consectetur adipiscing elit";
+
+    let out = convert_unwrap(src, OutputConfig::new().max_width(20));
+
+    assert_eq!(
+        out,
+        "<p>Lorem ipsum dolor sit amet, consectetur adipiscing elit</p>"
+    );
+}
+
+#[test]
+fn wrap_skips_preformatted() {
+    let src = r#"
+pre> Lorem ipsum dolor sit amet, consectetur adipiscing elit
+"#;
+
+    let out = convert_unwrap(src, OutputConfig::new().indent("  ").max_width(20));
+
+    assert_eq!(
+        out,
+        "<pre>Lorem ipsum dolor sit amet, consectetur adipiscing elit</pre>\n"
+    );
+}
+
+#[test]
+fn wrap_attribute_list() {
+    let src = r#"
+section {
+    p> Some words here <(a[href=https://example.com/a/long/path][title="A title"][rel=noopener]> linked café text )> after the link
+}
+"#;
+
+    let out = convert_unwrap(src, OutputConfig::new().indent("  ").max_width(30));
+
+    assert_max_width(&out, 30);
+    assert_eq!(
+        out,
+        concat!(
+            "<section>\n",
+            "  <p>Some words here <a\n",
+            "    href=\"https://example.com/a/long/path\"\n",
+            "    title=\"A title\"\n",
+            "    rel=\"noopener\">linked café\n",
+            "    text</a> after the\n",
+            "    link</p>\n",
+            "</section>\n",
+        )
+    );
+}